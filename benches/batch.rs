@@ -0,0 +1,128 @@
+//! Batch equations compared to calling the scalar equations in a loop
+//!
+//! Run with `cargo bench --bench batch`, optionally with `RUSTFLAGS="-C target-cpu=native"`.
+//!
+//! Criterion doesn't support wasm32, so the benchmarks are empty there.
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::hint::black_box;
+
+use cffdrs::{
+    batch::{fwi_batch, isi_batch, ros_batch},
+    fbp_system::{ros::rate_of_spread, FbpFuelType},
+    fwi_system::{fire_weather_index, initial_spread_index},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const CELLS: usize = 100_000;
+
+/// Deterministic values spread over `min..max`
+fn values(seed: u64, min: f64, max: f64) -> Vec<f64> {
+    let mut state = seed;
+
+    (0..CELLS)
+        .map(|_| {
+            // Linear congruential generator (Knuth's MMIX constants)
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            min + (max - min) * ((state >> 11) as f64 / (1u64 << 53) as f64)
+        })
+        .collect()
+}
+
+fn bench_isi(c: &mut Criterion) {
+    let ffmc = values(1, 60., 96.);
+    let ws = values(2, 0., 50.);
+    let mut isi = vec![0.; CELLS];
+
+    let mut group = c.benchmark_group("isi");
+    group.throughput(Throughput::Elements(CELLS as u64));
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            for ((isi, &ffmc), &ws) in isi.iter_mut().zip(&ffmc).zip(&ws) {
+                *isi = initial_spread_index(black_box(ffmc), black_box(ws));
+            }
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| isi_batch(black_box(&ffmc), black_box(&ws), &mut isi))
+    });
+    group.finish();
+}
+
+fn bench_fwi(c: &mut Criterion) {
+    let isi = values(3, 0., 30.);
+    let bui = values(4, 0., 200.);
+    let mut fwi = vec![0.; CELLS];
+
+    let mut group = c.benchmark_group("fwi");
+    group.throughput(Throughput::Elements(CELLS as u64));
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            for ((fwi, &isi), &bui) in fwi.iter_mut().zip(&isi).zip(&bui) {
+                *fwi = fire_weather_index(black_box(isi), black_box(bui));
+            }
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| fwi_batch(black_box(&isi), black_box(&bui), &mut fwi))
+    });
+    group.finish();
+}
+
+fn bench_ros(c: &mut Criterion) {
+    let isi = values(5, 0., 30.);
+    let bui = values(6, 0., 200.);
+    let fmc = values(7, 85., 120.);
+    let sfc = values(8, 0.5, 5.);
+    let pc = values(9, 0., 100.);
+    let pdf = values(10, 0., 100.);
+    let cc = values(11, 0., 100.);
+    let cbh = values(12, 2., 12.);
+    let mut ros = vec![0.; CELLS];
+
+    let mut group = c.benchmark_group("ros");
+    group.throughput(Throughput::Elements(CELLS as u64));
+    for fuel_type in [FbpFuelType::C2, FbpFuelType::C6, FbpFuelType::M1] {
+        group.bench_function(BenchmarkId::new("scalar", fuel_type), |b| {
+            b.iter(|| {
+                for (i, ros) in ros.iter_mut().enumerate() {
+                    *ros = rate_of_spread(
+                        fuel_type,
+                        black_box(isi[i]),
+                        black_box(bui[i]),
+                        fmc[i],
+                        sfc[i],
+                        pc[i],
+                        pdf[i],
+                        cc[i],
+                        cbh[i],
+                    );
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("batch", fuel_type), |b| {
+            b.iter(|| {
+                ros_batch(
+                    fuel_type,
+                    black_box(&isi),
+                    black_box(&bui),
+                    &fmc,
+                    &sfc,
+                    &pc,
+                    &pdf,
+                    &cc,
+                    &cbh,
+                    &mut ros,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_isi, bench_fwi, bench_ros);
+criterion_main!(benches);
@@ -0,0 +1,223 @@
+/* Canadian Forest Fire Danger Rating System. Generated by cbindgen, do not edit */
+
+#ifndef CFFDRS_H
+#define CFFDRS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of an FFI call
+typedef enum CffdrsStatus {
+  CFFDRS_STATUS_OK = 0,
+  // A required pointer was null
+  CFFDRS_STATUS_NULL_POINTER = 1,
+  // The fuel type isn't a valid [CffdrsFuelType]
+  CFFDRS_STATUS_INVALID_FUEL_TYPE = 2,
+  // The weather timestamp is out of range
+  CFFDRS_STATUS_INVALID_TIME = 3,
+} CffdrsStatus;
+
+// FBP fuel types, in the same order as [FbpFuelType]
+typedef enum CffdrsFuelType {
+  CFFDRS_FUEL_TYPE_C1 = 0,
+  CFFDRS_FUEL_TYPE_C2,
+  CFFDRS_FUEL_TYPE_C3,
+  CFFDRS_FUEL_TYPE_C4,
+  CFFDRS_FUEL_TYPE_C5,
+  CFFDRS_FUEL_TYPE_C6,
+  CFFDRS_FUEL_TYPE_C7,
+  CFFDRS_FUEL_TYPE_M1,
+  CFFDRS_FUEL_TYPE_M2,
+  CFFDRS_FUEL_TYPE_M3,
+  CFFDRS_FUEL_TYPE_M4,
+  CFFDRS_FUEL_TYPE_D1,
+  CFFDRS_FUEL_TYPE_D2,
+  CFFDRS_FUEL_TYPE_S1,
+  CFFDRS_FUEL_TYPE_S2,
+  CFFDRS_FUEL_TYPE_S3,
+  CFFDRS_FUEL_TYPE_O1A,
+  CFFDRS_FUEL_TYPE_O1B,
+  CFFDRS_FUEL_TYPE_NON_FUEL,
+} CffdrsFuelType;
+
+// C mirror of [FbpOptions]. Use [cffdrs_fbp_options_default()] for the defaults
+typedef struct CffdrsFbpOptions {
+  // Elevation (m), or NaN if unknown
+  double elevation;
+  // Julian day of minimum foliar moisture content, or 0 to calculate it from the location
+  int32_t date_of_minimum_fmc;
+  // Slope (%)
+  double slope;
+  // Slope azimuth (degrees)
+  double aspect;
+  // Degree of curing for grass fuel types (%)
+  double curing;
+  // Grass fuel load (kg/m^2)
+  double grass_fuel_load;
+  // Percent conifer (%)
+  double percent_conifer;
+  // Percent dead balsam fir (%)
+  double percent_dead_balsam;
+  // Julian day of green-up, or 0 to not switch fuel types seasonally
+  int32_t green_up;
+  // Julian day of leaf fall, or 0 to not switch fuel types seasonally
+  int32_t leaf_fall;
+  // Crown base height (m), or 0 to use the fuel type's default
+  double crown_base_height;
+} CffdrsFbpOptions;
+
+// C mirror of [Weather]
+typedef struct CffdrsWeather {
+  // UTC time the weather was sampled (seconds since the Unix epoch)
+  int64_t timestamp;
+  // Latitude (degrees)
+  double lat;
+  // Longitude (degrees)
+  double long_;
+  // Temperature (Celcius)
+  double temp;
+  // Relative humidity (%)
+  double rh;
+  // Wind speed (at 10m height, km/h)
+  double ws;
+  // Wind direction (degrees, 0 = wind from north, 90 = from east, etc.)
+  double wd;
+  // Precipitation (mm)
+  double precip;
+} CffdrsWeather;
+
+// C mirror of [StartingFwiValues]
+typedef struct CffdrsStartingFwiValues {
+  double dc;
+  double dmc;
+  double ffmc;
+} CffdrsStartingFwiValues;
+
+// C mirror of [HourlyFwiValues]
+typedef struct CffdrsHourlyFwiValues {
+  double isi;
+  double dc;
+  double dmc;
+  double ffmc;
+  double bui;
+  double fwi;
+} CffdrsHourlyFwiValues;
+
+// C mirror of [FbpValues]
+typedef struct CffdrsFbpValues {
+  // Crown fraction burned (0-1)
+  double cfb;
+  // Direction of spread (radians)
+  double raz;
+  // Head rate of spread (m/min)
+  double ros;
+  // Flank rate of spread (m/min)
+  double fros;
+  // Back rate of spread  (m/min)
+  double bros;
+  // Foliar moisture content
+  double fmc;
+  // Surface fuel consumption (kg/m^2)
+  double sfc;
+  // Crown fuel consumption (kg/m^2)
+  double cfc;
+  // Total fuel consumption (kg/m^2)
+  double tfc;
+  // Crown fuel load
+  double cfl;
+  // Crown base height (m)
+  double cbh;
+  // Effective wind speed (km/h)
+  double wsv;
+  // Effective wind direction (radians)
+  double wsz;
+  // Head fire intensity (kW/m)
+  double fi;
+} CffdrsFbpValues;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Hourly Fine Fuel Moisture Code. See
+// [hourly_fine_fuel_moisture_code()][crate::fwi_system::hourly_fine_fuel_moisture_code()]
+double cffdrs_hourly_fine_fuel_moisture_code(double temp,
+                                             double rh,
+                                             double ws,
+                                             double precip,
+                                             double previous_ffmc,
+                                             double timestep);
+
+// Duff Moisture Code. See [duff_moisture_code()][crate::fwi_system::duff_moisture_code()]
+double cffdrs_duff_moisture_code(double prev_dmc,
+                                 double temp,
+                                 double rh,
+                                 double precip,
+                                 double lat,
+                                 int32_t mon,
+                                 bool lat_adjustment);
+
+// Drought Code. See [drought_code()][crate::fwi_system::drought_code()]
+double cffdrs_drought_code(double prev_dc,
+                           double temp,
+                           double precip,
+                           double lat,
+                           int32_t mon,
+                           bool lat_adjustment);
+
+// Initial Spread Index. See [initial_spread_index()][crate::fwi_system::initial_spread_index()]
+double cffdrs_initial_spread_index(double ffmc, double ws);
+
+// Buildup Index. See [buildup_index()][crate::fwi_system::buildup_index()]
+double cffdrs_buildup_index(double dmc, double dc);
+
+// Fire Weather Index. See [fire_weather_index()][crate::fwi_system::fire_weather_index()]
+double cffdrs_fire_weather_index(double isi, double bui);
+
+// Default FBP options
+struct CffdrsFbpOptions cffdrs_fbp_options_default(void);
+
+// Parse a fuel type code such as `"C-2"` or `"o1a"` (see [FbpFuelType]'s `FromStr`)
+//
+// # Safety
+//
+// `code` must be a valid null-terminated string, and `out` must be valid for writes
+enum CffdrsStatus cffdrs_parse_fuel_type(const char *code, enum CffdrsFuelType *out);
+
+// Calculate all values for the FWI system from hourly weather. See
+// [calculate_hourly()][crate::fwi_system::calculate_hourly()]
+//
+// * `previous` - FWI values from the previous hour. May be null
+//
+// # Safety
+//
+// `weather` must be valid for reads, `previous` must be null or valid for reads, and `out` must
+// be valid for writes
+enum CffdrsStatus cffdrs_calculate_hourly(const struct CffdrsWeather *weather,
+                                          const struct CffdrsStartingFwiValues *previous,
+                                          struct CffdrsHourlyFwiValues *out);
+
+// Calculate all FBP values from weather and FWI values. See
+// [calculate_fbp()][crate::fbp_system::calculate_fbp()]
+//
+// * `fuel_type` - A [CffdrsFuelType]
+// * `options` - FBP options. May be null to use the defaults
+//
+// # Safety
+//
+// `fwi` and `weather` must be valid for reads, `options` must be null or valid for reads, and
+// `out` must be valid for writes
+enum CffdrsStatus cffdrs_calculate_fbp(int32_t fuel_type,
+                                       const struct CffdrsHourlyFwiValues *fwi,
+                                       const struct CffdrsWeather *weather,
+                                       const struct CffdrsFbpOptions *options,
+                                       struct CffdrsFbpValues *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CFFDRS_H */
@@ -0,0 +1,201 @@
+//! Individual FWI and FBP equations, vectorized over NumPy arrays
+//!
+//! Every numeric argument can be a float or an array. Arrays are broadcast against each other
+//! following NumPy's rules, and the result is a float if every argument was a float.
+
+use cffdrs::{fbp_system, fwi_system};
+use numpy::{
+    ndarray::{ArrayD, ArrayViewD, Dimension, IxDyn},
+    AllowTypeChange, IntoPyArray, PyArrayLikeDyn,
+};
+use pyo3::{exceptions::PyValueError, prelude::*, IntoPyObjectExt};
+
+use crate::fuel_type::PyFbpFuelType;
+
+type ArrayLike<'py> = PyArrayLikeDyn<'py, f64, AllowTypeChange>;
+
+/// Apply `f` element-wise to broadcast arrays
+fn vectorize<'py, const N: usize>(
+    py: Python<'py>,
+    arrays: [&ArrayLike<'py>; N],
+    f: impl Fn([f64; N]) -> f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    let views: [ArrayViewD<'_, f64>; N] = arrays.map(|array| array.as_array());
+
+    let ndim = views.iter().map(|view| view.ndim()).max().unwrap_or(0);
+    let mut shape = vec![1; ndim];
+    for view in &views {
+        for (axis, &len) in view.shape().iter().rev().enumerate() {
+            let size = &mut shape[ndim - 1 - axis];
+            if *size == 1 {
+                *size = len;
+            } else if len != 1 && len != *size {
+                return Err(PyValueError::new_err(format!(
+                    "arrays could not be broadcast together with shapes {:?}",
+                    views.iter().map(|view| view.shape()).collect::<Vec<_>>()
+                )));
+            }
+        }
+    }
+
+    let broadcast = views.each_ref().map(|view| {
+        view.broadcast(IxDyn(&shape))
+            .expect("shape is compatible with every array")
+    });
+    let output = ArrayD::from_shape_fn(IxDyn(&shape), |index| {
+        f(std::array::from_fn(|i| broadcast[i][index.slice()]))
+    });
+
+    if ndim == 0 {
+        output[IxDyn(&[])].into_bound_py_any(py)
+    } else {
+        Ok(output.into_pyarray(py).into_any())
+    }
+}
+
+/// Initial spread index from FFMC and wind speed (km/h)
+#[pyfunction]
+pub fn initial_spread_index<'py>(
+    py: Python<'py>,
+    ffmc: ArrayLike<'py>,
+    ws: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    vectorize(py, [&ffmc, &ws], |[ffmc, ws]| {
+        fwi_system::initial_spread_index(ffmc, ws)
+    })
+}
+
+/// Buildup index from DMC and DC
+#[pyfunction]
+pub fn buildup_index<'py>(
+    py: Python<'py>,
+    dmc: ArrayLike<'py>,
+    dc: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    vectorize(py, [&dmc, &dc], |[dmc, dc]| {
+        fwi_system::buildup_index(dmc, dc)
+    })
+}
+
+/// Fire weather index from ISI and BUI
+#[pyfunction]
+pub fn fire_weather_index<'py>(
+    py: Python<'py>,
+    isi: ArrayLike<'py>,
+    bui: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    vectorize(py, [&isi, &bui], |[isi, bui]| {
+        fwi_system::fire_weather_index(isi, bui)
+    })
+}
+
+/// Hourly FFMC from weather and the previous hour's FFMC
+#[pyfunction]
+#[pyo3(signature = (temp, rh, ws, precip, previous_ffmc, timestep = 1.))]
+pub fn hourly_fine_fuel_moisture_code<'py>(
+    py: Python<'py>,
+    temp: ArrayLike<'py>,
+    rh: ArrayLike<'py>,
+    ws: ArrayLike<'py>,
+    precip: ArrayLike<'py>,
+    previous_ffmc: ArrayLike<'py>,
+    timestep: f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    vectorize(
+        py,
+        [&temp, &rh, &ws, &precip, &previous_ffmc],
+        |[temp, rh, ws, precip, previous_ffmc]| {
+            fwi_system::hourly_fine_fuel_moisture_code(
+                temp,
+                rh,
+                ws,
+                precip,
+                previous_ffmc,
+                Some(timestep),
+            )
+        },
+    )
+}
+
+/// Head fire rate of spread (m/min)
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+pub fn rate_of_spread<'py>(
+    py: Python<'py>,
+    fuel_type: PyFbpFuelType,
+    isi: ArrayLike<'py>,
+    bui: ArrayLike<'py>,
+    fmc: ArrayLike<'py>,
+    sfc: ArrayLike<'py>,
+    pc: ArrayLike<'py>,
+    pdf: ArrayLike<'py>,
+    cc: ArrayLike<'py>,
+    cbh: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let fuel_type = fuel_type.into();
+
+    vectorize(
+        py,
+        [&isi, &bui, &fmc, &sfc, &pc, &pdf, &cc, &cbh],
+        |[isi, bui, fmc, sfc, pc, pdf, cc, cbh]| {
+            fbp_system::ros::rate_of_spread(fuel_type, isi, bui, fmc, sfc, pc, pdf, cc, cbh)
+        },
+    )
+}
+
+/// Surface fuel consumption (kg/m^2)
+#[pyfunction]
+pub fn surface_fuel_consumption<'py>(
+    py: Python<'py>,
+    fuel_type: PyFbpFuelType,
+    ffmc: ArrayLike<'py>,
+    bui: ArrayLike<'py>,
+    pc: ArrayLike<'py>,
+    gfl: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let fuel_type = fuel_type.into();
+
+    vectorize(py, [&ffmc, &bui, &pc, &gfl], |[ffmc, bui, pc, gfl]| {
+        fbp_system::surface_fuel_consumption(fuel_type, ffmc, bui, pc, gfl)
+    })
+}
+
+/// Buildup effect on rate of spread
+#[pyfunction]
+pub fn buildup_effect<'py>(
+    py: Python<'py>,
+    fuel_type: PyFbpFuelType,
+    bui: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let fuel_type = fuel_type.into();
+
+    vectorize(py, [&bui], |[bui]| {
+        fbp_system::buildup_effect(fuel_type, bui)
+    })
+}
+
+/// Length to breadth ratio of the fire ellipse
+#[pyfunction]
+pub fn length_to_breadth<'py>(
+    py: Python<'py>,
+    fuel_type: PyFbpFuelType,
+    wsv: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let fuel_type = fuel_type.into();
+
+    vectorize(py, [&wsv], |[wsv]| {
+        fbp_system::length_to_breadth(fuel_type, wsv)
+    })
+}
+
+/// Fire intensity (kW/m) from fuel consumption (kg/m^2) and rate of spread (m/min)
+#[pyfunction]
+pub fn fire_intensity<'py>(
+    py: Python<'py>,
+    fc: ArrayLike<'py>,
+    ros: ArrayLike<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    vectorize(py, [&fc, &ros], |[fc, ros]| {
+        fbp_system::fire_intensity(fc, ros)
+    })
+}
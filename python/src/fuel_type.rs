@@ -0,0 +1,102 @@
+//! FBP fuel types as a Python enum
+
+use cffdrs::fbp_system::FbpFuelType;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Fuel types for the FBP system
+#[pyclass(name = "FbpFuelType", eq, eq_int, frozen, hash, module = "cffdrs")]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum PyFbpFuelType {
+    C1,
+    C2,
+    C3,
+    C4,
+    C5,
+    C6,
+    C7,
+    M1,
+    M2,
+    M3,
+    M4,
+    D1,
+    D2,
+    S1,
+    S2,
+    S3,
+    O1a,
+    O1b,
+    NonFuel,
+}
+
+#[pymethods]
+impl PyFbpFuelType {
+    /// Parse a fuel type code, e.g. "C-2", "c2" or "O-1a"
+    #[staticmethod]
+    fn parse(code: &str) -> PyResult<Self> {
+        code.parse::<FbpFuelType>()
+            .map(Self::from)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Standard fuel type code, e.g. "C-2"
+    #[getter]
+    fn code(&self) -> &'static str {
+        FbpFuelType::from(*self).code()
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.code()
+    }
+}
+
+impl From<PyFbpFuelType> for FbpFuelType {
+    fn from(fuel_type: PyFbpFuelType) -> Self {
+        match fuel_type {
+            PyFbpFuelType::C1 => FbpFuelType::C1,
+            PyFbpFuelType::C2 => FbpFuelType::C2,
+            PyFbpFuelType::C3 => FbpFuelType::C3,
+            PyFbpFuelType::C4 => FbpFuelType::C4,
+            PyFbpFuelType::C5 => FbpFuelType::C5,
+            PyFbpFuelType::C6 => FbpFuelType::C6,
+            PyFbpFuelType::C7 => FbpFuelType::C7,
+            PyFbpFuelType::M1 => FbpFuelType::M1,
+            PyFbpFuelType::M2 => FbpFuelType::M2,
+            PyFbpFuelType::M3 => FbpFuelType::M3,
+            PyFbpFuelType::M4 => FbpFuelType::M4,
+            PyFbpFuelType::D1 => FbpFuelType::D1,
+            PyFbpFuelType::D2 => FbpFuelType::D2,
+            PyFbpFuelType::S1 => FbpFuelType::S1,
+            PyFbpFuelType::S2 => FbpFuelType::S2,
+            PyFbpFuelType::S3 => FbpFuelType::S3,
+            PyFbpFuelType::O1a => FbpFuelType::O1a,
+            PyFbpFuelType::O1b => FbpFuelType::O1b,
+            PyFbpFuelType::NonFuel => FbpFuelType::NonFuel,
+        }
+    }
+}
+
+impl From<FbpFuelType> for PyFbpFuelType {
+    fn from(fuel_type: FbpFuelType) -> Self {
+        match fuel_type {
+            FbpFuelType::C1 => PyFbpFuelType::C1,
+            FbpFuelType::C2 => PyFbpFuelType::C2,
+            FbpFuelType::C3 => PyFbpFuelType::C3,
+            FbpFuelType::C4 => PyFbpFuelType::C4,
+            FbpFuelType::C5 => PyFbpFuelType::C5,
+            FbpFuelType::C6 => PyFbpFuelType::C6,
+            FbpFuelType::C7 => PyFbpFuelType::C7,
+            FbpFuelType::M1 => PyFbpFuelType::M1,
+            FbpFuelType::M2 => PyFbpFuelType::M2,
+            FbpFuelType::M3 => PyFbpFuelType::M3,
+            FbpFuelType::M4 => PyFbpFuelType::M4,
+            FbpFuelType::D1 => PyFbpFuelType::D1,
+            FbpFuelType::D2 => PyFbpFuelType::D2,
+            FbpFuelType::S1 => PyFbpFuelType::S1,
+            FbpFuelType::S2 => PyFbpFuelType::S2,
+            FbpFuelType::S3 => PyFbpFuelType::S3,
+            FbpFuelType::O1a => PyFbpFuelType::O1a,
+            FbpFuelType::O1b => PyFbpFuelType::O1b,
+            FbpFuelType::NonFuel => PyFbpFuelType::NonFuel,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Python bindings for the cffdrs crate
+//!
+//! Build and install into the current virtual environment with
+//! [maturin](https://www.maturin.rs):
+//!
+//! ```text
+//! cd python
+//! maturin develop --release
+//! ```
+
+mod equations;
+mod fuel_type;
+mod system;
+
+use pyo3::prelude::*;
+
+/// Canadian Forest Fire Danger Rating System
+#[pymodule]
+#[pyo3(name = "cffdrs")]
+fn cffdrs_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<fuel_type::PyFbpFuelType>()?;
+    m.add_class::<system::PyWeather>()?;
+    m.add_class::<system::PyStartingFwiValues>()?;
+    m.add_class::<system::PyHourlyFwiValues>()?;
+    m.add_class::<system::PyFbpOptions>()?;
+    m.add_class::<system::PyFbpValues>()?;
+
+    m.add_function(wrap_pyfunction!(system::calculate_hourly, m)?)?;
+    m.add_function(wrap_pyfunction!(system::calculate_fbp, m)?)?;
+
+    m.add_function(wrap_pyfunction!(equations::initial_spread_index, m)?)?;
+    m.add_function(wrap_pyfunction!(equations::buildup_index, m)?)?;
+    m.add_function(wrap_pyfunction!(equations::fire_weather_index, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        equations::hourly_fine_fuel_moisture_code,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(equations::rate_of_spread, m)?)?;
+    m.add_function(wrap_pyfunction!(equations::surface_fuel_consumption, m)?)?;
+    m.add_function(wrap_pyfunction!(equations::buildup_effect, m)?)?;
+    m.add_function(wrap_pyfunction!(equations::length_to_breadth, m)?)?;
+    m.add_function(wrap_pyfunction!(equations::fire_intensity, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,343 @@
+//! Weather, FWI and FBP value classes, and the `calculate_*` helpers
+
+use cffdrs::{
+    fbp_system::{self, FbpOptions, LeafOnSeason},
+    fwi_system::{self, HourlyFwiValues, StartingFwiValues},
+    weather::Weather,
+};
+use chrono::{DateTime, Utc};
+use pyo3::prelude::*;
+
+use crate::fuel_type::PyFbpFuelType;
+
+/// A weather record for a single point-in-time, at a location
+#[pyclass(name = "Weather", get_all, set_all, module = "cffdrs")]
+#[derive(Clone, Debug)]
+pub struct PyWeather {
+    /// Time the weather was sampled (timezone-aware datetime)
+    time: DateTime<Utc>,
+    /// Latitude (degrees)
+    lat: f64,
+    /// Longitude (degrees)
+    long: f64,
+    /// Temperature (Celcius)
+    temp: f64,
+    /// Relative humidity (%)
+    rh: f64,
+    /// Wind speed (at 10m height, km/h)
+    ws: f64,
+    /// Wind direction (degrees, 0 = wind from north)
+    wd: f64,
+    /// Precipitation (mm)
+    precip: f64,
+}
+
+#[pymethods]
+impl PyWeather {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        time: DateTime<Utc>,
+        lat: f64,
+        long: f64,
+        temp: f64,
+        rh: f64,
+        ws: f64,
+        wd: f64,
+        precip: f64,
+    ) -> Self {
+        Self {
+            time,
+            lat,
+            long,
+            temp,
+            rh,
+            ws,
+            wd,
+            precip,
+        }
+    }
+}
+
+impl From<&PyWeather> for Weather {
+    fn from(weather: &PyWeather) -> Self {
+        Weather {
+            time: weather.time,
+            location: geo::Point::new(weather.long, weather.lat),
+            temp: weather.temp,
+            rh: weather.rh,
+            ws: weather.ws,
+            wd: weather.wd,
+            precip: weather.precip,
+        }
+    }
+}
+
+/// Starting values for FWI calculations, usually from the previous hour
+#[pyclass(name = "StartingFwiValues", get_all, set_all, module = "cffdrs")]
+#[derive(Clone, Debug)]
+pub struct PyStartingFwiValues {
+    ffmc: f64,
+    dmc: f64,
+    dc: f64,
+}
+
+#[pymethods]
+impl PyStartingFwiValues {
+    #[new]
+    fn new(ffmc: f64, dmc: f64, dc: f64) -> Self {
+        Self { ffmc, dmc, dc }
+    }
+}
+
+/// Hourly values for the FWI system
+#[pyclass(name = "HourlyFwiValues", get_all, set_all, module = "cffdrs")]
+#[derive(Clone, Debug)]
+pub struct PyHourlyFwiValues {
+    ffmc: f64,
+    dmc: f64,
+    dc: f64,
+    isi: f64,
+    bui: f64,
+    fwi: f64,
+}
+
+#[pymethods]
+impl PyHourlyFwiValues {
+    #[new]
+    fn new(ffmc: f64, dmc: f64, dc: f64, isi: f64, bui: f64, fwi: f64) -> Self {
+        Self {
+            ffmc,
+            dmc,
+            dc,
+            isi,
+            bui,
+            fwi,
+        }
+    }
+
+    /// Use these values as the starting values for the next hour
+    fn as_starting_values(&self) -> PyStartingFwiValues {
+        PyStartingFwiValues::new(self.ffmc, self.dmc, self.dc)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HourlyFwiValues(ffmc={}, dmc={}, dc={}, isi={}, bui={}, fwi={})",
+            self.ffmc, self.dmc, self.dc, self.isi, self.bui, self.fwi
+        )
+    }
+}
+
+impl From<&PyHourlyFwiValues> for HourlyFwiValues {
+    fn from(values: &PyHourlyFwiValues) -> Self {
+        HourlyFwiValues {
+            ffmc: values.ffmc,
+            dmc: values.dmc,
+            dc: values.dc,
+            isi: values.isi,
+            bui: values.bui,
+            fwi: values.fwi,
+        }
+    }
+}
+
+impl From<HourlyFwiValues> for PyHourlyFwiValues {
+    fn from(values: HourlyFwiValues) -> Self {
+        PyHourlyFwiValues {
+            ffmc: values.ffmc,
+            dmc: values.dmc,
+            dc: values.dc,
+            isi: values.isi,
+            bui: values.bui,
+            fwi: values.fwi,
+        }
+    }
+}
+
+/// Options for FBP calculations. See the Rust `FbpOptions` for details
+#[pyclass(name = "FbpOptions", get_all, set_all, module = "cffdrs")]
+#[derive(Clone, Debug)]
+pub struct PyFbpOptions {
+    /// Elevation (m)
+    elevation: Option<f64>,
+    /// Julian day of minimum foliar moisture content
+    date_of_minimum_fmc: Option<i32>,
+    /// Slope (%)
+    slope: f64,
+    /// Slope azimuth (degrees)
+    aspect: f64,
+    /// Degree of curing for grass fuel types (%)
+    curing: f64,
+    /// Grass fuel load (kg/m^2)
+    grass_fuel_load: f64,
+    /// Percent conifer (%)
+    percent_conifer: f64,
+    /// Percent dead balsam fir (%)
+    percent_dead_balsam: f64,
+    /// Julian days of green-up and leaf fall, for D1/D2 and M1/M2
+    leaf_on_season: Option<(i32, i32)>,
+    /// Crown base height (m)
+    crown_base_height: f64,
+}
+
+#[pymethods]
+impl PyFbpOptions {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        elevation = None,
+        date_of_minimum_fmc = None,
+        slope = None,
+        aspect = None,
+        curing = None,
+        grass_fuel_load = None,
+        percent_conifer = None,
+        percent_dead_balsam = None,
+        leaf_on_season = None,
+        crown_base_height = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        elevation: Option<f64>,
+        date_of_minimum_fmc: Option<i32>,
+        slope: Option<f64>,
+        aspect: Option<f64>,
+        curing: Option<f64>,
+        grass_fuel_load: Option<f64>,
+        percent_conifer: Option<f64>,
+        percent_dead_balsam: Option<f64>,
+        leaf_on_season: Option<(i32, i32)>,
+        crown_base_height: Option<f64>,
+    ) -> Self {
+        let defaults = FbpOptions::default();
+
+        Self {
+            elevation,
+            date_of_minimum_fmc,
+            slope: slope.unwrap_or(defaults.slope),
+            aspect: aspect.unwrap_or(defaults.aspect),
+            curing: curing.unwrap_or(defaults.curing),
+            grass_fuel_load: grass_fuel_load.unwrap_or(defaults.grass_fuel_load),
+            percent_conifer: percent_conifer.unwrap_or(defaults.percent_conifer),
+            percent_dead_balsam: percent_dead_balsam.unwrap_or(defaults.percent_dead_balsam),
+            leaf_on_season,
+            crown_base_height: crown_base_height.unwrap_or(defaults.crown_base_height),
+        }
+    }
+}
+
+impl From<&PyFbpOptions> for FbpOptions {
+    fn from(options: &PyFbpOptions) -> Self {
+        FbpOptions {
+            elevation: options.elevation,
+            date_of_minimum_fmc: options.date_of_minimum_fmc,
+            slope: options.slope,
+            aspect: options.aspect,
+            curing: options.curing,
+            grass_fuel_load: options.grass_fuel_load,
+            percent_conifer: options.percent_conifer,
+            percent_dead_balsam: options.percent_dead_balsam,
+            leaf_on_season: options
+                .leaf_on_season
+                .map(|(green_up, leaf_fall)| LeafOnSeason {
+                    green_up,
+                    leaf_fall,
+                }),
+            crown_base_height: options.crown_base_height,
+        }
+    }
+}
+
+/// FBP values
+#[pyclass(name = "FbpValues", get_all, frozen, module = "cffdrs")]
+#[derive(Clone, Debug)]
+pub struct PyFbpValues {
+    /// Crown fraction burned (0-1)
+    cfb: f64,
+    /// Direction of spread (radians)
+    raz: f64,
+    /// Head rate of spread (m/min)
+    ros: f64,
+    /// Flank rate of spread (m/min)
+    fros: f64,
+    /// Back rate of spread (m/min)
+    bros: f64,
+    /// Foliar moisture content
+    fmc: f64,
+    /// Surface fuel consumption (kg/m^2)
+    sfc: f64,
+    /// Crown fuel consumption (kg/m^2)
+    cfc: f64,
+    /// Total fuel consumption (kg/m^2)
+    tfc: f64,
+    /// Crown fuel load
+    cfl: f64,
+    /// Crown base height (m)
+    cbh: f64,
+    /// Effective wind speed (km/h)
+    wsv: f64,
+    /// Effective wind direction (radians)
+    wsz: f64,
+    /// Head fire intensity (kW/m)
+    fi: f64,
+}
+
+impl From<fbp_system::FbpValues> for PyFbpValues {
+    fn from(values: fbp_system::FbpValues) -> Self {
+        PyFbpValues {
+            cfb: values.cfb,
+            raz: values.raz,
+            ros: values.ros,
+            fros: values.fros,
+            bros: values.bros,
+            fmc: values.fmc,
+            sfc: values.sfc,
+            cfc: values.cfc,
+            tfc: values.tfc,
+            cfl: values.cfl,
+            cbh: values.cbh,
+            wsv: values.wsv,
+            wsz: values.wsz,
+            fi: values.fi,
+        }
+    }
+}
+
+/// Calculate all values for the FWI system from hourly weather
+#[pyfunction]
+#[pyo3(signature = (weather, previous = None))]
+pub fn calculate_hourly(
+    weather: PyRef<'_, PyWeather>,
+    previous: Option<PyRef<'_, PyStartingFwiValues>>,
+) -> PyHourlyFwiValues {
+    let previous = previous.map(|previous| StartingFwiValues {
+        ffmc: previous.ffmc,
+        dmc: previous.dmc,
+        dc: previous.dc,
+    });
+
+    fwi_system::calculate_hourly(&Weather::from(&*weather), previous.as_ref()).into()
+}
+
+/// Calculate all FBP values from weather and FWI values
+#[pyfunction]
+#[pyo3(signature = (fuel_type, fwi, weather, options = None))]
+pub fn calculate_fbp(
+    fuel_type: PyFbpFuelType,
+    fwi: PyRef<'_, PyHourlyFwiValues>,
+    weather: PyRef<'_, PyWeather>,
+    options: Option<PyRef<'_, PyFbpOptions>>,
+) -> PyFbpValues {
+    let options = options
+        .map(|options| FbpOptions::from(&*options))
+        .unwrap_or_default();
+
+    fbp_system::calculate_fbp(
+        fuel_type.into(),
+        &HourlyFwiValues::from(&*fwi),
+        &Weather::from(&*weather),
+        options,
+    )
+    .into()
+}
@@ -0,0 +1,69 @@
+import datetime
+
+import numpy as np
+import pytest
+
+import cffdrs
+
+
+@pytest.fixture
+def weather():
+    return cffdrs.Weather(
+        time=datetime.datetime(2023, 7, 8, 9, 10, 11, tzinfo=datetime.timezone.utc),
+        lat=50.69,
+        long=-120.34,
+        temp=28.0,
+        rh=12.0,
+        ws=16.0,
+        wd=95.0,
+        precip=0.0,
+    )
+
+
+def test_calculate_hourly(weather):
+    fwi = cffdrs.calculate_hourly(weather)
+
+    assert fwi.ffmc == 57.90482214144054
+    assert fwi.fwi == 2.2766000331952063
+
+    next_hour = cffdrs.calculate_hourly(weather, fwi.as_starting_values())
+    assert next_hour.ffmc > fwi.ffmc
+
+
+def test_calculate_fbp(weather):
+    fwi = cffdrs.calculate_hourly(weather)
+
+    flat = cffdrs.calculate_fbp(cffdrs.FbpFuelType.C2, fwi, weather)
+    steep = cffdrs.calculate_fbp(
+        cffdrs.FbpFuelType.C2, fwi, weather, cffdrs.FbpOptions(slope=40.0)
+    )
+
+    assert flat.ros > 0
+    assert steep.ros > flat.ros
+
+
+def test_fuel_type():
+    assert cffdrs.FbpFuelType.parse("C-2") == cffdrs.FbpFuelType.C2
+    assert cffdrs.FbpFuelType.parse("o1a") == cffdrs.FbpFuelType.O1a
+    assert str(cffdrs.FbpFuelType.NonFuel) == "Non-fuel"
+
+    with pytest.raises(ValueError):
+        cffdrs.FbpFuelType.parse("X-9")
+
+
+def test_vectorized_equations():
+    ffmc = np.array([[80.0, 85.0], [90.0, 95.0]])
+
+    isi = cffdrs.initial_spread_index(ffmc, 20.0)
+
+    assert isi.shape == (2, 2)
+    assert isi[1, 0] == cffdrs.initial_spread_index(90.0, 20.0)
+    assert isinstance(cffdrs.initial_spread_index(90.0, 20.0), float)
+
+    ros = cffdrs.rate_of_spread(
+        cffdrs.FbpFuelType.C2, isi, 60.0, 100.0, 2.0, 50.0, 35.0, 80.0, 3.0
+    )
+    assert np.all(np.diff(ros.ravel()) > 0)
+
+    with pytest.raises(ValueError):
+        cffdrs.buildup_index(np.zeros(2), np.zeros(3))
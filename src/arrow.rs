@@ -0,0 +1,635 @@
+//! FWI and FBP calculations for Arrow tables
+//!
+//! Calculates the FWI and FBP systems for every row of an Arrow [RecordBatch], e.g. one read from
+//! a Parquet file with the [parquet](https://docs.rs/parquet) crate. Outputs are returned as the
+//! input batch with the calculated values appended as `Float64` columns.
+//!
+//! Columns are found by name, ignoring case. Numeric columns can be `Float64`, `Float32`, `Int32`
+//! or `Int64`, and times must be timestamps (in any unit, with times without a timezone read as
+//! UTC). Rows with a null in a required column have null outputs.
+//!
+//! Requires the `arrow` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use arrow_array::{Float64Array, RecordBatch, StringArray, TimestampSecondArray};
+//! use cffdrs::arrow::{calculate_fbp_batch, calculate_fwi_batch};
+//! use cffdrs::fbp_system::{FbpFuelType, FbpOptions};
+//! use cffdrs::fwi_system::StartingFwiValues;
+//!
+//! let weather = RecordBatch::try_from_iter([
+//!     ("id", Arc::new(StringArray::from(vec!["a", "b", "a"])) as _),
+//!     ("time", Arc::new(TimestampSecondArray::from(vec![1688832000, 1688832000, 1688835600])) as _),
+//!     ("lat", Arc::new(Float64Array::from(vec![50.69, 49.95, 50.69])) as _),
+//!     ("long", Arc::new(Float64Array::from(vec![-120.34, -119.38, -120.34])) as _),
+//!     ("temp", Arc::new(Float64Array::from(vec![28., 31., 29.])) as _),
+//!     ("rh", Arc::new(Float64Array::from(vec![12., 15., 11.])) as _),
+//!     ("ws", Arc::new(Float64Array::from(vec![16., 9., 18.])) as _),
+//!     ("wd", Arc::new(Float64Array::from(vec![270., 180., 260.])) as _),
+//!     ("precip", Arc::new(Float64Array::from(vec![0., 0., 0.])) as _),
+//! ])?;
+//!
+//! let start = StartingFwiValues { ffmc: 85., dmc: 6., dc: 15. };
+//! let fwi = calculate_fwi_batch(&weather, &start)?;
+//! let fbp = calculate_fbp_batch(&fwi, Some(FbpFuelType::C2), &FbpOptions::default())?;
+//!
+//! assert!(fbp.column_by_name("hfi").is_some());
+//! # Ok::<(), arrow_schema::ArrowError>(())
+//! ```
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow_array::{
+    cast::AsArray,
+    types::{
+        ArrowTemporalType, Float32Type, Float64Type, Int32Type, Int64Type,
+        TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+        TimestampSecondType,
+    },
+    Array, ArrayRef, Float64Array, PrimitiveArray, RecordBatch,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    fbp_system::{calculate_fbp, FbpFuelType, FbpOptions, FbpValues},
+    fwi_system::{calculate_hourly, initial_spread_index, HourlyFwiValues, StartingFwiValues},
+    weather::Weather,
+};
+
+/// Names of the columns added by [calculate_fwi_batch()]
+pub const FWI_COLUMNS: [&str; 6] = ["ffmc", "dmc", "dc", "isi", "bui", "fwi"];
+
+/// Names of the columns added by [calculate_fbp_batch()], in the units of
+/// [FbpValues] (`hfi` is [`FbpValues::fi`])
+pub const FBP_COLUMNS: [&str; 10] = [
+    "ros", "fros", "bros", "hfi", "cfb", "cfc", "sfc", "tfc", "fmc", "raz",
+];
+
+/// Calculate FWI values for each row of hourly weather
+///
+/// Required columns are `time`, `lat`, `long`, `temp`, `rh`, `ws` and `precip`, named as the
+/// [Weather] fields. An optional `id` column (string or integer) identifies the station of each
+/// row.
+///
+/// Each station's rows are calculated in time order, carrying the station's FWI codes forward
+/// from its previous row and starting from `start`, so rows don't need to be grouped or sorted.
+/// Rows without a station all belong to the same station. The output keeps the input's row
+/// order, with the [FWI_COLUMNS] appended.
+pub fn calculate_fwi_batch(
+    batch: &RecordBatch,
+    start: &StartingFwiValues,
+) -> Result<RecordBatch, ArrowError> {
+    let id = station_column(batch)?;
+    let time = time_column(batch)?;
+    let lat = required_float_column(batch, &["lat", "latitude"])?;
+    let long = required_float_column(batch, &["long", "lon", "longitude"])?;
+    let temp = required_float_column(batch, &["temp"])?;
+    let rh = required_float_column(batch, &["rh"])?;
+    let ws = required_float_column(batch, &["ws"])?;
+    let precip = required_float_column(batch, &["precip", "prec"])?;
+
+    let mut order: Vec<usize> = (0..batch.num_rows()).collect();
+    order.sort_by(|&a, &b| id[a].cmp(&id[b]).then(time[a].cmp(&time[b])));
+
+    let mut results: Vec<Option<HourlyFwiValues>> = vec![None; batch.num_rows()];
+    let mut previous: HashMap<&Option<String>, StartingFwiValues> = HashMap::new();
+
+    for index in order {
+        let weather = (|| {
+            Some(Weather {
+                time: time[index]?,
+                location: geo::Point::new(long[index]?, lat[index]?),
+                temp: temp[index]?,
+                rh: rh[index]?,
+                ws: ws[index]?,
+                // Not used by the FWI system
+                wd: 0.,
+                precip: precip[index]?,
+            })
+        })();
+        let Some(weather) = weather else {
+            continue;
+        };
+
+        let values = calculate_hourly(&weather, Some(previous.get(&id[index]).unwrap_or(start)));
+        previous.insert(&id[index], values.as_starting_values());
+        results[index] = Some(values);
+    }
+
+    let column = |value: fn(&HourlyFwiValues) -> f64| {
+        results
+            .iter()
+            .map(|values| values.as_ref().map(value))
+            .collect::<Float64Array>()
+    };
+
+    append_columns(
+        batch,
+        FWI_COLUMNS,
+        [
+            column(|values| values.ffmc),
+            column(|values| values.dmc),
+            column(|values| values.dc),
+            column(|values| values.isi),
+            column(|values| values.bui),
+            column(|values| values.fwi),
+        ],
+    )
+}
+
+/// Calculate FBP values for each row of FWI values and weather, such as the output of
+/// [calculate_fwi_batch()]
+///
+/// Required columns are `time`, `lat`, `long`, `ws`, `wd`, `ffmc` and `bui`. `isi` is calculated
+/// from `ffmc` and `ws` when the column is missing. Rows are calculated independently.
+///
+/// The fuel type of each row is read from an optional `fuel_type` column (e.g. `"C-2"`), falling
+/// back to the `fuel_type` argument. Optional `elevation`, `slope`, `aspect` and `cbh` columns
+/// override the corresponding [FbpOptions] for their row. The output keeps the input's row order,
+/// with the [FBP_COLUMNS] appended.
+pub fn calculate_fbp_batch(
+    batch: &RecordBatch,
+    fuel_type: Option<FbpFuelType>,
+    options: &FbpOptions,
+) -> Result<RecordBatch, ArrowError> {
+    let fuel_types = fuel_type_column(batch)?;
+    let time = time_column(batch)?;
+    let lat = required_float_column(batch, &["lat", "latitude"])?;
+    let long = required_float_column(batch, &["long", "lon", "longitude"])?;
+    let ws = required_float_column(batch, &["ws"])?;
+    let wd = required_float_column(batch, &["wd"])?;
+    let ffmc = required_float_column(batch, &["ffmc"])?;
+    let bui = required_float_column(batch, &["bui"])?;
+    let isi = float_column(batch, &["isi"])?;
+    let elevation = float_column(batch, &["elevation", "elv"])?;
+    let slope = float_column(batch, &["slope", "gs"])?;
+    let aspect = float_column(batch, &["aspect"])?;
+    let cbh = float_column(batch, &["cbh"])?;
+
+    if fuel_types.is_none() && fuel_type.is_none() {
+        return Err(ArrowError::SchemaError(
+            "batch has no `fuel_type` column, and no fuel type was given".to_string(),
+        ));
+    }
+
+    let optional = |column: &Option<Vec<Option<f64>>>, index: usize| {
+        column.as_ref().and_then(|column| column[index])
+    };
+
+    let mut results = Vec::with_capacity(batch.num_rows());
+    for index in 0..batch.num_rows() {
+        let fuel = match &fuel_types {
+            Some(fuel_types) => fuel_types[index].or(fuel_type),
+            None => fuel_type,
+        };
+        let inputs = (|| {
+            let weather = Weather {
+                time: time[index]?,
+                location: geo::Point::new(long[index]?, lat[index]?),
+                temp: 0.,
+                rh: 0.,
+                ws: ws[index]?,
+                wd: wd[index]?,
+                precip: 0.,
+            };
+            let ffmc = ffmc[index]?;
+            let fwi = HourlyFwiValues {
+                ffmc,
+                bui: bui[index]?,
+                isi: optional(&isi, index)
+                    .unwrap_or_else(|| initial_spread_index(ffmc, weather.ws)),
+                // Not used by the FBP system
+                dc: f64::NAN,
+                dmc: f64::NAN,
+                fwi: f64::NAN,
+            };
+
+            Some((fuel?, weather, fwi))
+        })();
+
+        results.push(inputs.map(|(fuel, weather, fwi)| {
+            let options = FbpOptions {
+                elevation: optional(&elevation, index).or(options.elevation),
+                slope: optional(&slope, index).unwrap_or(options.slope),
+                aspect: optional(&aspect, index).unwrap_or(options.aspect),
+                crown_base_height: optional(&cbh, index).unwrap_or(options.crown_base_height),
+                ..options.clone()
+            };
+
+            calculate_fbp(fuel, &fwi, &weather, options)
+        }));
+    }
+
+    let column = |value: fn(&FbpValues) -> f64| {
+        results
+            .iter()
+            .map(|values| values.as_ref().map(value))
+            .collect::<Float64Array>()
+    };
+
+    append_columns(
+        batch,
+        FBP_COLUMNS,
+        [
+            column(|values| values.ros),
+            column(|values| values.fros),
+            column(|values| values.bros),
+            column(|values| values.fi),
+            column(|values| values.cfb),
+            column(|values| values.cfc),
+            column(|values| values.sfc),
+            column(|values| values.tfc),
+            column(|values| values.fmc),
+            column(|values| values.raz),
+        ],
+    )
+}
+
+/// Add `Float64` columns to the end of a batch
+fn append_columns<const N: usize>(
+    batch: &RecordBatch,
+    names: [&str; N],
+    columns: [Float64Array; N],
+) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    let mut fields = schema.fields().to_vec();
+    let mut arrays = batch.columns().to_vec();
+
+    for (name, column) in names.into_iter().zip(columns) {
+        if find_column(batch, &[name]).is_some() {
+            return Err(ArrowError::SchemaError(format!(
+                "batch already has a `{name}` column"
+            )));
+        }
+
+        fields.push(Arc::new(Field::new(name, DataType::Float64, true)));
+        arrays.push(Arc::new(column));
+    }
+
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        arrays,
+    )
+}
+
+/// Find a column by any of its `names`, ignoring case
+fn find_column<'a>(batch: &'a RecordBatch, names: &[&str]) -> Option<&'a ArrayRef> {
+    batch
+        .schema_ref()
+        .fields()
+        .iter()
+        .position(|field| {
+            names
+                .iter()
+                .any(|name| field.name().eq_ignore_ascii_case(name))
+        })
+        .map(|index| batch.column(index))
+}
+
+fn required_column<'a>(batch: &'a RecordBatch, names: &[&str]) -> Result<&'a ArrayRef, ArrowError> {
+    find_column(batch, names)
+        .ok_or_else(|| ArrowError::SchemaError(format!("batch has no `{}` column", names[0])))
+}
+
+fn float_column(
+    batch: &RecordBatch,
+    names: &[&str],
+) -> Result<Option<Vec<Option<f64>>>, ArrowError> {
+    let Some(column) = find_column(batch, names) else {
+        return Ok(None);
+    };
+
+    Ok(Some(match column.data_type() {
+        DataType::Float64 => column.as_primitive::<Float64Type>().iter().collect(),
+        DataType::Float32 => floats(column.as_primitive::<Float32Type>(), f64::from),
+        DataType::Int32 => floats(column.as_primitive::<Int32Type>(), f64::from),
+        DataType::Int64 => floats(column.as_primitive::<Int64Type>(), |value| value as f64),
+        other => {
+            return Err(ArrowError::SchemaError(format!(
+                "column `{}` must be numeric, not {other}",
+                names[0]
+            )))
+        }
+    }))
+}
+
+fn required_float_column(
+    batch: &RecordBatch,
+    names: &[&str],
+) -> Result<Vec<Option<f64>>, ArrowError> {
+    required_column(batch, names)?;
+    Ok(float_column(batch, names)?.expect("column exists"))
+}
+
+fn floats<T: arrow_array::ArrowPrimitiveType>(
+    array: &PrimitiveArray<T>,
+    convert: fn(T::Native) -> f64,
+) -> Vec<Option<f64>> {
+    array.iter().map(|value| value.map(convert)).collect()
+}
+
+/// The `time` column, as UTC times
+fn time_column(batch: &RecordBatch) -> Result<Vec<Option<DateTime<Utc>>>, ArrowError> {
+    let column = required_column(batch, &["time"])?;
+
+    Ok(match column.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            times(column.as_primitive::<TimestampSecondType>())
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            times(column.as_primitive::<TimestampMillisecondType>())
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            times(column.as_primitive::<TimestampMicrosecondType>())
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            times(column.as_primitive::<TimestampNanosecondType>())
+        }
+        other => {
+            return Err(ArrowError::SchemaError(format!(
+                "column `time` must be a timestamp, not {other}"
+            )))
+        }
+    })
+}
+
+fn times<T: ArrowTemporalType>(array: &PrimitiveArray<T>) -> Vec<Option<DateTime<Utc>>>
+where
+    i64: From<T::Native>,
+{
+    (0..array.len())
+        .map(|index| {
+            array
+                .is_valid(index)
+                .then(|| array.value_as_datetime(index))
+                .flatten()
+                .map(|time| time.and_utc())
+        })
+        .collect()
+}
+
+/// The optional `id` column, as strings. Rows without a station are `None`
+fn station_column(batch: &RecordBatch) -> Result<Vec<Option<String>>, ArrowError> {
+    let Some(column) = find_column(batch, &["id", "station"]) else {
+        return Ok(vec![None; batch.num_rows()]);
+    };
+
+    Ok(match column.data_type() {
+        DataType::Int32 | DataType::Int64 => float_column(batch, &["id", "station"])?
+            .expect("column exists")
+            .into_iter()
+            .map(|id| id.map(|id| id.to_string()))
+            .collect(),
+        _ => strings(column, "id")?
+            .into_iter()
+            .map(|id| id.map(str::to_string))
+            .collect(),
+    })
+}
+
+/// The optional `fuel_type` column, parsed. Null values are `None`
+fn fuel_type_column(batch: &RecordBatch) -> Result<Option<Vec<Option<FbpFuelType>>>, ArrowError> {
+    let Some(column) = find_column(batch, &["fuel_type", "fueltype", "fuel"]) else {
+        return Ok(None);
+    };
+
+    strings(column, "fuel_type")?
+        .into_iter()
+        .enumerate()
+        .map(|(index, fuel_type)| {
+            fuel_type
+                .map(|fuel_type| {
+                    fuel_type.parse().map_err(|err| {
+                        ArrowError::InvalidArgumentError(format!("row {index}: {err}"))
+                    })
+                })
+                .transpose()
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+fn strings<'a>(column: &'a ArrayRef, name: &str) -> Result<Vec<Option<&'a str>>, ArrowError> {
+    Ok(match column.data_type() {
+        DataType::Utf8 => column.as_string::<i32>().iter().collect(),
+        DataType::LargeUtf8 => column.as_string::<i64>().iter().collect(),
+        DataType::Utf8View => column.as_string_view().iter().collect(),
+        other => {
+            return Err(ArrowError::SchemaError(format!(
+                "column `{name}` must be a string, not {other}"
+            )))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Float32Array, Int64Array, StringArray, TimestampMillisecondArray};
+    use chrono::TimeZone;
+
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn start() -> StartingFwiValues {
+        StartingFwiValues {
+            ffmc: 85.,
+            dmc: 6.,
+            dc: 15.,
+        }
+    }
+
+    fn weather_batch() -> RecordBatch {
+        let time = Utc
+            .with_ymd_and_hms(2023, 7, 8, 12, 0, 0)
+            .unwrap()
+            .timestamp_millis();
+
+        // Station "a" is out of order, and station "b" has a missing temperature
+        RecordBatch::try_from_iter([
+            (
+                "ID",
+                Arc::new(StringArray::from(vec!["a", "b", "a", "b", "a"])) as ArrayRef,
+            ),
+            (
+                "time",
+                Arc::new(TimestampMillisecondArray::from(vec![
+                    time + 2 * HOUR,
+                    time,
+                    time,
+                    time + HOUR,
+                    time + HOUR,
+                ])) as ArrayRef,
+            ),
+            (
+                "lat",
+                Arc::new(Float64Array::from(vec![50.69; 5])) as ArrayRef,
+            ),
+            (
+                "long",
+                Arc::new(Float32Array::from(vec![-120.34; 5])) as ArrayRef,
+            ),
+            (
+                "temp",
+                Arc::new(Float64Array::from(vec![
+                    Some(30.),
+                    Some(25.),
+                    Some(28.),
+                    None,
+                    Some(29.),
+                ])) as ArrayRef,
+            ),
+            (
+                "rh",
+                Arc::new(Int64Array::from(vec![10, 20, 12, 20, 11])) as ArrayRef,
+            ),
+            (
+                "ws",
+                Arc::new(Float64Array::from(vec![18., 10., 16., 10., 17.])) as ArrayRef,
+            ),
+            (
+                "wd",
+                Arc::new(Float64Array::from(vec![270.; 5])) as ArrayRef,
+            ),
+            (
+                "precip",
+                Arc::new(Float64Array::from(vec![0.; 5])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    fn weather(batch: &RecordBatch, index: usize) -> Weather {
+        let long = float_column(batch, &["long"]).unwrap().unwrap();
+        let temp = float_column(batch, &["temp"]).unwrap().unwrap();
+        let rh = float_column(batch, &["rh"]).unwrap().unwrap();
+        let ws = float_column(batch, &["ws"]).unwrap().unwrap();
+
+        Weather {
+            time: time_column(batch).unwrap()[index].unwrap(),
+            location: geo::Point::new(long[index].unwrap(), 50.69),
+            temp: temp[index].unwrap(),
+            rh: rh[index].unwrap(),
+            ws: ws[index].unwrap(),
+            wd: 0.,
+            precip: 0.,
+        }
+    }
+
+    fn values(batch: &RecordBatch, name: &str) -> Vec<Option<f64>> {
+        float_column(batch, &[name]).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_calculate_fwi_batch() {
+        let batch = weather_batch();
+        let output = calculate_fwi_batch(&batch, &start()).unwrap();
+
+        assert_eq!(output.num_rows(), 5);
+        assert_eq!(
+            output.num_columns(),
+            batch.num_columns() + FWI_COLUMNS.len()
+        );
+
+        // Station "a" is calculated in time order: rows 2, 4 then 0
+        let first = calculate_hourly(&weather(&batch, 2), Some(&start()));
+        let second = calculate_hourly(&weather(&batch, 4), Some(&first.as_starting_values()));
+        let third = calculate_hourly(&weather(&batch, 0), Some(&second.as_starting_values()));
+
+        let ffmc = values(&output, "ffmc");
+        let fwi = values(&output, "fwi");
+        assert_eq!(ffmc[2], Some(first.ffmc));
+        assert_eq!(ffmc[4], Some(second.ffmc));
+        assert_eq!(ffmc[0], Some(third.ffmc));
+        assert_eq!(fwi[0], Some(third.fwi));
+
+        // Station "b" skips the row with missing weather
+        assert_eq!(ffmc[3], None);
+        let b = calculate_hourly(&weather(&batch, 1), Some(&start()));
+        assert_eq!(ffmc[1], Some(b.ffmc));
+    }
+
+    #[test]
+    fn test_calculate_fbp_batch() {
+        let fwi = calculate_fwi_batch(&weather_batch(), &start()).unwrap();
+        let fuel_types = StringArray::from(vec![Some("C-2"), None, Some("D1"), None, Some("O-1a")]);
+        let batch = RecordBatch::try_from_iter(
+            fwi.schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .zip(fwi.columns().iter().cloned())
+                .chain([("fuel_type".to_string(), Arc::new(fuel_types) as ArrayRef)]),
+        )
+        .unwrap();
+
+        let options = FbpOptions::default();
+        let output = calculate_fbp_batch(&batch, Some(FbpFuelType::C2), &options).unwrap();
+        assert_eq!(
+            output.num_columns(),
+            batch.num_columns() + FBP_COLUMNS.len()
+        );
+
+        let ros = values(&output, "ros");
+        let hfi = values(&output, "hfi");
+        let ffmc = values(&batch, "ffmc");
+        let bui = values(&batch, "bui");
+        let isi = values(&batch, "isi");
+
+        for (index, fuel_type) in [
+            (0, FbpFuelType::C2),
+            (1, FbpFuelType::C2),
+            (2, FbpFuelType::D1),
+            (4, FbpFuelType::O1a),
+        ] {
+            let fwi = HourlyFwiValues {
+                ffmc: ffmc[index].unwrap(),
+                bui: bui[index].unwrap(),
+                isi: isi[index].unwrap(),
+                dc: f64::NAN,
+                dmc: f64::NAN,
+                fwi: f64::NAN,
+            };
+            let weather = Weather {
+                wd: 270.,
+                ..weather(&batch, index)
+            };
+            let expected = calculate_fbp(fuel_type, &fwi, &weather, options.clone());
+
+            assert_eq!(ros[index], Some(expected.ros));
+            assert_eq!(hfi[index], Some(expected.fi));
+        }
+
+        // No FWI values for the row with missing weather
+        assert_eq!(ros[3], None);
+    }
+
+    #[test]
+    fn test_calculate_fbp_batch_without_fuel_type() {
+        let fwi = calculate_fwi_batch(&weather_batch(), &start()).unwrap();
+
+        assert!(calculate_fbp_batch(&fwi, None, &FbpOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let batch = weather_batch().project(&[0, 1, 2, 3, 4, 5, 6]).unwrap();
+        let err = calculate_fwi_batch(&batch, &start()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Schema error: batch has no `precip` column"
+        );
+    }
+
+    #[test]
+    fn test_existing_column() {
+        let fwi = calculate_fwi_batch(&weather_batch(), &start()).unwrap();
+
+        assert!(calculate_fwi_batch(&fwi, &start()).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Branch-free `exp()` and `ln()`
+//!
+//! Calls into the platform's math library can't be vectorized, so the batch kernels use these
+//! instead. They only use arithmetic, bit casts and selects, which the compiler can turn into SIMD
+//! instructions, and are accurate to a few ulps for normal inputs.
+
+use core::f64::consts::{LOG2_E, SQRT_2};
+
+/// `ln(2)` split into a high part (exactly representable when multiplied by an exponent) and the
+/// rest, from fdlibm
+const LN_2_HI: f64 = 6.931_471_803_691_238e-1;
+const LN_2_LO: f64 = 1.908_214_929_270_587_7e-10;
+
+/// 1.5 * 2^52. Adding this to a (small enough) float rounds it to an integer, stored in the low
+/// bits of the mantissa
+const ROUND: f64 = 6_755_399_441_055_744.;
+
+/// Largest input that doesn't overflow, `ln(f64::MAX)`
+const MAX_INPUT: f64 = 709.782_712_893_384;
+/// Smallest input with a normal result (below this, results are flushed to zero)
+const MIN_INPUT: f64 = -708.;
+
+/// 2^52, for converting small integers stored in the mantissa back to floats
+const TWO_52: f64 = 4_503_599_627_370_496.;
+
+const MANTISSA_MASK: u64 = (1 << 52) - 1;
+
+/// `e^x`, flushing subnormal results to zero
+#[inline(always)]
+pub(super) fn exp(x: f64) -> f64 {
+    // x = n * ln(2) + r, with |r| <= ln(2) / 2
+    let clamped = x.clamp(MIN_INPUT, MAX_INPUT);
+    let t = clamped * LOG2_E + ROUND;
+    let n = t - ROUND;
+    let r = (clamped - n * LN_2_HI) - n * LN_2_LO;
+
+    // e^r, Taylor series up to r^12 (the r^13 term is below f64 precision)
+    let p = 1. / 479_001_600.;
+    let p = p * r + 1. / 39_916_800.;
+    let p = p * r + 1. / 3_628_800.;
+    let p = p * r + 1. / 362_880.;
+    let p = p * r + 1. / 40_320.;
+    let p = p * r + 1. / 5_040.;
+    let p = p * r + 1. / 720.;
+    let p = p * r + 1. / 120.;
+    let p = p * r + 1. / 24.;
+    let p = p * r + 1. / 6.;
+    let p = p * r + 0.5;
+    let p = p * r + 1.;
+    let p = p * r + 1.;
+
+    // 2^(n - 1), by putting n (which is in the low bits of t) into the exponent. n can be 1024
+    // just below MAX_INPUT, so this multiplies by 2 separately
+    let scale = f64::from_bits(t.to_bits().wrapping_add(1022) << 52);
+    let result = p * scale * 2.;
+
+    if x < MIN_INPUT {
+        0.
+    } else if x > MAX_INPUT {
+        f64::INFINITY
+    } else if x.is_nan() {
+        x
+    } else {
+        result
+    }
+}
+
+/// Natural logarithm
+#[inline(always)]
+pub(super) fn ln(x: f64) -> f64 {
+    // Scale subnormals up, so they have an exponent
+    let subnormal = x < f64::MIN_POSITIVE;
+    let scaled = if subnormal { x * TWO_52 } else { x };
+
+    // x = 2^k * m, with sqrt(2)/2 <= m < sqrt(2)
+    let bits = scaled.to_bits();
+    let biased_exponent = f64::from_bits((bits >> 52) | ROUND.to_bits()) - ROUND;
+    let m = f64::from_bits((bits & MANTISSA_MASK) | 1f64.to_bits());
+    let (m, k) = if m > SQRT_2 {
+        (m * 0.5, biased_exponent - 1022.)
+    } else {
+        (m, biased_exponent - 1023.)
+    };
+    let k = if subnormal { k - 52. } else { k };
+
+    // ln(m) = 2 * atanh(s), with s = (m - 1) / (m + 1), so |s| <= 0.1716
+    let s = (m - 1.) / (m + 1.);
+    let z = s * s;
+    let p = 1. / 23.;
+    let p = p * z + 1. / 21.;
+    let p = p * z + 1. / 19.;
+    let p = p * z + 1. / 17.;
+    let p = p * z + 1. / 15.;
+    let p = p * z + 1. / 13.;
+    let p = p * z + 1. / 11.;
+    let p = p * z + 1. / 9.;
+    let p = p * z + 1. / 7.;
+    let p = p * z + 1. / 5.;
+    let p = p * z + 1. / 3.;
+    let p = p * z + 1.;
+    let ln_m = 2. * s * p;
+
+    let result = k * LN_2_HI + (ln_m + k * LN_2_LO);
+
+    if x == 0. {
+        f64::NEG_INFINITY
+    } else if x > 0. && x < f64::INFINITY {
+        result
+    } else if x == f64::INFINITY {
+        x
+    } else {
+        f64::NAN
+    }
+}
+
+/// `x^y` for `y > 0`
+#[inline(always)]
+pub(super) fn powf(x: f64, y: f64) -> f64 {
+    if x == 0. {
+        0.
+    } else {
+        exp(y * ln(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 4. * f64::EPSILON * expected.abs(),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_exp() {
+        for i in -7080..=7097 {
+            let x = i as f64 / 10. + 0.0123;
+            assert_close(exp(x), x.exp());
+        }
+
+        assert_close(exp(MAX_INPUT), MAX_INPUT.exp());
+        assert_eq!(exp(0.), 1.);
+        assert_eq!(exp(-1000.), 0.);
+        assert_eq!(exp(f64::NEG_INFINITY), 0.);
+        assert_eq!(exp(1000.), f64::INFINITY);
+        assert!(exp(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_ln() {
+        for i in -300..=300 {
+            for m in [1., 1.2345, SQRT_2, SQRT_2 + 1e-12, 1.5, 1.9999] {
+                let x = m * 10f64.powi(i);
+                assert_close(ln(x), x.ln());
+            }
+        }
+
+        assert_close(ln(1e-310), 1e-310f64.ln());
+        assert_eq!(ln(1.), 0.);
+        assert_eq!(ln(0.), f64::NEG_INFINITY);
+        assert_eq!(ln(f64::INFINITY), f64::INFINITY);
+        assert!(ln(-1.).is_nan());
+        assert!(ln(f64::NAN).is_nan());
+    }
+}
@@ -0,0 +1,256 @@
+//! `cffdrs fbp` - FBP values from FWI values and weather
+
+use std::error::Error;
+
+use cffdrs::{
+    fbp_system::{
+        calculate_fbp, crown_base_height,
+        crown_fire::{fbp_fire_type, CrownFireType},
+        FbpFuelType, FbpOptions, FuelModel,
+    },
+    fwi_system::{initial_spread_index, HourlyFwiValues},
+    weather::Weather,
+};
+use chrono::{NaiveDate, TimeZone, Utc};
+use clap::{Args, ValueEnum};
+
+use crate::table::{InputTable, OutputTable, Value};
+
+/// Which FBP values to output
+#[derive(ValueEnum, PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum FbpOutput {
+    /// ID, CFB, CFC, FD, HFI, RAZ, ROS, SFC and TFC
+    #[default]
+    Primary,
+    /// Primary values, plus BUI, FFMC, ISI, FMC, FROS, BROS, CBH, CFL, LB and WSV
+    All,
+}
+
+#[derive(Args, Debug)]
+pub struct FbpArgs {
+    /// Fuel type for records without a `FuelType` column (e.g. C2 or C-2)
+    #[arg(long)]
+    pub fuel: Option<FbpFuelType>,
+    /// Which values to output
+    #[arg(long, value_enum, default_value_t)]
+    pub values: FbpOutput,
+}
+
+/// Day of year used when the input has neither `dj` nor a date
+const DEFAULT_DAY_OF_YEAR: u32 = 180;
+
+/// Calculate FBP values for each record
+///
+/// Input columns are named as in R cffdrs `fbp()`: `id`, `FuelType`, `lat`, `long`, `elv`,
+/// `ffmc`, `bui`, `isi`, `ws`, `wd`, `gs`, `aspect`, `dj`, `d0`, `pc`, `pdf`, `gfl`, `cc`,
+/// `cbh`, `sd` and `sh`. Only `lat`, `long`, `ffmc` and `bui` are required. A date can be given
+/// with `yr`, `mon` and `day` instead of `dj`. Missing values use the defaults from
+/// [FbpOptions], and `ISI` is calculated from `FFMC` and `WS` when it's missing.
+pub fn fbp(input: &InputTable, args: &FbpArgs) -> Result<OutputTable, Box<dyn Error>> {
+    let id = input.column(&["id"]);
+    let fuel_type = input.column(&["fueltype", "fuel_type", "fuel"]);
+    let lat = input.required_column(&["lat"])?;
+    let long = input.required_column(&["long", "lon"])?;
+    let elv = input.column(&["elv", "elevation"]);
+    let ffmc = input.required_column(&["ffmc"])?;
+    let bui = input.required_column(&["bui"])?;
+    let isi = input.column(&["isi"]);
+    let ws = input.column(&["ws"]);
+    let wd = input.column(&["wd"]);
+    let gs = input.column(&["gs", "slope"]);
+    let aspect = input.column(&["aspect"]);
+    let dj = input.column(&["dj"]);
+    let yr = input.column(&["yr", "year"]);
+    let mon = input.column(&["mon", "month"]);
+    let day = input.column(&["day"]);
+    let d0 = input.column(&["d0"]);
+    let pc = input.column(&["pc"]);
+    let pdf = input.column(&["pdf"]);
+    let gfl = input.column(&["gfl"]);
+    let cc = input.column(&["cc"]);
+    let cbh = input.column(&["cbh"]);
+    let sd = input.column(&["sd"]);
+    let sh = input.column(&["sh"]);
+
+    if fuel_type.is_none() && args.fuel.is_none() {
+        return Err("input has no \"FuelType\" column, and no --fuel was given".into());
+    }
+
+    let mut headers = vec!["ID", "CFB", "CFC", "FD", "HFI", "RAZ", "ROS", "SFC", "TFC"];
+    if args.values == FbpOutput::All {
+        headers.extend([
+            "BUI", "FFMC", "ISI", "FMC", "FROS", "BROS", "CBH", "CFL", "LB", "WSV",
+        ]);
+    }
+    let mut output = OutputTable::new(headers.into_iter().map(str::to_string).collect());
+    let defaults = FbpOptions::default();
+
+    for (index, row) in input.rows().enumerate() {
+        let fuel = match row.text(fuel_type) {
+            Some(fuel) => fuel
+                .parse::<FbpFuelType>()
+                .map_err(|err| format!("line {}: {err}", row.line))?,
+            None => args
+                .fuel
+                .ok_or_else(|| format!("line {}: missing fuel type", row.line))?,
+        };
+
+        let date = match (row.number(yr)?, row.number(mon)?, row.number(day)?) {
+            (Some(yr), Some(mon), Some(day)) => {
+                NaiveDate::from_ymd_opt(yr as i32, mon as u32, day as u32)
+            }
+            // Any non-leap year
+            _ => NaiveDate::from_yo_opt(
+                2001,
+                row.number(dj)?.map_or(DEFAULT_DAY_OF_YEAR, |dj| dj as u32),
+            ),
+        }
+        .ok_or_else(|| format!("line {}: invalid date", row.line))?;
+
+        let ws = row.number(ws)?.unwrap_or(0.);
+        let weather = Weather {
+            time: Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap()),
+            location: geo::Point::new(row.required_number(long)?, row.required_number(lat)?),
+            temp: 0.,
+            rh: 0.,
+            ws,
+            wd: row.number(wd)?.unwrap_or(0.),
+            precip: 0.,
+        };
+
+        let ffmc = row.required_number(ffmc)?;
+        let bui = row.required_number(bui)?;
+        let isi = match row.number(isi)? {
+            Some(isi) => isi,
+            None => initial_spread_index(ffmc, ws),
+        };
+        let fwi = HourlyFwiValues {
+            ffmc,
+            bui,
+            isi,
+            // Not used by the FBP system
+            dc: f64::NAN,
+            dmc: f64::NAN,
+            fwi: f64::NAN,
+        };
+
+        // C6 crown base height can be calculated from stand density and height. Otherwise 0
+        // leaves it to calculate_fbp(), which uses the fuel type's default
+        let cbh = match (row.number(cbh)?, row.number(sd)?, row.number(sh)?) {
+            (Some(cbh), _, _) if cbh > 0. => cbh,
+            (_, Some(sd), Some(sh)) if fuel == FbpFuelType::C6 && sd > 0. && sh > 0. => {
+                crown_base_height(fuel, sd, sh)
+            }
+            _ => 0.,
+        };
+
+        let options = FbpOptions {
+            elevation: row.number(elv)?,
+            date_of_minimum_fmc: row.number(d0)?.map(|d0| d0 as i32),
+            slope: row.number(gs)?.unwrap_or(defaults.slope),
+            aspect: row.number(aspect)?.unwrap_or(defaults.aspect),
+            curing: row.number(cc)?.unwrap_or(defaults.curing),
+            grass_fuel_load: row.number(gfl)?.unwrap_or(defaults.grass_fuel_load),
+            percent_conifer: row.number(pc)?.unwrap_or(defaults.percent_conifer),
+            percent_dead_balsam: row.number(pdf)?.unwrap_or(defaults.percent_dead_balsam),
+            crown_base_height: cbh,
+            ..defaults.clone()
+        };
+
+        let values = calculate_fbp(fuel, &fwi, &weather, options);
+
+        let fire_description = match fbp_fire_type(values.cfb) {
+            CrownFireType::Surface => "S",
+            CrownFireType::Passive => "I",
+            CrownFireType::Active => "C",
+        };
+
+        let mut out = vec![
+            row.text(id)
+                .map_or(Value::Number((index + 1) as f64), Value::parse),
+            values.cfb.into(),
+            values.cfc.into(),
+            fire_description.into(),
+            values.fi.into(),
+            values.raz.to_degrees().rem_euclid(360.).into(),
+            values.ros.into(),
+            values.sfc.into(),
+            values.tfc.into(),
+        ];
+        if args.values == FbpOutput::All {
+            out.extend(
+                [
+                    bui,
+                    ffmc,
+                    isi,
+                    values.fmc,
+                    values.fros,
+                    values.bros,
+                    values.cbh,
+                    values.cfl,
+                    fuel.length_to_breadth(values.wsv),
+                    values.wsv,
+                ]
+                .map(Value::from),
+            );
+        }
+        output.push(out);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fbp() -> Result<(), Box<dyn Error>> {
+        let input = "\
+ID,FuelType,LAT,LONG,FFMC,BUI,WS,WD,GS,Dj
+1,C-2,55,-120,90,60,20,270,0,180
+2,,55,-120,90,60,20,270,0,180
+3,D1,55,-120,90,60,20,270,0,180
+4,C6,55,-120,90,60,20,270,0,180
+";
+        let args = FbpArgs {
+            fuel: Some(FbpFuelType::C2),
+            values: FbpOutput::All,
+        };
+        let output = fbp(&InputTable::from_reader(input.as_bytes())?, &args)?;
+
+        assert_eq!(output.headers.len(), 19);
+        assert_eq!(output.rows[0][0], Value::Number(1.));
+
+        let ros = output.headers.iter().position(|h| h == "ROS").unwrap();
+        let raz = output.headers.iter().position(|h| h == "RAZ").unwrap();
+        let cfc = output.headers.iter().position(|h| h == "CFC").unwrap();
+        let cbh = output.headers.iter().position(|h| h == "CBH").unwrap();
+
+        // Falls back to --fuel
+        assert_eq!(output.rows[0][ros], output.rows[1][ros]);
+        assert_ne!(output.rows[0][ros], output.rows[2][ros]);
+
+        // Wind from the west spreads the fire east
+        assert_eq!(output.rows[0][raz], Value::Number(90.));
+        // Deciduous fuels have no crown fuel to consume
+        assert_eq!(output.rows[2][cfc], Value::Number(0.));
+        // The fuel type's default crown base height is used
+        assert_eq!(output.rows[3][cbh], Value::Number(7.));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fbp_without_fuel() -> Result<(), Box<dyn Error>> {
+        let input = InputTable::from_reader("LAT,LONG,FFMC,BUI\n55,-120,90,60\n".as_bytes())?;
+        let args = FbpArgs {
+            fuel: None,
+            values: FbpOutput::Primary,
+        };
+
+        assert!(fbp(&input, &args).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+//! `cffdrs fwi` - FWI values from daily or hourly weather
+
+use std::{collections::HashMap, error::Error};
+
+use cffdrs::{
+    fwi_system::{calculate_daily, calculate_hourly, StartingFwiValues},
+    weather::Weather,
+};
+use chrono::{TimeZone, Utc};
+use clap::Args;
+
+use crate::table::{InputTable, OutputTable, Value};
+
+#[derive(Args, Debug)]
+pub struct FwiArgs {
+    /// FFMC before the first record of each station
+    #[arg(long, default_value_t = 85.)]
+    pub start_ffmc: f64,
+    /// DMC before the first record of each station
+    #[arg(long, default_value_t = 6.)]
+    pub start_dmc: f64,
+    /// DC before the first record of each station
+    #[arg(long, default_value_t = 15.)]
+    pub start_dc: f64,
+}
+
+/// Calculate FWI values for each weather record
+///
+/// Input columns are named as in R cffdrs `fwi()`: `id` (optional), `lat`, `long`, `yr`, `mon`,
+/// `day`, `temp`, `rh`, `ws`, `wd` (optional) and `prec`. Records are calculated in order, with
+/// each station (`id`) carrying its FWI codes forward to its next record.
+///
+/// Without an `hr` column, records are daily noon weather and are calculated like R `fwi()` (see
+/// [calculate_daily()]). With an `hr` column, records are hourly weather (see
+/// [calculate_hourly()]).
+///
+/// The output contains the input columns, upper-cased, followed by `FFMC`, `DMC`, `DC`, `ISI`,
+/// `BUI` and `FWI`.
+pub fn fwi(input: &InputTable, args: &FwiArgs) -> Result<OutputTable, Box<dyn Error>> {
+    let id = input.column(&["id"]);
+    let lat = input.required_column(&["lat"])?;
+    let long = input.required_column(&["long", "lon"])?;
+    let yr = input.required_column(&["yr", "year"])?;
+    let mon = input.required_column(&["mon", "month"])?;
+    let day = input.required_column(&["day"])?;
+    let hr = input.column(&["hr", "hour"]);
+    let temp = input.required_column(&["temp"])?;
+    let rh = input.required_column(&["rh"])?;
+    let ws = input.required_column(&["ws"])?;
+    let wd = input.column(&["wd"]);
+    let prec = input.required_column(&["prec", "precip"])?;
+
+    let mut headers: Vec<String> = input.headers.iter().map(|h| h.to_uppercase()).collect();
+    headers.extend(["FFMC", "DMC", "DC", "ISI", "BUI", "FWI"].map(str::to_string));
+    let mut output = OutputTable::new(headers);
+
+    let start = StartingFwiValues {
+        ffmc: args.start_ffmc,
+        dmc: args.start_dmc,
+        dc: args.start_dc,
+    };
+    let mut previous: HashMap<Option<String>, StartingFwiValues> = HashMap::new();
+
+    for row in input.rows() {
+        let hour = match hr {
+            Some(hr) => row.required_number(hr)?,
+            None => 12.,
+        };
+        let time = Utc
+            .with_ymd_and_hms(
+                row.required_number(yr)? as i32,
+                row.required_number(mon)? as u32,
+                row.required_number(day)? as u32,
+                hour as u32,
+                0,
+                0,
+            )
+            .single()
+            .ok_or_else(|| format!("line {}: invalid date", row.line))?;
+
+        let weather = Weather {
+            time,
+            location: geo::Point::new(row.required_number(long)?, row.required_number(lat)?),
+            temp: row.required_number(temp)?,
+            rh: row.required_number(rh)?,
+            ws: row.required_number(ws)?,
+            wd: row.number(wd)?.unwrap_or(0.),
+            precip: row.required_number(prec)?,
+        };
+
+        let station = row.text(id).map(str::to_string);
+        let previous_values = previous.get(&station).unwrap_or(&start);
+        let values = match hr {
+            Some(_) => calculate_hourly(&weather, Some(previous_values)),
+            None => calculate_daily(&weather, Some(previous_values)),
+        };
+        previous.insert(station, values.as_starting_values());
+
+        let mut out: Vec<Value> = row.values().collect();
+        out.extend(
+            [
+                values.ffmc,
+                values.dmc,
+                values.dc,
+                values.isi,
+                values.bui,
+                values.fwi,
+            ]
+            .map(Value::from),
+        );
+        output.push(out);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> FwiArgs {
+        FwiArgs {
+            start_ffmc: 85.,
+            start_dmc: 6.,
+            start_dc: 15.,
+        }
+    }
+
+    #[test]
+    fn test_fwi() -> Result<(), Box<dyn Error>> {
+        let input = "\
+id,lat,long,yr,mon,day,temp,rh,ws,prec
+a,50.69,-120.34,2023,7,8,28,12,16,0
+b,50.69,-120.34,2023,7,8,28,12,16,0
+a,50.69,-120.34,2023,7,9,28,12,16,0
+";
+        let output = fwi(&InputTable::from_reader(input.as_bytes())?, &args())?;
+
+        assert_eq!(output.headers[0], "ID");
+        assert_eq!(output.headers.len(), 16);
+
+        let ffmc = output.headers.iter().position(|h| h == "FFMC").unwrap();
+        let dc = output.headers.iter().position(|h| h == "DC").unwrap();
+
+        // Each station carries its own codes forward
+        assert_eq!(output.rows[0][ffmc], output.rows[1][ffmc]);
+        assert_ne!(output.rows[0][ffmc], output.rows[2][ffmc]);
+
+        let start = StartingFwiValues {
+            ffmc: 85.,
+            dmc: 6.,
+            dc: 15.,
+        };
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 12, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 28.,
+            rh: 12.,
+            ws: 16.,
+            wd: 0.,
+            precip: 0.,
+        };
+        let expected = calculate_daily(&weather, Some(&start));
+        assert_eq!(output.rows[0][dc], Value::Number(expected.dc));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fwi_daily_matches_r() -> Result<(), Box<dyn Error>> {
+        // First record of the R cffdrs `test_fwi` dataset
+        let input = "\
+id,lat,long,yr,mon,day,temp,rh,ws,prec
+1,45.98,-77.57,2011,4,13,17,42,25,0
+";
+        let output = fwi(&InputTable::from_reader(input.as_bytes())?, &args())?;
+
+        // R `fwi(test_fwi)`, to the precision R prints
+        let expected = [
+            ("FFMC", 87.69298, 5e-6),
+            ("ISI", 10.853661, 5e-7),
+            ("FWI", 10.096371, 5e-7),
+        ];
+        for (header, value, tolerance) in expected {
+            let column = output.headers.iter().position(|h| h == header).unwrap();
+            let Value::Number(actual) = output.rows[0][column] else {
+                panic!("{header} is not a number");
+            };
+            assert!((actual - value).abs() < tolerance, "{header}: {actual}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fwi_hourly() -> Result<(), Box<dyn Error>> {
+        let input = "\
+lat,long,yr,mon,day,hr,temp,rh,ws,prec
+50.69,-120.34,2023,7,8,13,28,12,16,0
+";
+        let output = fwi(&InputTable::from_reader(input.as_bytes())?, &args())?;
+        let ffmc = output.headers.iter().position(|h| h == "FFMC").unwrap();
+
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 13, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 28.,
+            rh: 12.,
+            ws: 16.,
+            wd: 0.,
+            precip: 0.,
+        };
+        let start = StartingFwiValues {
+            ffmc: 85.,
+            dmc: 6.,
+            dc: 15.,
+        };
+        let expected = calculate_hourly(&weather, Some(&start));
+        assert_eq!(output.rows[0][ffmc], Value::Number(expected.ffmc));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fwi_missing_column() -> Result<(), Box<dyn Error>> {
+        let input = InputTable::from_reader("lat,long,yr,mon,day\n".as_bytes())?;
+        assert!(fwi(&input, &args()).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//! Command-line tool for calculating FWI and FBP values from CSV files
+//!
+//! Requires the `cli` feature.
+//!
+//! ```text
+//! cffdrs fwi --input weather.csv --start-ffmc 85 --start-dmc 6 --start-dc 15 > fwi.csv
+//! cffdrs fbp --fuel C2 --input fwi.csv --format json --output fbp.json
+//! ```
+
+mod fbp;
+mod fwi;
+mod table;
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand};
+
+use fbp::FbpArgs;
+use fwi::FwiArgs;
+use table::{Format, InputTable};
+
+/// Canadian Forest Fire Danger Rating System calculations
+#[derive(Parser, Debug)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Calculate FWI values from hourly weather, using R cffdrs fwi() column names
+    Fwi {
+        #[command(flatten)]
+        io: IoArgs,
+        #[command(flatten)]
+        args: FwiArgs,
+    },
+    /// Calculate FBP values from FWI values, using R cffdrs fbp() column names
+    Fbp {
+        #[command(flatten)]
+        io: IoArgs,
+        #[command(flatten)]
+        args: FbpArgs,
+    },
+}
+
+#[derive(Args, Debug)]
+struct IoArgs {
+    /// Input CSV file. Reads from stdin if not given
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+    /// Output file. Writes to stdout if not given
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+}
+
+impl IoArgs {
+    fn read_input(&self) -> Result<InputTable, Box<dyn Error>> {
+        let reader: Box<dyn Read> = match &self.input {
+            Some(path) => {
+                Box::new(BufReader::new(File::open(path).map_err(|err| {
+                    format!("failed to open {}: {err}", path.display())
+                })?))
+            }
+            None => Box::new(io::stdin().lock()),
+        };
+
+        InputTable::from_reader(reader)
+    }
+
+    fn writer(&self) -> Result<Box<dyn Write>, Box<dyn Error>> {
+        Ok(match &self.output {
+            Some(path) => {
+                Box::new(BufWriter::new(File::create(path).map_err(|err| {
+                    format!("failed to create {}: {err}", path.display())
+                })?))
+            }
+            None => Box::new(io::stdout().lock()),
+        })
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let (io, output) = match &cli.command {
+        Command::Fwi { io, args } => (io, fwi::fwi(&io.read_input()?, args)?),
+        Command::Fbp { io, args } => (io, fbp::fbp(&io.read_input()?, args)?),
+    };
+
+    let mut writer = io.writer()?;
+    output.write(&mut writer, io.format)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Reading input CSV tables and writing output tables as CSV or JSON
+
+use std::{error::Error, io};
+
+use clap::ValueEnum;
+use serde_json::{Map, Number, Value as JsonValue};
+
+/// Output file format
+#[derive(ValueEnum, PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum Format {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// An input CSV table, with columns looked up by case-insensitive name
+pub struct InputTable {
+    /// Column names, as they appear in the input
+    pub headers: Vec<String>,
+    rows: Vec<csv::StringRecord>,
+}
+
+impl InputTable {
+    pub fn from_reader<R: io::Read>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let headers = reader.headers()?.iter().map(str::to_string).collect();
+        let rows = reader.records().collect::<Result<_, _>>()?;
+
+        Ok(Self { headers, rows })
+    }
+
+    /// Index of the first column matching one of `names` (ignoring case)
+    pub fn column(&self, names: &[&str]) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| names.iter().any(|name| header.eq_ignore_ascii_case(name)))
+    }
+
+    /// Like [InputTable::column()], but an error if the column is missing
+    pub fn required_column(&self, names: &[&str]) -> Result<usize, Box<dyn Error>> {
+        self.column(names)
+            .ok_or_else(|| format!("input is missing a \"{}\" column", names[0]).into())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        self.rows.iter().enumerate().map(|(index, record)| Row {
+            // Line numbers start at 1, after the header
+            line: index + 2,
+            record,
+        })
+    }
+}
+
+/// A row of an [InputTable]
+pub struct Row<'a> {
+    pub line: usize,
+    record: &'a csv::StringRecord,
+}
+
+impl Row<'_> {
+    /// Text of a column, if the column exists and isn't empty
+    pub fn text(&self, column: Option<usize>) -> Option<&str> {
+        column
+            .and_then(|column| self.record.get(column))
+            .filter(|field| !field.is_empty() && !field.eq_ignore_ascii_case("NA"))
+    }
+
+    /// Number in a column, if the column exists and isn't empty
+    pub fn number(&self, column: Option<usize>) -> Result<Option<f64>, Box<dyn Error>> {
+        self.text(column)
+            .map(|field| {
+                field
+                    .parse::<f64>()
+                    .map_err(|_| format!("line {}: \"{field}\" is not a number", self.line).into())
+            })
+            .transpose()
+    }
+
+    /// Number in a column that must be present
+    pub fn required_number(&self, column: usize) -> Result<f64, Box<dyn Error>> {
+        self.number(Some(column))?
+            .ok_or_else(|| format!("line {}: missing value", self.line).into())
+    }
+
+    /// Every field in the row, as output values
+    pub fn values(&self) -> impl Iterator<Item = Value> + '_ {
+        self.record.iter().map(Value::parse)
+    }
+}
+
+/// A single output value
+#[derive(PartialEq, Clone, Debug)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    /// A number if `field` is numeric, otherwise text
+    pub fn parse(field: &str) -> Self {
+        match field.parse::<f64>() {
+            Ok(number) => Value::Number(number),
+            Err(_) => Value::Text(field.to_string()),
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+/// Output table, with the same columns in every row
+#[derive(Default)]
+pub struct OutputTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl OutputTable {
+    pub fn new(headers: Vec<String>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+        self.rows.push(row);
+    }
+
+    pub fn write<W: io::Write>(&self, writer: W, format: Format) -> Result<(), Box<dyn Error>> {
+        match format {
+            Format::Csv => self.write_csv(writer),
+            Format::Json => self.write_json(writer),
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: W) -> Result<(), Box<dyn Error>> {
+        let mut writer = csv::Writer::from_writer(writer);
+
+        writer.write_record(&self.headers)?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(|value| match value {
+                Value::Number(number) if number.is_nan() => "NA".to_string(),
+                Value::Number(number) => number.to_string(),
+                Value::Text(text) => text.clone(),
+            }))?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Write an array of objects, one per row
+    fn write_json<W: io::Write>(&self, mut writer: W) -> Result<(), Box<dyn Error>> {
+        let rows: Vec<JsonValue> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, JsonValue> = self
+                    .headers
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(|value| {
+                        match value {
+                            // Non-finite numbers can't be represented in JSON
+                            Value::Number(number) => Number::from_f64(*number)
+                                .map(JsonValue::Number)
+                                .unwrap_or(JsonValue::Null),
+                            Value::Text(text) => JsonValue::String(text.clone()),
+                        }
+                    }))
+                    .collect();
+                JsonValue::Object(object)
+            })
+            .collect();
+
+        serde_json::to_writer_pretty(&mut writer, &rows)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_table() -> Result<(), Box<dyn Error>> {
+        let table = InputTable::from_reader("ID, Temp ,rh\na, 20.5,NA\n".as_bytes())?;
+
+        assert_eq!(table.column(&["temp"]), Some(1));
+        assert_eq!(table.column(&["ws"]), None);
+        assert!(table.required_column(&["ws"]).is_err());
+
+        let row = table.rows().next().unwrap();
+        assert_eq!(row.line, 2);
+        assert_eq!(row.number(Some(1))?, Some(20.5));
+        assert_eq!(row.number(Some(2))?, None);
+        assert!(row.number(Some(0)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_table() -> Result<(), Box<dyn Error>> {
+        let mut table = OutputTable::new(vec!["ID".to_string(), "ROS".to_string()]);
+        table.push(vec!["a".into(), 1.5.into()]);
+        table.push(vec!["b".into(), f64::NAN.into()]);
+
+        let mut csv = Vec::new();
+        table.write(&mut csv, Format::Csv)?;
+        assert_eq!(String::from_utf8(csv)?, "ID,ROS\na,1.5\nb,NA\n");
+
+        let mut json = Vec::new();
+        table.write(&mut json, Format::Json)?;
+        let json: JsonValue = serde_json::from_slice(&json)?;
+        assert_eq!(
+            json,
+            serde_json::json!([{"ID": "a", "ROS": 1.5}, {"ID": "b", "ROS": null}])
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,605 @@
+//! Ensemble (Monte Carlo) FWI and FBP calculations for uncertain weather
+//!
+//! Samples weather and starting FWI codes from distributions or ensemble members, calculates
+//! [calculate_hourly()] and [calculate_fbp()] for each sample, and summarizes the results as
+//! percentiles and fire type probabilities.
+//!
+//! Samples are drawn from a PCG random number generator seeded with [`EnsembleOptions::seed`], so
+//! results are reproducible across runs and platforms.
+//!
+//! Requires the `ensemble` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use cffdrs::ensemble::{
+//!     calculate_ensemble, EnsembleOptions, EnsembleStartingValues, EnsembleWeather,
+//!     InputDistribution,
+//! };
+//! use cffdrs::fbp_system::{crown_fire::CrownFireType, FbpFuelType, FbpOptions};
+//! use chrono::prelude::*;
+//!
+//! let weather = EnsembleWeather {
+//!     time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+//!     location: geo::Point::new(-120.34, 50.69),
+//!     temp: InputDistribution::Normal { mean: 28., std_dev: 2. },
+//!     rh: InputDistribution::Normal { mean: 20., std_dev: 5. },
+//!     // Wind speed and direction members from a forecast ensemble, sampled together
+//!     ws: InputDistribution::Members(vec![10., 15., 25., 30.]),
+//!     wd: InputDistribution::Members(vec![250., 270., 280., 300.]),
+//!     precip: InputDistribution::Constant(0.),
+//! };
+//! let start = EnsembleStartingValues {
+//!     ffmc: InputDistribution::Uniform { min: 86., max: 90. },
+//!     dmc: InputDistribution::Constant(40.),
+//!     dc: InputDistribution::Constant(300.),
+//! };
+//! let options = FbpOptions { crown_base_height: 3., ..Default::default() };
+//!
+//! let result = calculate_ensemble(
+//!     FbpFuelType::C2,
+//!     &weather,
+//!     &start,
+//!     &options,
+//!     &EnsembleOptions { samples: 500, seed: 42 },
+//! )?;
+//!
+//! assert!(result.ros.percentile(90.) > result.ros.median());
+//! assert!(result.fire_type_probability(CrownFireType::Active) > 0.);
+//! # Ok::<(), cffdrs::ensemble::EnsembleError>(())
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use geo::Point;
+use rand::{RngExt, SeedableRng};
+use rand_distr::{Distribution, Normal, Triangular};
+use rand_pcg::Pcg64Mcg;
+
+use crate::{
+    fbp_system::{
+        calculate_fbp,
+        crown_fire::{fbp_fire_type, CrownFireType},
+        FbpFuelType, FbpOptions,
+    },
+    fwi_system::{calculate_hourly, StartingFwiValues},
+    weather::Weather,
+};
+
+/// Distribution of an uncertain input
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputDistribution {
+    /// A known value
+    Constant(f64),
+    /// Normal distribution
+    Normal { mean: f64, std_dev: f64 },
+    /// Uniform distribution between `min` and `max`
+    Uniform { min: f64, max: f64 },
+    /// Triangular distribution between `min` and `max`, peaking at `mode`
+    Triangular { min: f64, mode: f64, max: f64 },
+    /// Members of an ensemble forecast, each equally likely. Inputs with the same number of
+    /// members are sampled together, so each sample takes all of them from the same member.
+    /// Inputs with different numbers of members are sampled independently
+    Members(Vec<f64>),
+}
+
+/// Hourly weather with uncertain values, see [Weather]
+#[derive(Debug, Clone)]
+pub struct EnsembleWeather {
+    /// UTC time of the weather
+    pub time: DateTime<Utc>,
+    /// Location of the weather
+    pub location: Point<f64>,
+    /// Temperature (Celcius)
+    pub temp: InputDistribution,
+    /// Relative humidity (%), limited to 0-100
+    pub rh: InputDistribution,
+    /// Wind speed (at 10m height, km/h), limited to 0 or more
+    pub ws: InputDistribution,
+    /// Wind direction (degrees, 0 = wind from north, 90 = from east, etc.)
+    pub wd: InputDistribution,
+    /// Precipitation (mm), limited to 0 or more
+    pub precip: InputDistribution,
+}
+
+/// Uncertain FWI codes from the previous hour, see [StartingFwiValues]
+#[derive(Debug, Clone)]
+pub struct EnsembleStartingValues {
+    /// FFMC, limited to 0-101
+    pub ffmc: InputDistribution,
+    /// DMC, limited to 0 or more
+    pub dmc: InputDistribution,
+    /// DC, limited to 0 or more
+    pub dc: InputDistribution,
+}
+
+/// Sampling options for [calculate_ensemble()]
+#[derive(Debug, Clone)]
+pub struct EnsembleOptions {
+    /// Number of samples to calculate
+    pub samples: usize,
+    /// Random number generator seed. The same seed and inputs always give the same results
+    pub seed: u64,
+}
+
+impl Default for EnsembleOptions {
+    fn default() -> Self {
+        Self {
+            samples: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// Error returned when an ensemble can't be calculated
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnsembleError {
+    /// An [InputDistribution]'s parameters are invalid
+    InvalidDistribution {
+        /// Name of the input (e.g. `"temp"`)
+        input: &'static str,
+        /// Why the parameters are invalid
+        reason: String,
+    },
+    /// [`EnsembleOptions::samples`] is 0
+    NoSamples,
+}
+
+impl fmt::Display for EnsembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnsembleError::InvalidDistribution { input, reason } => {
+                write!(f, "invalid distribution for {input}: {reason}")
+            }
+            EnsembleError::NoSamples => write!(f, "ensemble must have at least 1 sample"),
+        }
+    }
+}
+
+impl std::error::Error for EnsembleError {}
+
+/// Sampled values of an output, sorted in ascending order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Samples {
+    values: Vec<f64>,
+}
+
+impl Samples {
+    fn new(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        Self { values }
+    }
+
+    /// The sampled values, sorted in ascending order
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Percentile (0-100), linearly interpolated between samples
+    pub fn percentile(&self, percentile: f64) -> f64 {
+        let position = (percentile / 100.).clamp(0., 1.) * (self.values.len() - 1) as f64;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+
+        self.values[lower] + (self.values[upper] - self.values[lower]) * (position - lower as f64)
+    }
+
+    /// 50th percentile
+    pub fn median(&self) -> f64 {
+        self.percentile(50.)
+    }
+
+    /// Mean of the samples
+    pub fn mean(&self) -> f64 {
+        self.values.iter().sum::<f64>() / self.values.len() as f64
+    }
+}
+
+/// Summary of an ensemble's FBP results
+#[derive(Debug, Clone)]
+pub struct EnsembleResult {
+    /// Head rate of spread (m/min)
+    pub ros: Samples,
+    /// Head fire intensity (kW/m)
+    pub hfi: Samples,
+    /// Crown fraction burned (0-1)
+    pub cfb: Samples,
+}
+
+impl EnsembleResult {
+    /// Fraction of samples (0-1) with the given fire type (see [fbp_fire_type()])
+    pub fn fire_type_probability(&self, fire_type: CrownFireType) -> f64 {
+        let count = self
+            .cfb
+            .values()
+            .iter()
+            .filter(|&&cfb| fbp_fire_type(cfb) == fire_type)
+            .count();
+
+        count as f64 / self.cfb.values().len() as f64
+    }
+}
+
+/// Calculate FWI and FBP values for samples of uncertain weather and starting FWI codes
+pub fn calculate_ensemble(
+    fuel_type: FbpFuelType,
+    weather: &EnsembleWeather,
+    start: &EnsembleStartingValues,
+    options: &FbpOptions,
+    ensemble: &EnsembleOptions,
+) -> Result<EnsembleResult, EnsembleError> {
+    if ensemble.samples == 0 {
+        return Err(EnsembleError::NoSamples);
+    }
+
+    let temp = Sampler::new("temp", &weather.temp)?;
+    let rh = Sampler::new("rh", &weather.rh)?;
+    let ws = Sampler::new("ws", &weather.ws)?;
+    let wd = Sampler::new("wd", &weather.wd)?;
+    let precip = Sampler::new("precip", &weather.precip)?;
+    let ffmc = Sampler::new("ffmc", &start.ffmc)?;
+    let dmc = Sampler::new("dmc", &start.dmc)?;
+    let dc = Sampler::new("dc", &start.dc)?;
+
+    let mut rng = Pcg64Mcg::seed_from_u64(ensemble.seed);
+    let mut ros = Vec::with_capacity(ensemble.samples);
+    let mut hfi = Vec::with_capacity(ensemble.samples);
+    let mut cfb = Vec::with_capacity(ensemble.samples);
+
+    for _ in 0..ensemble.samples {
+        // Shared by inputs with the same number of members, so they're sampled together
+        let mut members = MemberIndices::default();
+
+        let sample_weather = Weather {
+            time: weather.time,
+            location: weather.location,
+            temp: temp.sample(&mut rng, &mut members),
+            rh: rh.sample(&mut rng, &mut members).clamp(0., 100.),
+            ws: ws.sample(&mut rng, &mut members).max(0.),
+            wd: wd.sample(&mut rng, &mut members).rem_euclid(360.),
+            precip: precip.sample(&mut rng, &mut members).max(0.),
+        };
+        let sample_start = StartingFwiValues {
+            ffmc: ffmc.sample(&mut rng, &mut members).clamp(0., 101.),
+            dmc: dmc.sample(&mut rng, &mut members).max(0.),
+            dc: dc.sample(&mut rng, &mut members).max(0.),
+        };
+
+        let fwi = calculate_hourly(&sample_weather, Some(&sample_start));
+        let fbp = calculate_fbp(fuel_type, &fwi, &sample_weather, options.clone());
+
+        ros.push(fbp.ros);
+        hfi.push(fbp.fi);
+        cfb.push(fbp.cfb);
+    }
+
+    Ok(EnsembleResult {
+        ros: Samples::new(ros),
+        hfi: Samples::new(hfi),
+        cfb: Samples::new(cfb),
+    })
+}
+
+/// A validated [InputDistribution]
+enum Sampler<'a> {
+    Constant(f64),
+    Normal(Normal<f64>),
+    Uniform { min: f64, max: f64 },
+    Triangular(Triangular<f64>),
+    Members(&'a [f64]),
+}
+
+impl<'a> Sampler<'a> {
+    fn new(
+        input: &'static str,
+        distribution: &'a InputDistribution,
+    ) -> Result<Self, EnsembleError> {
+        let error = |reason: String| EnsembleError::InvalidDistribution { input, reason };
+
+        Ok(match distribution {
+            InputDistribution::Constant(value) => Sampler::Constant(*value),
+            InputDistribution::Normal { mean, std_dev } => {
+                if *std_dev < 0. || std_dev.is_nan() {
+                    return Err(error(format!("std_dev ({std_dev}) must not be negative")));
+                }
+                Sampler::Normal(Normal::new(*mean, *std_dev).map_err(|err| error(err.to_string()))?)
+            }
+            InputDistribution::Uniform { min, max } => {
+                if !min.is_finite() || !max.is_finite() || min > max {
+                    return Err(error(format!(
+                        "min ({min}) and max ({max}) must be finite, with min not above max"
+                    )));
+                }
+                Sampler::Uniform {
+                    min: *min,
+                    max: *max,
+                }
+            }
+            InputDistribution::Triangular { min, mode, max } => Sampler::Triangular(
+                Triangular::new(*min, *max, *mode).map_err(|err| error(err.to_string()))?,
+            ),
+            InputDistribution::Members(members) => {
+                if members.is_empty() {
+                    return Err(error("no members".to_string()));
+                }
+                Sampler::Members(members)
+            }
+        })
+    }
+
+    /// Sample a value, choosing ensemble members from `members`
+    fn sample(&self, rng: &mut Pcg64Mcg, members: &mut MemberIndices) -> f64 {
+        match self {
+            Sampler::Constant(value) => *value,
+            Sampler::Normal(normal) => normal.sample(rng),
+            Sampler::Uniform { min, max } => min + (max - min) * rng.random::<f64>(),
+            Sampler::Triangular(triangular) => triangular.sample(rng),
+            Sampler::Members(values) => values[members.index(rng, values.len())],
+        }
+    }
+}
+
+/// Ensemble member chosen for each number of members in a sample
+#[derive(Default)]
+struct MemberIndices(Vec<(usize, usize)>);
+
+impl MemberIndices {
+    /// Index of the member (below `count`) for inputs with `count` members
+    fn index(&mut self, rng: &mut Pcg64Mcg, count: usize) -> usize {
+        match self.0.iter().find(|&&(members, _)| members == count) {
+            Some(&(_, index)) => index,
+            None => {
+                let index = rng.random_range(0..count);
+                self.0.push((count, index));
+                index
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn weather() -> EnsembleWeather {
+        EnsembleWeather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            location: Point::new(-120.34, 50.69),
+            temp: InputDistribution::Normal {
+                mean: 28.,
+                std_dev: 2.,
+            },
+            rh: InputDistribution::Triangular {
+                min: 10.,
+                mode: 20.,
+                max: 40.,
+            },
+            ws: InputDistribution::Members(vec![5., 15., 25., 35.]),
+            wd: InputDistribution::Members(vec![180., 200., 220., 240.]),
+            precip: InputDistribution::Constant(0.),
+        }
+    }
+
+    fn start() -> EnsembleStartingValues {
+        EnsembleStartingValues {
+            ffmc: InputDistribution::Uniform { min: 85., max: 91. },
+            dmc: InputDistribution::Constant(40.),
+            dc: InputDistribution::Constant(300.),
+        }
+    }
+
+    fn options() -> FbpOptions {
+        FbpOptions {
+            crown_base_height: 3.,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_seeded_results_are_reproducible() {
+        let ensemble = EnsembleOptions {
+            samples: 200,
+            seed: 7,
+        };
+        let run = |ensemble: &EnsembleOptions| {
+            calculate_ensemble(FbpFuelType::C3, &weather(), &start(), &options(), ensemble).unwrap()
+        };
+
+        let first = run(&ensemble);
+        assert_eq!(first.ros, run(&ensemble).ros);
+        assert_ne!(
+            first.ros,
+            run(&EnsembleOptions {
+                seed: 8,
+                ..ensemble
+            })
+            .ros
+        );
+    }
+
+    #[test]
+    fn test_constant_inputs_match_single_calculation() {
+        let constant = InputDistribution::Constant;
+        let weather = EnsembleWeather {
+            temp: constant(28.),
+            rh: constant(20.),
+            ws: constant(15.),
+            wd: constant(200.),
+            ..weather()
+        };
+        let start = EnsembleStartingValues {
+            ffmc: constant(88.),
+            ..start()
+        };
+        let result = calculate_ensemble(
+            FbpFuelType::C3,
+            &weather,
+            &start,
+            &options(),
+            &EnsembleOptions {
+                samples: 10,
+                seed: 0,
+            },
+        )
+        .unwrap();
+
+        let sample_weather = Weather {
+            time: weather.time,
+            location: weather.location,
+            temp: 28.,
+            rh: 20.,
+            ws: 15.,
+            wd: 200.,
+            precip: 0.,
+        };
+        let fwi = calculate_hourly(
+            &sample_weather,
+            Some(&StartingFwiValues {
+                ffmc: 88.,
+                dmc: 40.,
+                dc: 300.,
+            }),
+        );
+        let fbp = calculate_fbp(FbpFuelType::C3, &fwi, &sample_weather, options());
+
+        assert_eq!(result.ros.percentile(0.), fbp.ros);
+        assert_eq!(result.ros.percentile(100.), fbp.ros);
+        assert_eq!(result.hfi.median(), fbp.fi);
+        assert_eq!(result.fire_type_probability(fbp_fire_type(fbp.cfb)), 1.);
+    }
+
+    #[test]
+    fn test_ensemble_summary() {
+        let result = calculate_ensemble(
+            FbpFuelType::C3,
+            &weather(),
+            &start(),
+            &options(),
+            &EnsembleOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.ros.values().len(), 1000);
+        assert!(result.ros.percentile(10.) < result.ros.median());
+        assert!(result.ros.median() < result.ros.percentile(90.));
+        assert!(result.hfi.percentile(10.) < result.hfi.percentile(90.));
+
+        let probabilities: f64 = [
+            CrownFireType::Surface,
+            CrownFireType::Passive,
+            CrownFireType::Active,
+        ]
+        .into_iter()
+        .map(|fire_type| result.fire_type_probability(fire_type))
+        .sum();
+        assert!((probabilities - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_members_are_sampled_together() {
+        // Every sample with the strongest wind member has the strongest wind direction member,
+        // so ROS only takes 4 distinct values
+        let weather = EnsembleWeather {
+            temp: InputDistribution::Constant(28.),
+            rh: InputDistribution::Constant(20.),
+            ..weather()
+        };
+        let start = EnsembleStartingValues {
+            ffmc: InputDistribution::Constant(88.),
+            ..start()
+        };
+        let result = calculate_ensemble(
+            FbpFuelType::C3,
+            &weather,
+            &start,
+            &options(),
+            &EnsembleOptions::default(),
+        )
+        .unwrap();
+
+        let mut distinct = result.ros.values().to_vec();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 4);
+    }
+
+    #[test]
+    fn test_different_member_counts_are_independent() {
+        // 3 temperature members and 4 wind speed members give all 12 combinations
+        let weather = EnsembleWeather {
+            temp: InputDistribution::Members(vec![20., 25., 30.]),
+            rh: InputDistribution::Constant(20.),
+            wd: InputDistribution::Constant(200.),
+            ..weather()
+        };
+        let start = EnsembleStartingValues {
+            ffmc: InputDistribution::Constant(88.),
+            ..start()
+        };
+        let result = calculate_ensemble(
+            FbpFuelType::C3,
+            &weather,
+            &start,
+            &options(),
+            &EnsembleOptions::default(),
+        )
+        .unwrap();
+
+        let mut distinct = result.ros.values().to_vec();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 12);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples = Samples::new(vec![4., 1., 3., 2.]);
+
+        assert_eq!(samples.values(), &[1., 2., 3., 4.]);
+        assert_eq!(samples.percentile(0.), 1.);
+        assert_eq!(samples.median(), 2.5);
+        assert_eq!(samples.percentile(100.), 4.);
+        assert_eq!(samples.mean(), 2.5);
+    }
+
+    #[test]
+    fn test_invalid_distribution() {
+        let weather = EnsembleWeather {
+            ws: InputDistribution::Normal {
+                mean: 10.,
+                std_dev: -1.,
+            },
+            ..weather()
+        };
+        let err = calculate_ensemble(
+            FbpFuelType::C3,
+            &weather,
+            &start(),
+            &options(),
+            &EnsembleOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            EnsembleError::InvalidDistribution { input: "ws", .. }
+        ));
+    }
+
+    #[test]
+    fn test_no_samples() {
+        let result = calculate_ensemble(
+            FbpFuelType::C3,
+            &weather(),
+            &start(),
+            &options(),
+            &EnsembleOptions {
+                samples: 0,
+                seed: 0,
+            },
+        );
+
+        assert_eq!(result.unwrap_err(), EnsembleError::NoSamples);
+    }
+}
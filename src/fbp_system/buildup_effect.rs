@@ -1,26 +1,22 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{c, Float};
 
 use super::FbpFuelType;
 
-/// Calculate the buildup effect on fire spread rate
-///
-/// * `bui` - Buildup index value
-///
-/// Returns the buildup effect
-///
-/// ```
-/// # use cffdrs::fbp_system::{buildup_effect, FbpFuelType};
-/// # {
-/// let bui = 13.5;
-/// let buildup_effect = buildup_effect(FbpFuelType::C3, bui);
-/// assert_eq!(buildup_effect, 0.43453121924260246);
-/// # }
-///
-/// assert_eq!(buildup_effect(FbpFuelType::O1a, 10.8), 1.);
-/// assert_eq!(buildup_effect(FbpFuelType::S3, 13.5), 0.5479968092625566);
-/// ```
-pub fn buildup_effect(fuel_type: FbpFuelType, bui: f64) -> f64 {
-    let bui_avg: f64 = match fuel_type {
+/// Fuel type-specific coefficients for the buildup effect
+pub struct BuildupEffectParams {
+    /// Average BUI for the fuel type
+    pub bui0: f64,
+    /// Proportion of maximum rate of spread at BUI equal to 50
+    pub q: f64,
+    /// Maximum buildup effect
+    pub max: f64,
+}
+
+/// Fuel type-specific coefficients for the buildup effect
+pub fn buildup_effect_params(fuel_type: FbpFuelType) -> BuildupEffectParams {
+    let bui0: f64 = match fuel_type {
         FbpFuelType::C1 => 72.,
         FbpFuelType::C2 => 64.,
         FbpFuelType::C3 => 62.,
@@ -28,7 +24,7 @@ pub fn buildup_effect(fuel_type: FbpFuelType, bui: f64) -> f64 {
         FbpFuelType::C5 => 56.,
         FbpFuelType::C6 => 62.,
         FbpFuelType::C7 => 106.,
-        FbpFuelType::D1 => 32.,
+        FbpFuelType::D1 | FbpFuelType::D2 => 32.,
         FbpFuelType::M1 => 50.,
         FbpFuelType::M2 => 50.,
         FbpFuelType::M3 => 50.,
@@ -48,7 +44,7 @@ pub fn buildup_effect(fuel_type: FbpFuelType, bui: f64) -> f64 {
         FbpFuelType::C5 => 0.8,
         FbpFuelType::C6 => 0.8,
         FbpFuelType::C7 => 0.85,
-        FbpFuelType::D1 => 0.9,
+        FbpFuelType::D1 | FbpFuelType::D2 => 0.9,
         FbpFuelType::M1 => 0.8,
         FbpFuelType::M2 => 0.8,
         FbpFuelType::M3 => 0.8,
@@ -68,7 +64,7 @@ pub fn buildup_effect(fuel_type: FbpFuelType, bui: f64) -> f64 {
         FbpFuelType::C5 => 1.220,
         FbpFuelType::C6 => 1.197,
         FbpFuelType::C7 => 1.134,
-        FbpFuelType::D1 => 1.179,
+        FbpFuelType::D1 | FbpFuelType::D2 => 1.179,
         FbpFuelType::M1 => 1.25,
         FbpFuelType::M2 => 1.25,
         FbpFuelType::M3 => 1.25,
@@ -81,11 +77,41 @@ pub fn buildup_effect(fuel_type: FbpFuelType, bui: f64) -> f64 {
         FbpFuelType::NonFuel => 0.,
     };
 
-    if bui > 0. && bui_avg > 0. {
-        consts::E
-            .powf(50. * q.ln() * (1. / bui - 1. / bui_avg))
-            .min(max)
+    BuildupEffectParams { bui0, q, max }
+}
+
+/// Calculate the buildup effect on fire spread rate
+///
+/// * `bui` - Buildup index value
+///
+/// Returns the buildup effect
+///
+/// ```
+/// # use cffdrs::fbp_system::{buildup_effect, FbpFuelType};
+/// # {
+/// let bui = 13.5;
+/// let buildup_effect = buildup_effect(FbpFuelType::C3, bui);
+/// assert_eq!(buildup_effect, 0.43453121924260246);
+/// # }
+///
+/// assert_eq!(buildup_effect(FbpFuelType::O1a, 10.8), 1.);
+/// assert_eq!(buildup_effect(FbpFuelType::S3, 13.5), 0.5479968092625566);
+/// ```
+pub fn buildup_effect<T: Float>(fuel_type: FbpFuelType, bui: T) -> T {
+    let params = buildup_effect_params(fuel_type);
+
+    buildup_effect_with_params(&params, bui)
+}
+
+/// Calculate the buildup effect from explicit coefficients (see [buildup_effect()])
+pub(crate) fn buildup_effect_with_params<T: Float>(params: &BuildupEffectParams, bui: T) -> T {
+    if bui > T::zero() && params.bui0 > 0. {
+        c::<T>(consts::E)
+            .powf(
+                c::<T>(50.) * c::<T>(params.q).ln() * (T::one() / bui - T::one() / c(params.bui0)),
+            )
+            .min(c(params.max))
     } else {
-        1.
+        T::one()
     }
 }
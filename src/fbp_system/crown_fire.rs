@@ -0,0 +1,351 @@
+//! Crown fire initiation and spread models, independent of the FBP system's crown fraction burned
+//!
+//! These can be used to cross-check FBP crowning predictions (see [fbp_fire_type()]).
+//!
+//! References:
+//!
+//! Van Wagner, C.E. 1977. Conditions for the start and spread of crown fire. Canadian Journal of
+//! Forest Research 7: 23-34.
+//!
+//! Cruz, M.G.; Alexander, M.E.; Wakimoto, R.H. 2004. Modeling the likelihood of crown fire
+//! occurrence in conifer forest stands. Forest Science 50(5): 640-658.
+//!
+//! Cruz, M.G.; Alexander, M.E.; Wakimoto, R.H. 2005. Development and testing of models for
+//! predicting crown fire rate of spread in conifer forest stands. Canadian Journal of Forest
+//! Research 35: 1626-1639.
+
+use crate::float::Float;
+
+use core::f64::consts::E;
+
+use super::{
+    ros::{csi, rate_of_spread_extended, rso},
+    surface_fuel_consumption, FbpFuelType,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Type of fire
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum CrownFireType {
+    /// Surface fire
+    Surface,
+    /// Passive (intermittent) crown fire
+    Passive,
+    /// Active (continuous) crown fire
+    Active,
+}
+
+/// Critical surface fire intensity for crown fire initiation (Van Wagner 1977)
+///
+/// * `fmc` - Foliar moisture content (%)
+/// * `cbh` - Crown base height (m)
+///
+/// Returns critical surface intensity (kW/m)
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::critical_surface_intensity;
+/// assert_eq!(critical_surface_intensity(100., 5.), 1883.2360778723416);
+/// ```
+pub fn critical_surface_intensity(fmc: f64, cbh: f64) -> f64 {
+    csi(fmc, cbh)
+}
+
+/// Critical rate of spread for active crown fire (Van Wagner 1977)
+///
+/// Uses a critical mass flow rate of 3.0 kg/m^2/min through the canopy
+///
+/// * `cbd` - Canopy bulk density (kg/m^3)
+///
+/// Returns critical rate of spread (m/min)
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::critical_active_rate_of_spread;
+/// assert_eq!(critical_active_rate_of_spread(0.15), 20.);
+/// ```
+pub fn critical_active_rate_of_spread(cbd: f64) -> f64 {
+    3.0 / cbd
+}
+
+/// Classify fire type using Van Wagner's (1977) criteria for crown fire initiation and
+/// active crown fire spread
+///
+/// * `fi` - Surface fire intensity (kW/m)
+/// * `ros` - Rate of spread (m/min)
+/// * `fmc` - Foliar moisture content (%)
+/// * `cbh` - Crown base height (m)
+/// * `cbd` - Canopy bulk density (kg/m^3)
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::{van_wagner_fire_type, CrownFireType};
+/// assert_eq!(van_wagner_fire_type(800., 5., 100., 5., 0.15), CrownFireType::Surface);
+/// assert_eq!(van_wagner_fire_type(2000., 10., 100., 5., 0.15), CrownFireType::Passive);
+/// assert_eq!(van_wagner_fire_type(2000., 25., 100., 5., 0.15), CrownFireType::Active);
+/// ```
+pub fn van_wagner_fire_type(fi: f64, ros: f64, fmc: f64, cbh: f64, cbd: f64) -> CrownFireType {
+    if fi < critical_surface_intensity(fmc, cbh) {
+        CrownFireType::Surface
+    } else if ros < critical_active_rate_of_spread(cbd) {
+        CrownFireType::Passive
+    } else {
+        CrownFireType::Active
+    }
+}
+
+/// Fire type from FBP crown fraction burned
+///
+/// * `cfb` - Crown fraction burned (0-1)
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::{fbp_fire_type, CrownFireType};
+/// assert_eq!(fbp_fire_type(0.05), CrownFireType::Surface);
+/// assert_eq!(fbp_fire_type(0.5), CrownFireType::Passive);
+/// assert_eq!(fbp_fire_type(0.95), CrownFireType::Active);
+/// ```
+pub fn fbp_fire_type(cfb: f64) -> CrownFireType {
+    if cfb < 0.1 {
+        CrownFireType::Surface
+    } else if cfb < 0.9 {
+        CrownFireType::Passive
+    } else {
+        CrownFireType::Active
+    }
+}
+
+/// Estimate fine fuel moisture content from FFMC
+///
+/// * `ffmc` - Fine fuel moisture code
+///
+/// Returns moisture content (%)
+pub fn fine_fuel_moisture_content(ffmc: f64) -> f64 {
+    147.27723 * (101. - ffmc) / (59.5 + ffmc)
+}
+
+/// Probability of crown fire occurrence (CFIS, Cruz et al. 2004)
+///
+/// * `ws` - 10m open wind speed (km/h)
+/// * `fsg` - Fuel strata gap (m), the distance from the top of the surface fuel to the bottom of
+///   the canopy. Crown base height is a reasonable approximation
+/// * `effm` - Estimated fine fuel moisture (%) (see [fine_fuel_moisture_content()])
+/// * `sfc` - Surface fuel consumption (kg/m^2)
+///
+/// Returns probability of crown fire (0-1)
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::crown_fire_initiation_probability;
+/// let p = crown_fire_initiation_probability(20., 4., 8., 2.5);
+/// assert_eq!(p, 0.9972351494050785);
+///
+/// assert_eq!(crown_fire_initiation_probability(10., 8., 12., 0.5), 0.0015639116381142902);
+/// ```
+pub fn crown_fire_initiation_probability(ws: f64, fsg: f64, effm: f64, sfc: f64) -> f64 {
+    // Surface fuel consumption class
+    let delta = if sfc < 1. {
+        -4.613
+    } else if sfc <= 2. {
+        -1.856
+    } else {
+        0.
+    };
+
+    let g = 4.236 + 0.357 * ws - 0.71 * fsg - 0.331 * effm + delta;
+
+    Float::powf(E, g) / (1. + Float::powf(E, g))
+}
+
+/// Crown fire rate of spread predicted by CFIS
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct CrownFireSpread {
+    /// Crown fire rate of spread (m/min)
+    pub ros: f64,
+    /// Active crown fire rate of spread (m/min), regardless of whether the fire is active
+    pub active_ros: f64,
+    /// Criterion for active crowning. Values of 1 or more indicate active crown fire
+    pub cac: f64,
+    /// Passive or active crown fire
+    pub fire_type: CrownFireType,
+}
+
+/// Crown fire rate of spread (CFIS, Cruz et al. 2005)
+///
+/// Assumes a crown fire has initiated (see [crown_fire_initiation_probability()])
+///
+/// * `ws` - 10m open wind speed (km/h)
+/// * `cbd` - Canopy bulk density (kg/m^3)
+/// * `effm` - Estimated fine fuel moisture (%) (see [fine_fuel_moisture_content()])
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::{crown_fire_rate_of_spread, CrownFireType};
+/// let spread = crown_fire_rate_of_spread(20., 0.15, 8.);
+/// assert_eq!(spread.fire_type, CrownFireType::Active);
+/// assert_eq!(spread.ros, 29.236503628845565);
+///
+/// let spread = crown_fire_rate_of_spread(10., 0.05, 10.);
+/// assert_eq!(spread.fire_type, CrownFireType::Passive);
+/// assert_eq!(spread.ros, 7.7834771788421735);
+/// ```
+pub fn crown_fire_rate_of_spread(ws: f64, cbd: f64, effm: f64) -> CrownFireSpread {
+    let active_ros =
+        11.02 * Float::powf(ws, 0.90) * Float::powf(cbd, 0.19) * Float::powf(E, -0.17 * effm);
+    let cac = active_ros / critical_active_rate_of_spread(cbd);
+
+    if cac >= 1. {
+        CrownFireSpread {
+            ros: active_ros,
+            active_ros,
+            cac,
+            fire_type: CrownFireType::Active,
+        }
+    } else {
+        CrownFireSpread {
+            ros: active_ros * Float::powf(E, -cac),
+            active_ros,
+            cac,
+            fire_type: CrownFireType::Passive,
+        }
+    }
+}
+
+/// Highest ISI searched by [crowning_thresholds()]
+const MAX_THRESHOLD_ISI: f64 = 200.;
+
+/// Crowning thresholds for a stand at a fixed BUI (see [crowning_thresholds()])
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct CrowningThresholds {
+    /// Surface fuel consumption (kg/m^2)
+    pub sfc: f64,
+    /// Critical surface fire intensity for crown fire initiation (kW/m)
+    pub csi: f64,
+    /// Critical surface fire rate of spread for crown fire initiation (m/min)
+    pub rso: f64,
+    /// ISI at which crowning begins (CFB above 0), or `None` if it doesn't by ISI 200
+    pub initiation_isi: Option<f64>,
+    /// ISI at which CFB reaches 0.9 (active crown fire), or `None` if it doesn't by ISI 200
+    pub active_isi: Option<f64>,
+}
+
+/// Critical surface intensity, critical surface rate of spread, and the ISI at which crowning
+/// begins and becomes active (CFB of 0.9) for a fuel type at a given BUI
+///
+/// Surface fuel consumption is calculated from `bui` (and `ffmc`, `pc` and `gfl` for the fuel
+/// types that use them, see [surface_fuel_consumption()][super::surface_fuel_consumption()]),
+/// as [calculate_fbp()][crate::fbp_system::calculate_fbp()] does. FFMC is held fixed while ISI
+/// varies, as if only the wind changes.
+///
+/// * `fuel_type` - FBP fuel type
+/// * `ffmc` - Fine fuel moisture code, only used for C1/C7 surface fuel consumption
+/// * `bui` - Buildup index
+/// * `fmc` - Foliar moisture content (%)
+/// * `cbh` - Crown base height (m)
+/// * `pc` - Percent conifer (%), only used for M1/M2
+/// * `pdf` - Percent dead balsam fir (%), only used for M3/M4
+/// * `cc` - Degree of curing (%), only used for O1a/O1b
+/// * `gfl` - Grass fuel load (kg/m^2), only used for O1a/O1b
+///
+/// ```
+/// # use cffdrs::fbp_system::crown_fire::{critical_surface_intensity, crowning_thresholds};
+/// # use cffdrs::fbp_system::{surface_fuel_consumption, FbpFuelType};
+/// let thresholds = crowning_thresholds(FbpFuelType::C3, 90., 80., 97., 8., 0., 0., 0., 0.3);
+/// assert_eq!(thresholds.sfc, surface_fuel_consumption(FbpFuelType::C3, 90., 80., 0., 0.3));
+/// assert_eq!(thresholds.csi, critical_surface_intensity(97., 8.));
+/// assert_eq!(thresholds.rso, thresholds.csi / (300. * thresholds.sfc));
+///
+/// let initiation = thresholds.initiation_isi.unwrap();
+/// let active = thresholds.active_isi.unwrap();
+/// assert!(initiation < active);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn crowning_thresholds(
+    fuel_type: FbpFuelType,
+    ffmc: f64,
+    bui: f64,
+    fmc: f64,
+    cbh: f64,
+    pc: f64,
+    pdf: f64,
+    cc: f64,
+    gfl: f64,
+) -> CrowningThresholds {
+    let sfc = surface_fuel_consumption(fuel_type, ffmc, bui, pc, gfl);
+    let cfb =
+        |isi: f64| rate_of_spread_extended(fuel_type, isi, bui, fmc, sfc, pc, pdf, cc, cbh).cfb;
+    let csi = csi(fmc, cbh);
+
+    CrowningThresholds {
+        sfc,
+        csi,
+        rso: rso(csi, sfc),
+        initiation_isi: threshold_isi(|isi| cfb(isi) > 0.),
+        active_isi: threshold_isi(|isi| cfb(isi) >= 0.9),
+    }
+}
+
+/// Lowest ISI up to [MAX_THRESHOLD_ISI] at which `reached` holds, assuming it keeps holding at
+/// higher ISIs
+///
+/// This is a simpler bisection than `inverse::solve_threshold()`, which needs `std` to sample the
+/// output and check that it's monotonic, so that this module stays `no_std`. CFB only increases
+/// with ISI, so that check isn't needed here, and the bracket is narrowed to an absolute ISI
+/// tolerance rather than one relative to the search range.
+fn threshold_isi(reached: impl Fn(f64) -> bool) -> Option<f64> {
+    if !reached(MAX_THRESHOLD_ISI) {
+        return None;
+    }
+    if reached(0.) {
+        return Some(0.);
+    }
+
+    let (mut below, mut above) = (0., MAX_THRESHOLD_ISI);
+    while above - below > 1e-9 {
+        let middle = below + (above - below) / 2.;
+        if reached(middle) {
+            above = middle;
+        } else {
+            below = middle;
+        }
+    }
+
+    Some(above)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfb(fuel_type: FbpFuelType, isi: f64) -> f64 {
+        let sfc = surface_fuel_consumption(fuel_type, 90., 80., 0., 0.3);
+        rate_of_spread_extended(fuel_type, isi, 80., 97., sfc, 0., 0., 0., 8.).cfb
+    }
+
+    #[test]
+    fn test_crowning_thresholds() {
+        for fuel_type in [
+            FbpFuelType::C1,
+            FbpFuelType::C2,
+            FbpFuelType::C3,
+            FbpFuelType::C6,
+        ] {
+            let thresholds = crowning_thresholds(fuel_type, 90., 80., 97., 8., 0., 0., 0., 0.3);
+            let initiation = thresholds.initiation_isi.unwrap();
+            let active = thresholds.active_isi.unwrap();
+
+            assert_eq!(cfb(fuel_type, initiation - 1e-6), 0.);
+            assert!(cfb(fuel_type, initiation + 1e-6) > 0.);
+            assert!((cfb(fuel_type, active) - 0.9).abs() < 1e-6);
+            assert!(initiation < active);
+        }
+    }
+
+    #[test]
+    fn test_crowning_thresholds_unreachable() {
+        // A very high crown base height can't be reached by a surface fire
+        let thresholds = crowning_thresholds(FbpFuelType::C3, 90., 80., 97., 100., 0., 0., 0., 0.3);
+
+        assert_eq!(thresholds.initiation_isi, None);
+        assert_eq!(thresholds.active_isi, None);
+    }
+}
@@ -1,4 +1,6 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{c, Float};
 
 /// Calculate crown fraction burned (CFB)
 ///
@@ -7,10 +9,10 @@ use std::f64::consts;
 /// * `rso` - Critical surface fire spread rate
 ///
 /// Return CFB in the range `0..=1`
-pub fn crown_fraction_burned(ros: f64, rso: f64) -> f64 {
+pub fn crown_fraction_burned<T: Float>(ros: T, rso: T) -> T {
     if ros > rso {
-        1. - consts::E.powf(-0.23 * (ros - rso))
+        T::one() - c::<T>(consts::E).powf(c::<T>(-0.23) * (ros - rso))
     } else {
-        0.
+        T::zero()
     }
 }
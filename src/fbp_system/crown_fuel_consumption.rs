@@ -1,3 +1,5 @@
+use crate::float::{c, Float};
+
 use super::FbpFuelType;
 
 /// Crown fuel consumption (CFC)
@@ -9,18 +11,18 @@ use super::FbpFuelType;
 ///
 /// Returns CFC (kg/m^2)
 ///
-pub fn crown_fuel_consumption(
+pub fn crown_fuel_consumption<T: Float>(
     fuel_type: FbpFuelType,
-    cfl: f64,
-    cfb: f64,
-    pc: f64,
-    pdf: f64,
-) -> f64 {
+    cfl: T,
+    cfb: T,
+    pc: T,
+    pdf: T,
+) -> T {
     let cfc = cfl * cfb;
 
     match fuel_type {
-        FbpFuelType::M1 | FbpFuelType::M2 => pc / 100. * cfc,
-        FbpFuelType::M3 | FbpFuelType::M4 => pdf / 100. * cfc,
+        FbpFuelType::M1 | FbpFuelType::M2 => pc / c(100.) * cfc,
+        FbpFuelType::M3 | FbpFuelType::M4 => pdf / c(100.) * cfc,
         _ => cfc,
     }
 }
@@ -28,7 +30,7 @@ pub fn crown_fuel_consumption(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -60,4 +62,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_crown_fuel_consumption_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/crown_fuel_consumption.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                crown_fuel_consumption(
+                    record.fuel_type,
+                    record.cfl as f32,
+                    record.cfb as f32,
+                    record.pc as f32,
+                    record.pdf as f32,
+                ),
+                crown_fuel_consumption(
+                    record.fuel_type,
+                    record.cfl,
+                    record.cfb,
+                    record.pc,
+                    record.pdf,
+                ),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -15,6 +15,7 @@ pub const fn crown_fuel_load(fuel_type: FbpFuelType) -> f64 {
         FbpFuelType::M3 => 0.8,
         FbpFuelType::M4 => 0.8,
         FbpFuelType::D1 => 0.,
+        FbpFuelType::D2 => 0.,
         FbpFuelType::S1 => 0.,
         FbpFuelType::S2 => 0.,
         FbpFuelType::S3 => 0.,
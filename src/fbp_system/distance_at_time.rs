@@ -1,4 +1,6 @@
-use std::f64::consts::E;
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
 
 use super::FbpFuelType;
 
@@ -7,7 +9,8 @@ use super::FbpFuelType;
 /// * `ros` - Rate of spread (m/min)
 /// * `time` - Elapsed time (min)
 /// * `cfb` - Crow fraction burned
-pub fn distance_at_time(fuel_type: FbpFuelType, ros: f64, time: f64, cfb: f64) -> f64 {
+pub fn distance_at_time<T: Float>(fuel_type: FbpFuelType, ros: T, time: T, cfb: T) -> T {
+    let e = c::<T>(E);
     let alpha = match fuel_type {
         FbpFuelType::C1
         | FbpFuelType::O1a
@@ -15,17 +18,18 @@ pub fn distance_at_time(fuel_type: FbpFuelType, ros: f64, time: f64, cfb: f64) -
         | FbpFuelType::S1
         | FbpFuelType::S2
         | FbpFuelType::S3
-        | FbpFuelType::D1 => 0.115,
-        _ => 0.115 - 18.8 * cfb.powf(2.5) * E.powf(-8. * cfb),
+        | FbpFuelType::D1
+        | FbpFuelType::D2 => c(0.115),
+        _ => c::<T>(0.115) - c::<T>(18.8) * cfb.powf(c(2.5)) * e.powf(c::<T>(-8.) * cfb),
     };
 
-    ros * (time + E.powf(-alpha * time) / alpha - 1. / alpha)
+    ros * (time + e.powf(-alpha * time) / alpha - T::one() / alpha)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -50,4 +54,26 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_distance_at_time_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/distance_at_time.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                distance_at_time(
+                    record.fuel_type,
+                    record.ros as f32,
+                    record.time as f32,
+                    record.cfb as f32,
+                ),
+                distance_at_time(record.fuel_type, record.ros, record.time, record.cfb),
+            );
+        }
+
+        Ok(())
+    }
 }
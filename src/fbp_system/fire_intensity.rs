@@ -1,3 +1,5 @@
+use crate::float::{c, Float};
+
 /// Fire intensity (FI)
 ///
 /// * `fc` - Fuel consumption, either surface or total (kg/m^2) - See
@@ -6,14 +8,14 @@
 ///
 /// Returns FI (kW/m)
 ///
-pub fn fire_intensity(fc: f64, ros: f64) -> f64 {
-    300. * fc * ros
+pub fn fire_intensity<T: Float>(fc: T, ros: T) -> T {
+    c::<T>(300.) * fc * ros
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -36,4 +38,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_fire_intensity_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/fire_intensity.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                fire_intensity(record.fc as f32, record.ros as f32),
+                fire_intensity(record.fc, record.ros),
+            );
+        }
+
+        Ok(())
+    }
 }
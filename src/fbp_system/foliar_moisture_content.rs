@@ -1,4 +1,6 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{c, Float};
 
 /// Calculate foliar moisture content (FMC)
 ///
@@ -28,35 +30,37 @@ use std::f64::consts;
 /// assert_eq!(foliar_moisture_content(31.5, 180., 0, None, None), 114.4572);
 ///
 /// ```
-pub fn foliar_moisture_content(
-    lat: f64,
-    long: f64,
+pub fn foliar_moisture_content<T: Float>(
+    lat: T,
+    long: T,
     day_of_year: i32,
-    elev: Option<f64>,
+    elev: Option<T>,
     date_of_minimum_fmc: Option<i32>,
-) -> f64 {
+) -> T {
     let latn = if elev.is_some() {
-        43. + 33.7 * consts::E.powf(-0.0351 * (150. - long))
+        c::<T>(43.) + c::<T>(33.7) * c::<T>(consts::E).powf(c::<T>(-0.0351) * (c::<T>(150.) - long))
     } else {
-        46. + 23.4 * consts::E.powf(-0.0360 * (150. - long))
+        c::<T>(46.) + c::<T>(23.4) * c::<T>(consts::E).powf(c::<T>(-0.0360) * (c::<T>(150.) - long))
     };
 
     let d0 = date_of_minimum_fmc.unwrap_or_else(|| {
-        if let Some(elev) = elev {
-            (142.1 * (lat / latn) + 0.0172 * elev).round() as i32
+        let d0 = if let Some(elev) = elev {
+            c::<T>(142.1) * (lat / latn) + c::<T>(0.0172) * elev
         } else {
-            (151. * (lat / latn)).round() as i32
-        }
+            c::<T>(151.) * (lat / latn)
+        };
+
+        d0.round().to_i32().unwrap_or(0)
     });
 
     // Number of days between day of year and date of min FMC
-    let nd = (day_of_year - d0).abs() as f64;
+    let nd = c::<T>((day_of_year - d0).abs() as f64);
 
-    if nd < 30. {
-        85. + 0.0189 * nd.powi(2)
-    } else if (30. ..50.).contains(&nd) {
-        32.9 + 3.17 * nd - 0.0288 * nd.powi(2)
+    if nd < c(30.) {
+        c::<T>(85.) + c::<T>(0.0189) * nd.powi(2)
+    } else if (c(30.)..c(50.)).contains(&nd) {
+        c::<T>(32.9) + c::<T>(3.17) * nd - c::<T>(0.0288) * nd.powi(2)
     } else {
-        120.
+        c(120.)
     }
 }
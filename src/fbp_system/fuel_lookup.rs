@@ -0,0 +1,377 @@
+//! Fuel lookup tables, mapping fuel grid codes to fuel types
+
+use std::{collections::HashMap, fmt, io::Read};
+
+use super::{seasonal_fuel_type, FbpFuelType, FbpOptions, LeafOnSeason};
+
+/// Fuel type and fuel-specific options for a single fuel grid code
+#[derive(PartialEq, Clone, Debug)]
+pub struct FuelLookupEntry {
+    /// Fuel type. For seasonal pairs such as `M-1/M-2`, this is the leafless fuel type
+    pub fuel_type: FbpFuelType,
+    /// Green fuel type of a seasonal pair such as `M-1/M-2`, used during the leaf-on season
+    pub leaf_on_fuel_type: Option<FbpFuelType>,
+    /// Descriptive name, if the lookup table has one
+    pub name: Option<String>,
+    /// Percent conifer (%), for M1/M2
+    pub percent_conifer: Option<f64>,
+    /// Percent dead balsam fir (%), for M3/M4
+    pub percent_dead_balsam: Option<f64>,
+    /// Degree of curing (%), for O1a/O1b
+    pub curing: Option<f64>,
+}
+
+impl FuelLookupEntry {
+    /// Entry for a single fuel type, without a name or fuel-specific options
+    pub fn new(fuel_type: FbpFuelType) -> Self {
+        Self {
+            fuel_type,
+            leaf_on_fuel_type: None,
+            name: None,
+            percent_conifer: None,
+            percent_dead_balsam: None,
+            curing: None,
+        }
+    }
+
+    /// Fuel type on a Julian day of year. Seasonal pairs use the green fuel type during the
+    /// leaf-on `season`, and the leafless fuel type otherwise or without a season
+    pub fn fuel_type_on(&self, day_of_year: i32, season: Option<&LeafOnSeason>) -> FbpFuelType {
+        match (self.leaf_on_fuel_type, season) {
+            (Some(_), Some(season)) => seasonal_fuel_type(self.fuel_type, day_of_year, season),
+            _ => self.fuel_type,
+        }
+    }
+
+    /// FBP options for this entry, using `options` for anything the entry doesn't specify
+    pub fn apply(&self, options: &FbpOptions) -> FbpOptions {
+        FbpOptions {
+            percent_conifer: self.percent_conifer.unwrap_or(options.percent_conifer),
+            percent_dead_balsam: self
+                .percent_dead_balsam
+                .unwrap_or(options.percent_dead_balsam),
+            curing: self.curing.unwrap_or(options.curing),
+            ..options.clone()
+        }
+    }
+}
+
+/// Error returned when a fuel lookup table can't be read
+#[derive(Debug)]
+pub enum FuelLookupError {
+    /// The lookup table couldn't be read
+    Io(std::io::Error),
+    /// A required column is missing from the header
+    MissingColumn(&'static str),
+    /// A row couldn't be parsed
+    InvalidRow {
+        /// Line number (starting at 1)
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for FuelLookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FuelLookupError::Io(err) => write!(f, "failed to read fuel lookup table: {err}"),
+            FuelLookupError::MissingColumn(column) => {
+                write!(f, "fuel lookup table is missing a \"{column}\" column")
+            }
+            FuelLookupError::InvalidRow { line, message } => {
+                write!(f, "invalid fuel lookup table row on line {line}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FuelLookupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FuelLookupError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FuelLookupError {
+    fn from(err: std::io::Error) -> Self {
+        FuelLookupError::Io(err)
+    }
+}
+
+/// Maps fuel grid codes to fuel types, e.g. for interpreting fuel rasters
+///
+/// # Examples
+///
+/// ```
+/// # use cffdrs::fbp_system::{FbpFuelType, FuelLookupTable};
+/// let lut = "\
+/// grid_value,export_value,descriptive_name,fuel_type
+/// 2,2,Boreal Spruce,C-2
+/// 101,101,Non-fuel,Non-fuel
+/// 425,425,Boreal Mixedwood - Leafless,M-1 (25 PC)
+/// ";
+///
+/// let table = FuelLookupTable::from_reader(lut.as_bytes()).unwrap();
+///
+/// assert_eq!(table.fuel_type(2), Some(FbpFuelType::C2));
+/// assert_eq!(table.fuel_type(101), Some(FbpFuelType::NonFuel));
+/// assert_eq!(table.get(425).unwrap().percent_conifer, Some(25.));
+/// assert_eq!(table.fuel_type(999), None);
+/// ```
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct FuelLookupTable {
+    entries: HashMap<i64, FuelLookupEntry>,
+}
+
+impl FuelLookupTable {
+    /// Empty lookup table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a comma-separated fuel lookup table, such as those used by Prometheus and CWFIS
+    ///
+    /// The header must contain a grid code column (`grid_value`, `grid_code` or `code`) and a
+    /// `fuel_type` column. Fuel types are parsed with [`FromStr`][std::str::FromStr], and may
+    /// include a modifier such as `M-1 (25 PC)`, `M-3 (50 PDF)` or `O-1a (80 C)`. Optional `pc`,
+    /// `pdf`, `curing` and `descriptive_name` columns are also read. Seasonal pairs (`D-1/D-2`,
+    /// `M-1/M-2` and `M-3/M-4`) keep both fuel types (see [`FuelLookupEntry::fuel_type_on()`]),
+    /// and any other pair is an error.
+    ///
+    /// Fields are split on commas, so they can't contain commas themselves.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, FuelLookupError> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents)?;
+
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let header: Vec<String> = match lines.next() {
+            Some((_, header)) => split_fields(header)
+                .map(|field| field.to_lowercase())
+                .collect(),
+            None => return Err(FuelLookupError::MissingColumn("grid_value")),
+        };
+        let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+        let code_column = column(&["grid_value", "grid_code", "code"])
+            .ok_or(FuelLookupError::MissingColumn("grid_value"))?;
+        let fuel_type_column =
+            column(&["fuel_type"]).ok_or(FuelLookupError::MissingColumn("fuel_type"))?;
+        let name_column = column(&["descriptive_name", "name"]);
+        let pc_column = column(&["pc", "percent_conifer"]);
+        let pdf_column = column(&["pdf", "percent_dead_balsam"]);
+        let curing_column = column(&["curing", "cc"]);
+
+        let mut table = Self::new();
+
+        for (index, line) in lines {
+            let line_number = index + 1;
+            let invalid = |message: String| FuelLookupError::InvalidRow {
+                line: line_number,
+                message,
+            };
+
+            let fields: Vec<&str> = split_fields(line).collect();
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|column| fields.get(column))
+                    .copied()
+                    .filter(|field| !field.is_empty())
+            };
+            let number = |column: Option<usize>| {
+                field(column)
+                    .map(|field| {
+                        field
+                            .parse::<f64>()
+                            .map_err(|_| invalid(format!("\"{field}\" is not a number")))
+                    })
+                    .transpose()
+            };
+
+            let code =
+                field(Some(code_column)).ok_or_else(|| invalid("missing grid code".to_string()))?;
+            let code = code
+                .parse::<i64>()
+                .map_err(|_| invalid(format!("\"{code}\" is not a valid grid code")))?;
+
+            let fuel_type = field(Some(fuel_type_column))
+                .ok_or_else(|| invalid("missing fuel type".to_string()))?;
+            let mut entry = parse_fuel_type(fuel_type).map_err(invalid)?;
+
+            entry.name = field(name_column).map(str::to_string);
+            if let Some(pc) = number(pc_column)? {
+                entry.percent_conifer = Some(pc);
+            }
+            if let Some(pdf) = number(pdf_column)? {
+                entry.percent_dead_balsam = Some(pdf);
+            }
+            if let Some(curing) = number(curing_column)? {
+                entry.curing = Some(curing);
+            }
+
+            table.insert(code, entry);
+        }
+
+        Ok(table)
+    }
+
+    /// Add or replace the entry for a grid code
+    pub fn insert(&mut self, code: i64, entry: FuelLookupEntry) {
+        self.entries.insert(code, entry);
+    }
+
+    /// Entry for a grid code
+    pub fn get(&self, code: i64) -> Option<&FuelLookupEntry> {
+        self.entries.get(&code)
+    }
+
+    /// Fuel type for a grid code
+    pub fn fuel_type(&self, code: i64) -> Option<FbpFuelType> {
+        self.get(code).map(|entry| entry.fuel_type)
+    }
+
+    /// Number of grid codes in the table
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table has no grid codes
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn split_fields(line: &str) -> impl Iterator<Item = &str> {
+    line.split(',')
+        .map(|field| field.trim().trim_matches('"').trim())
+}
+
+/// Parse a fuel type with an optional modifier, e.g. `M-1 (25 PC)`
+fn parse_fuel_type(value: &str) -> Result<FuelLookupEntry, String> {
+    let (fuel_type, modifier) = match value.split_once('(') {
+        Some((fuel_type, modifier)) => (fuel_type, Some(modifier.trim_end_matches(')'))),
+        None => (value, None),
+    };
+
+    let parse = |fuel_type: &str| fuel_type.parse().map_err(|err| format!("{err}"));
+    let mut entry = match fuel_type.split_once('/') {
+        // Seasonal pairs, leafless then green (e.g. "M-1/M-2")
+        Some((leafless, green)) => {
+            let pair = (parse(leafless)?, parse(green)?);
+            match pair {
+                (FbpFuelType::D1, FbpFuelType::D2)
+                | (FbpFuelType::M1, FbpFuelType::M2)
+                | (FbpFuelType::M3, FbpFuelType::M4) => FuelLookupEntry {
+                    leaf_on_fuel_type: Some(pair.1),
+                    ..FuelLookupEntry::new(pair.0)
+                },
+                _ => return Err(format!("\"{fuel_type}\" isn't a seasonal fuel type pair")),
+            }
+        }
+        None => FuelLookupEntry::new(parse(fuel_type)?),
+    };
+
+    if let Some(modifier) = modifier {
+        let mut parts = modifier.split_whitespace();
+        let amount = parts
+            .next()
+            .map(|amount| amount.trim_end_matches('%'))
+            .and_then(|amount| amount.parse::<f64>().ok())
+            .ok_or_else(|| format!("invalid fuel type modifier \"{modifier}\""))?;
+
+        match parts.next().map(str::to_lowercase).as_deref() {
+            Some("pc") => entry.percent_conifer = Some(amount),
+            Some("pdf") => entry.percent_dead_balsam = Some(amount),
+            Some("c") | Some("cured") | Some("curing") => entry.curing = Some(amount),
+            _ => match entry.fuel_type {
+                FbpFuelType::M1 | FbpFuelType::M2 => entry.percent_conifer = Some(amount),
+                FbpFuelType::M3 | FbpFuelType::M4 => entry.percent_dead_balsam = Some(amount),
+                FbpFuelType::O1a | FbpFuelType::O1b => entry.curing = Some(amount),
+                _ => return Err(format!("invalid fuel type modifier \"{modifier}\"")),
+            },
+        }
+    }
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuel_lookup_table() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/fuel_lookup_table.csv")?;
+        let table = FuelLookupTable::from_reader(fixture)?;
+
+        assert_eq!(table.fuel_type(1), Some(FbpFuelType::C1));
+        assert_eq!(table.fuel_type(7), Some(FbpFuelType::C7));
+        assert_eq!(table.fuel_type(101), Some(FbpFuelType::NonFuel));
+        assert_eq!(table.fuel_type(102), Some(FbpFuelType::NonFuel));
+        assert_eq!(table.fuel_type(31), Some(FbpFuelType::O1a));
+
+        let m1 = table.get(425).unwrap();
+        assert_eq!(m1.fuel_type, FbpFuelType::M1);
+        assert_eq!(m1.percent_conifer, Some(25.));
+        assert_eq!(m1.name.as_deref(), Some("Boreal Mixedwood - Leafless"));
+
+        let m4 = table.get(850).unwrap();
+        assert_eq!(m4.fuel_type, FbpFuelType::M4);
+        assert_eq!(m4.percent_dead_balsam, Some(50.));
+
+        let seasonal = table.get(950).unwrap();
+        assert_eq!(seasonal.fuel_type, FbpFuelType::M3);
+        assert_eq!(seasonal.leaf_on_fuel_type, Some(FbpFuelType::M4));
+        assert_eq!(seasonal.percent_dead_balsam, Some(50.));
+
+        let aspen = table.get(13).unwrap();
+        let season = LeafOnSeason {
+            green_up: 150,
+            leaf_fall: 270,
+        };
+        assert_eq!(aspen.fuel_type_on(200, Some(&season)), FbpFuelType::D2);
+        assert_eq!(aspen.fuel_type_on(100, Some(&season)), FbpFuelType::D1);
+        assert_eq!(aspen.fuel_type_on(200, None), FbpFuelType::D1);
+        assert_eq!(
+            table.get(11).unwrap().fuel_type_on(200, Some(&season)),
+            FbpFuelType::D1
+        );
+
+        let grass = table.get(31).unwrap();
+        assert_eq!(grass.curing, Some(60.));
+        let options = grass.apply(&FbpOptions::default());
+        assert_eq!(options.curing, 60.);
+        assert_eq!(options.percent_conifer, 50.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuel_lookup_table_errors() {
+        assert!(matches!(
+            FuelLookupTable::from_reader("code,name\n1,C-1".as_bytes()),
+            Err(FuelLookupError::MissingColumn("fuel_type"))
+        ));
+        assert!(matches!(
+            FuelLookupTable::from_reader("code,fuel_type\n1,C-1\n2,X-9".as_bytes()),
+            Err(FuelLookupError::InvalidRow { line: 3, .. })
+        ));
+        assert!(matches!(
+            FuelLookupTable::from_reader("code,fuel_type\nabc,C-1".as_bytes()),
+            Err(FuelLookupError::InvalidRow { line: 2, .. })
+        ));
+        assert!(matches!(
+            FuelLookupTable::from_reader("code,fuel_type\n1,C-2/C-3".as_bytes()),
+            Err(FuelLookupError::InvalidRow { line: 2, .. })
+        ));
+        assert!(matches!(
+            FuelLookupTable::from_reader("code,fuel_type\n1,M-2/M-1".as_bytes()),
+            Err(FuelLookupError::InvalidRow { line: 2, .. })
+        ));
+    }
+}
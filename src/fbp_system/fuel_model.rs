@@ -0,0 +1,389 @@
+//! Fuel models, for running FBP calculations on custom (user-defined) fuel types
+
+use crate::float::Float;
+
+use crate::fwi_system::initial_spread_index;
+
+use super::{
+    buildup_effect::buildup_effect_with_params,
+    buildup_effect_params, crown_base_height, crown_fraction_burned, crown_fuel_consumption,
+    crown_fuel_load, length_to_breadth,
+    length_to_breadth::forest_length_to_breadth,
+    ros::{csi, default_rsi_calc, rate_of_spread_extended, rate_of_spread_params, rso},
+    ros::{ExtendedRateOfSpread, RateOfSpreadParams},
+    slope_adjustment,
+    slope_adjustment::{isf_from_rsf, spread_factor, wind_slope_vector},
+    surface_fuel_consumption, BuildupEffectParams, FbpFuelType,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Fuel-specific behaviour used by [calculate_fbp_for_fuel()][crate::fbp_system::calculate_fbp_for_fuel()]
+///
+/// Implemented for the built-in [FbpFuelType]s, and for [FuelParameters] to describe custom fuel
+/// types.
+pub trait FuelModel {
+    /// Surface fuel consumption (kg/m^2)
+    ///
+    /// See [surface_fuel_consumption()][crate::fbp_system::surface_fuel_consumption()]
+    fn surface_fuel_consumption(&self, ffmc: f64, bui: f64, pc: f64, gfl: f64) -> f64;
+
+    /// Rate of spread, as well as CFB, CSI and RSO
+    ///
+    /// See [rate_of_spread_extended()][crate::fbp_system::ros::rate_of_spread_extended()]
+    #[allow(clippy::too_many_arguments)]
+    fn rate_of_spread_extended(
+        &self,
+        isi: f64,
+        bui: f64,
+        fmc: f64,
+        sfc: f64,
+        pc: f64,
+        pdf: f64,
+        cc: f64,
+        cbh: f64,
+    ) -> ExtendedRateOfSpread;
+
+    /// Slope adjusted wind speed and direction of spread, returning (raz, wsv)
+    ///
+    /// See [slope_adjustment()][crate::fbp_system::slope_adjustment()]
+    #[allow(clippy::too_many_arguments)]
+    fn slope_adjustment(
+        &self,
+        ffmc: f64,
+        ws: f64,
+        waz: f64,
+        gs: f64,
+        saz: f64,
+        fmc: f64,
+        sfc: f64,
+        pc: f64,
+        pdf: f64,
+        cc: f64,
+        cbh: f64,
+    ) -> (f64, f64);
+
+    /// Length-to-breadth ratio
+    ///
+    /// See [length_to_breadth()][crate::fbp_system::length_to_breadth()]
+    fn length_to_breadth(&self, wsv: f64) -> f64;
+
+    /// Crown fuel load (kg/m^2)
+    fn crown_fuel_load(&self) -> f64;
+
+    /// Default crown base height (m), used when
+    /// [`FbpOptions::crown_base_height`][crate::fbp_system::FbpOptions::crown_base_height] isn't
+    /// set
+    fn crown_base_height(&self) -> f64;
+
+    /// Crown fuel consumption (kg/m^2)
+    ///
+    /// See [crown_fuel_consumption()][crate::fbp_system::crown_fuel_consumption()]
+    fn crown_fuel_consumption(&self, cfl: f64, cfb: f64, pc: f64, pdf: f64) -> f64;
+}
+
+impl FuelModel for FbpFuelType {
+    fn surface_fuel_consumption(&self, ffmc: f64, bui: f64, pc: f64, gfl: f64) -> f64 {
+        surface_fuel_consumption(*self, ffmc, bui, pc, gfl)
+    }
+
+    fn rate_of_spread_extended(
+        &self,
+        isi: f64,
+        bui: f64,
+        fmc: f64,
+        sfc: f64,
+        pc: f64,
+        pdf: f64,
+        cc: f64,
+        cbh: f64,
+    ) -> ExtendedRateOfSpread {
+        rate_of_spread_extended(*self, isi, bui, fmc, sfc, pc, pdf, cc, cbh)
+    }
+
+    fn slope_adjustment(
+        &self,
+        ffmc: f64,
+        ws: f64,
+        waz: f64,
+        gs: f64,
+        saz: f64,
+        fmc: f64,
+        sfc: f64,
+        pc: f64,
+        pdf: f64,
+        cc: f64,
+        cbh: f64,
+    ) -> (f64, f64) {
+        slope_adjustment(*self, ffmc, ws, waz, gs, saz, fmc, sfc, pc, pdf, cc, cbh)
+    }
+
+    fn length_to_breadth(&self, wsv: f64) -> f64 {
+        length_to_breadth(*self, wsv)
+    }
+
+    fn crown_fuel_load(&self) -> f64 {
+        crown_fuel_load(*self)
+    }
+
+    fn crown_base_height(&self) -> f64 {
+        match self {
+            // C6 crown base height depends on the stand, so use the R cffdrs default
+            FbpFuelType::C6 => 7.,
+            _ => crown_base_height(*self, 0., 0.),
+        }
+    }
+
+    fn crown_fuel_consumption(&self, cfl: f64, cfb: f64, pc: f64, pdf: f64) -> f64 {
+        crown_fuel_consumption(*self, cfl, cfb, pc, pdf)
+    }
+}
+
+/// Equation used to calculate surface fuel consumption for a custom fuel type
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum SurfaceFuelConsumptionModel {
+    /// Use the equation for a built-in fuel type
+    FuelType(FbpFuelType),
+    /// `max * (1 - e^(-k * BUI))^p`, the form used by C2-C6 and D1
+    Bui {
+        /// Maximum surface fuel consumption (kg/m^2)
+        max: f64,
+        k: f64,
+        p: f64,
+    },
+    /// Constant surface fuel consumption (kg/m^2)
+    Fixed(f64),
+}
+
+/// Coefficients describing a custom fuel type
+///
+/// Custom fuel types use the standard FBP equations for a single (non-mixedwood, non-grass) fuel
+/// type, with the coefficients given here. With the `serde` feature these can be loaded from any
+/// serde-supported format (e.g. TOML or JSON).
+///
+/// # Examples
+///
+/// ```
+/// # use cffdrs::fbp_system::{FbpFuelType, FuelModel, FuelParameters};
+/// // Start with C7 and use a lower crown base height
+/// let modified_c7 = FuelParameters {
+///     cbh: 6.,
+///     ..FuelParameters::from_fuel_type(FbpFuelType::C7)
+/// };
+///
+/// assert_eq!(modified_c7.crown_fuel_load(), 0.5);
+/// assert_eq!(
+///     modified_c7.length_to_breadth(20.),
+///     FbpFuelType::C7.length_to_breadth(20.)
+/// );
+/// ```
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct FuelParameters {
+    /// Rate of spread coefficient a
+    pub a: f64,
+    /// Rate of spread coefficient b
+    pub b: f64,
+    /// Rate of spread coefficient c
+    pub c: f64,
+    /// Average BUI
+    pub bui0: f64,
+    /// Proportion of maximum rate of spread at BUI equal to 50
+    pub q: f64,
+    /// Maximum buildup effect
+    pub be_max: f64,
+    /// Crown base height (m), used when
+    /// [`FbpOptions::crown_base_height`][crate::fbp_system::FbpOptions::crown_base_height] isn't
+    /// set
+    pub cbh: f64,
+    /// Crown fuel load (kg/m^2)
+    pub cfl: f64,
+    /// Surface fuel consumption equation
+    pub sfc: SurfaceFuelConsumptionModel,
+}
+
+impl FuelParameters {
+    /// Parameters of a built-in fuel type, as a starting point for a custom fuel type
+    ///
+    /// Only fuel types that use the standard equations (C1-C5, C7, D1, S1-S3) are reproduced
+    /// exactly. Mixedwood, grass and C6 fuel types have extra behaviour that can't be described by
+    /// [FuelParameters]. C6 uses the same 7m default crown base height as [FbpFuelType].
+    pub fn from_fuel_type(fuel_type: FbpFuelType) -> Self {
+        let RateOfSpreadParams { a, b, c } = rate_of_spread_params(fuel_type);
+        let BuildupEffectParams { bui0, q, max } = buildup_effect_params(fuel_type);
+
+        Self {
+            a,
+            b,
+            c,
+            bui0,
+            q,
+            be_max: max,
+            cbh: fuel_type.crown_base_height(),
+            cfl: crown_fuel_load(fuel_type),
+            sfc: SurfaceFuelConsumptionModel::FuelType(fuel_type),
+        }
+    }
+
+    fn buildup_effect(&self, bui: f64) -> f64 {
+        buildup_effect_with_params(
+            &BuildupEffectParams {
+                bui0: self.bui0,
+                q: self.q,
+                max: self.be_max,
+            },
+            bui,
+        )
+    }
+}
+
+impl FuelModel for FuelParameters {
+    fn surface_fuel_consumption(&self, ffmc: f64, bui: f64, pc: f64, gfl: f64) -> f64 {
+        match self.sfc {
+            SurfaceFuelConsumptionModel::FuelType(fuel_type) => {
+                surface_fuel_consumption(fuel_type, ffmc, bui, pc, gfl)
+            }
+            SurfaceFuelConsumptionModel::Bui { max, k, p } => {
+                max * Float::powf(1. - Float::exp(-k * bui), p)
+            }
+            SurfaceFuelConsumptionModel::Fixed(sfc) => sfc,
+        }
+    }
+
+    fn rate_of_spread_extended(
+        &self,
+        isi: f64,
+        bui: f64,
+        fmc: f64,
+        sfc: f64,
+        _pc: f64,
+        _pdf: f64,
+        _cc: f64,
+        cbh: f64,
+    ) -> ExtendedRateOfSpread {
+        let rsi = default_rsi_calc(self.a, self.b, self.c, isi);
+
+        let csi = csi(fmc, cbh);
+        let rso = rso(csi, sfc);
+
+        let rss = rsi * self.buildup_effect(bui);
+        let cfb = crown_fraction_burned(rss, rso);
+
+        let ros = if rss <= 0. { 0.000001 } else { rss };
+
+        ExtendedRateOfSpread { ros, cfb, csi, rso }
+    }
+
+    fn slope_adjustment(
+        &self,
+        ffmc: f64,
+        ws: f64,
+        waz: f64,
+        gs: f64,
+        saz: f64,
+        fmc: f64,
+        sfc: f64,
+        pc: f64,
+        pdf: f64,
+        cc: f64,
+        cbh: f64,
+    ) -> (f64, f64) {
+        // Surface spread rate with 0 wind upslope
+        let isz = initial_spread_index(ffmc, 0.);
+        let rsz = self
+            .rate_of_spread_extended(isz, -1., fmc, sfc, pc, pdf, cc, cbh)
+            .ros;
+        let rsf = rsz * spread_factor(gs);
+
+        let isf = isf_from_rsf(rsf, self.a, self.b, self.c);
+
+        wind_slope_vector(ffmc, ws, waz, saz, isf)
+    }
+
+    fn length_to_breadth(&self, wsv: f64) -> f64 {
+        forest_length_to_breadth(wsv)
+    }
+
+    fn crown_fuel_load(&self) -> f64 {
+        self.cfl
+    }
+
+    fn crown_base_height(&self) -> f64 {
+        self.cbh
+    }
+
+    fn crown_fuel_consumption(&self, cfl: f64, cfb: f64, _pc: f64, _pdf: f64) -> f64 {
+        cfl * cfb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuel_parameters_match_fuel_type() {
+        for fuel_type in [
+            FbpFuelType::C1,
+            FbpFuelType::C2,
+            FbpFuelType::C3,
+            FbpFuelType::C4,
+            FbpFuelType::C5,
+            FbpFuelType::C7,
+            FbpFuelType::D1,
+            FbpFuelType::S1,
+            FbpFuelType::S2,
+            FbpFuelType::S3,
+        ] {
+            let params = FuelParameters::from_fuel_type(fuel_type);
+            let cbh = params.cbh;
+
+            for (ffmc, bui, ws) in [(85., 30., 5.), (90., 60., 20.), (95., 120., 40.)] {
+                let isi = initial_spread_index(ffmc, ws);
+                let sfc = fuel_type.surface_fuel_consumption(ffmc, bui, 50., 0.3);
+                assert_eq!(params.surface_fuel_consumption(ffmc, bui, 50., 0.3), sfc);
+
+                let expected =
+                    fuel_type.rate_of_spread_extended(isi, bui, 97., sfc, 50., 35., 80., cbh);
+                let actual = params.rate_of_spread_extended(isi, bui, 97., sfc, 50., 35., 80., cbh);
+                assert_eq!(actual.ros, expected.ros);
+                assert_eq!(actual.cfb, expected.cfb);
+
+                let expected =
+                    fuel_type.slope_adjustment(ffmc, ws, 1., 30., 2., 97., sfc, 50., 35., 80., cbh);
+                let actual =
+                    params.slope_adjustment(ffmc, ws, 1., 30., 2., 97., sfc, 50., 35., 80., cbh);
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_fuel_parameters_from_json() -> Result<(), Box<dyn std::error::Error>> {
+        let params: FuelParameters = serde_json::from_str(
+            r#"{
+                "a": 45.0,
+                "b": 0.0305,
+                "c": 2.0,
+                "bui0": 106.0,
+                "q": 0.85,
+                "be_max": 1.134,
+                "cbh": 6.0,
+                "cfl": 0.5,
+                "sfc": { "Bui": { "max": 3.5, "k": 0.0201, "p": 1.0 } }
+            }"#,
+        )?;
+
+        assert_eq!(params.cbh, 6.);
+        assert_eq!(params.surface_fuel_consumption(90., 0., 50., 0.3), 0.);
+        assert_eq!(
+            params.surface_fuel_consumption(90., 60., 50., 0.3),
+            3.5 * (1. - (-0.0201f64 * 60.).exp())
+        );
+
+        Ok(())
+    }
+}
@@ -1,19 +1,27 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{c, Float};
 
 use super::FbpFuelType;
 
 /// Calculate length-to-breadth ratio (LB)
 ///
 /// * `wsv` - slope-adjusted wind speed (km/h) (see [crate::fbp_system::slope_adjustment()] to calculate this value)
-pub fn length_to_breadth(fuel_type: FbpFuelType, wsv: f64) -> f64 {
+pub fn length_to_breadth<T: Float>(fuel_type: FbpFuelType, wsv: T) -> T {
     match fuel_type {
         FbpFuelType::O1a | FbpFuelType::O1b => {
-            if wsv >= 1.0 {
-                1.1 * wsv.powf(0.464)
+            if wsv >= T::one() {
+                c::<T>(1.1) * wsv.powf(c(0.464))
             } else {
-                1.
+                T::one()
             }
         }
-        _ => 1. + 8.729 * (1. - consts::E.powf(-0.030 * wsv)).powf(2.155),
+        _ => forest_length_to_breadth(wsv),
     }
 }
+
+/// Length-to-breadth ratio for non-grass fuel types
+pub(crate) fn forest_length_to_breadth<T: Float>(wsv: T) -> T {
+    T::one()
+        + c::<T>(8.729) * (T::one() - c::<T>(consts::E).powf(c::<T>(-0.030) * wsv)).powf(c(2.155))
+}
@@ -1,5 +1,9 @@
 //! Contains calculations for the Fire Behaviour Prediction (FBP) system
 
+use core::fmt;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+
 mod buildup_effect;
 mod crown_base_height;
 mod crown_fraction_burned;
@@ -8,13 +12,22 @@ mod crown_fuel_load;
 mod distance_at_time;
 mod fire_intensity;
 mod foliar_moisture_content;
+#[cfg(feature = "std")]
+mod fuel_lookup;
+mod fuel_model;
 mod length_to_breadth;
+mod seasonal_fuel_type;
 mod slope_adjustment;
+#[cfg(feature = "std")]
+mod spread;
 mod surface_fuel_consumption;
+#[cfg(feature = "std")]
 mod system;
 mod total_fuel_consumption;
 
+pub mod crown_fire;
 pub mod ros;
+pub mod spotting;
 
 pub use buildup_effect::*;
 pub use crown_base_height::*;
@@ -24,16 +37,24 @@ pub use crown_fuel_load::*;
 pub use distance_at_time::*;
 pub use fire_intensity::*;
 pub use foliar_moisture_content::*;
+#[cfg(feature = "std")]
+pub use fuel_lookup::*;
+pub use fuel_model::*;
 pub use length_to_breadth::*;
+pub use seasonal_fuel_type::*;
 pub use slope_adjustment::*;
+#[cfg(feature = "std")]
+pub use spread::*;
 pub use surface_fuel_consumption::*;
+#[cfg(feature = "std")]
 pub use system::*;
 pub use total_fuel_consumption::*;
 
-#[cfg(feature = "serde")]
+#[cfg(any(feature = "serde", test))]
 use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+// Also deserialized from the test fixtures, which don't need the serde feature
+#[cfg_attr(any(feature = "serde", test), derive(Deserialize, Serialize))]
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub enum FbpFuelType {
     /// Spruce-Lichen Woodland
@@ -60,6 +81,8 @@ pub enum FbpFuelType {
     M4,
     /// Leafless Aspen
     D1,
+    /// Green Aspen
+    D2,
     /// Jack or Lodgepole Pine Slash
     S1,
     /// White Spruce-Balsam Slash
@@ -73,3 +96,105 @@ pub enum FbpFuelType {
     /// Non-fuel
     NonFuel,
 }
+
+impl FbpFuelType {
+    /// All fuel types, including [`FbpFuelType::NonFuel`]
+    pub const ALL: [FbpFuelType; 19] = [
+        FbpFuelType::C1,
+        FbpFuelType::C2,
+        FbpFuelType::C3,
+        FbpFuelType::C4,
+        FbpFuelType::C5,
+        FbpFuelType::C6,
+        FbpFuelType::C7,
+        FbpFuelType::M1,
+        FbpFuelType::M2,
+        FbpFuelType::M3,
+        FbpFuelType::M4,
+        FbpFuelType::D1,
+        FbpFuelType::D2,
+        FbpFuelType::S1,
+        FbpFuelType::S2,
+        FbpFuelType::S3,
+        FbpFuelType::O1a,
+        FbpFuelType::O1b,
+        FbpFuelType::NonFuel,
+    ];
+
+    /// Standard name of the fuel type, e.g. `"C-2"` or `"O-1a"`
+    pub const fn code(&self) -> &'static str {
+        match self {
+            FbpFuelType::C1 => "C-1",
+            FbpFuelType::C2 => "C-2",
+            FbpFuelType::C3 => "C-3",
+            FbpFuelType::C4 => "C-4",
+            FbpFuelType::C5 => "C-5",
+            FbpFuelType::C6 => "C-6",
+            FbpFuelType::C7 => "C-7",
+            FbpFuelType::M1 => "M-1",
+            FbpFuelType::M2 => "M-2",
+            FbpFuelType::M3 => "M-3",
+            FbpFuelType::M4 => "M-4",
+            FbpFuelType::D1 => "D-1",
+            FbpFuelType::D2 => "D-2",
+            FbpFuelType::S1 => "S-1",
+            FbpFuelType::S2 => "S-2",
+            FbpFuelType::S3 => "S-3",
+            FbpFuelType::O1a => "O-1a",
+            FbpFuelType::O1b => "O-1b",
+            FbpFuelType::NonFuel => "Non-fuel",
+        }
+    }
+}
+
+impl fmt::Display for FbpFuelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Error returned when a string can't be parsed as an [FbpFuelType]
+#[cfg(feature = "std")]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseFuelTypeError(String);
+
+#[cfg(feature = "std")]
+impl fmt::Display for ParseFuelTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown fuel type \"{}\"", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseFuelTypeError {}
+
+#[cfg(feature = "std")]
+impl FromStr for FbpFuelType {
+    type Err = ParseFuelTypeError;
+
+    /// Parse a fuel type, ignoring case, dashes and whitespace (e.g. `"C-2"`, `"c2"` and `"O-1a"`)
+    ///
+    /// ```
+    /// # use cffdrs::fbp_system::FbpFuelType;
+    /// assert_eq!("C-2".parse(), Ok(FbpFuelType::C2));
+    /// assert_eq!("c2".parse(), Ok(FbpFuelType::C2));
+    /// assert_eq!("O-1a".parse(), Ok(FbpFuelType::O1a));
+    /// assert_eq!("non-fuel".parse(), Ok(FbpFuelType::NonFuel));
+    /// assert!("C-8".parse::<FbpFuelType>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        match normalized.as_str() {
+            "nonfuel" | "nf" => Ok(FbpFuelType::NonFuel),
+            _ => FbpFuelType::ALL
+                .into_iter()
+                .find(|fuel_type| fuel_type.code().replace('-', "").to_lowercase() == normalized)
+                .ok_or_else(|| ParseFuelTypeError(s.to_string())),
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::f64::consts::E;
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
 
 use crate::fbp_system::{ros::rate_of_spread, FbpFuelType};
 
@@ -16,32 +18,41 @@ use crate::fbp_system::{ros::rate_of_spread, FbpFuelType};
 ///
 /// Returns back fire rate of spread (m/min)
 #[allow(clippy::too_many_arguments)]
-pub fn back_rate_of_spread(
+pub fn back_rate_of_spread<T: Float>(
     fuel_type: FbpFuelType,
-    ffmc: f64,
-    bui: f64,
-    wsv: f64,
-    fmc: f64,
-    sfc: f64,
-    pc: f64,
-    pdf: f64,
-    cc: f64,
-    cbh: f64,
-) -> f64 {
-    let m = 147.27723 * (101. - ffmc) / (59.5 + ffmc);
-    let ff = 91.9 * E.powf(-0.1386 * m) * (1. + m.powf(5.31) / 4.93e7);
-    // Back fire wind function
-    let bfw = E.powf(-0.05039 * wsv);
-    // ISI associated with the back fire spread rate
-    let bisi = 0.208 * bfw * ff;
+    ffmc: T,
+    bui: T,
+    wsv: T,
+    fmc: T,
+    sfc: T,
+    pc: T,
+    pdf: T,
+    cc: T,
+    cbh: T,
+) -> T {
+    let bisi = back_initial_spread_index(ffmc, wsv);
 
     rate_of_spread(fuel_type, bisi, bui, fmc, sfc, pc, pdf, cc, cbh)
 }
 
+/// ISI associated with the back fire spread rate
+///
+/// * `ffmc` - Fine fuel moisture code
+/// * `wsv` - Wind speed vector
+pub(crate) fn back_initial_spread_index<T: Float>(ffmc: T, wsv: T) -> T {
+    let e = c::<T>(E);
+    let m = c::<T>(147.27723) * (c::<T>(101.) - ffmc) / (c::<T>(59.5) + ffmc);
+    let ff = c::<T>(91.9) * e.powf(c::<T>(-0.1386) * m) * (T::one() + m.powf(c(5.31)) / c(4.93e7));
+    // Back fire wind function
+    let bfw = e.powf(c::<T>(-0.05039) * wsv);
+
+    c::<T>(0.208) * bfw * ff
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -83,4 +94,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_back_rate_of_spread_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/back_rate_of_spread.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                back_rate_of_spread(
+                    record.fuel_type,
+                    record.ffmc as f32,
+                    record.bui as f32,
+                    record.wsv as f32,
+                    record.fmc as f32,
+                    record.sfc as f32,
+                    record.pc as f32,
+                    record.pdf as f32,
+                    record.cc as f32,
+                    record.cbh as f32,
+                ),
+                back_rate_of_spread(
+                    record.fuel_type,
+                    record.ffmc,
+                    record.bui,
+                    record.wsv,
+                    record.fmc,
+                    record.sfc,
+                    record.pc,
+                    record.pdf,
+                    record.cc,
+                    record.cbh,
+                ),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,3 +1,5 @@
+use crate::float::{c, Float};
+
 /// Calculate flank rate of spread (FROS)
 ///
 /// * `ros` - fire rate of spread (m/min)
@@ -20,14 +22,14 @@
 /// assert_eq!(flank_rate_of_spread(393.66, 196.83, -1.), -295.245);
 /// assert_eq!(flank_rate_of_spread(274.59, 393.66, 0.62), 538.9112903225806);
 /// ```
-pub fn flank_rate_of_spread(ros: f64, bros: f64, lb: f64) -> f64 {
-    (ros + bros) / lb / 2.
+pub fn flank_rate_of_spread<T: Float>(ros: T, bros: T, lb: T) -> T {
+    (ros + bros) / lb / c(2.)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -51,4 +53,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_flank_rate_of_spread_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/flank_rate_of_spread.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                flank_rate_of_spread(record.ros as f32, record.bros as f32, record.lb as f32),
+                flank_rate_of_spread(record.ros, record.bros, record.lb),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,4 +1,6 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{self, c, Float};
 
 use crate::fbp_system::{buildup_effect, crown_fraction_burned, FbpFuelType};
 
@@ -14,8 +16,9 @@ impl RateOfSpreadParams {
     }
 }
 
-fn default_rsi_calc(a: f64, b: f64, c: f64, isi: f64) -> f64 {
-    a * (1. - consts::E.powf(-b * isi)).powf(c)
+pub(crate) fn default_rsi_calc<T: Float>(a: f64, b: f64, c: f64, isi: T) -> T {
+    float::c::<T>(a)
+        * (T::one() - float::c::<T>(consts::E).powf(float::c::<T>(-b) * isi)).powf(float::c(c))
 }
 
 /// Fuel type-specific coefficients for rate of spread
@@ -30,7 +33,7 @@ pub fn rate_of_spread_params(fuel_type: FbpFuelType) -> RateOfSpreadParams {
         FbpFuelType::C7 => RateOfSpreadParams::new(45., 0.0305, 2.0),
         FbpFuelType::M3 => RateOfSpreadParams::new(120., 0.0572, 1.4),
         FbpFuelType::M4 => RateOfSpreadParams::new(100., 0.0404, 1.48),
-        FbpFuelType::D1 => RateOfSpreadParams::new(30., 0.0232, 1.6),
+        FbpFuelType::D1 | FbpFuelType::D2 => RateOfSpreadParams::new(30., 0.0232, 1.6),
         FbpFuelType::S1 => RateOfSpreadParams::new(75., 0.0297, 1.3),
         FbpFuelType::S2 => RateOfSpreadParams::new(40., 0.0438, 1.7),
         FbpFuelType::S3 => RateOfSpreadParams::new(55., 0.0829, 3.2),
@@ -45,7 +48,9 @@ pub fn rate_of_spread_params(fuel_type: FbpFuelType) -> RateOfSpreadParams {
 /// * `isi` - Initial spread index
 /// * `pc` - Percent conifer (%)
 /// * `pdf` - Percent dead balsam fir (%)
-fn rsi(fuel_type: FbpFuelType, isi: f64, pc: f64, pdf: f64) -> f64 {
+fn rsi<T: Float>(fuel_type: FbpFuelType, isi: T, pc: T, pdf: T) -> T {
+    let hundred = c::<T>(100.);
+
     match fuel_type {
         FbpFuelType::C1
         | FbpFuelType::C2
@@ -63,73 +68,82 @@ fn rsi(fuel_type: FbpFuelType, isi: f64, pc: f64, pdf: f64) -> f64 {
             default_rsi_calc(params.a, params.b, params.c, isi)
         }
         FbpFuelType::M1 => {
-            (pc / 100.) * (rsi(FbpFuelType::C2, isi, pc, pdf))
-                + ((100. - pc) / 100.) * (rsi(FbpFuelType::D1, isi, pc, pdf))
+            (pc / hundred) * (rsi(FbpFuelType::C2, isi, pc, pdf))
+                + ((hundred - pc) / hundred) * (rsi(FbpFuelType::D1, isi, pc, pdf))
         }
         FbpFuelType::M2 => {
-            (pc / 100.) * (rsi(FbpFuelType::C2, isi, pc, pdf))
-                + 0.2 * ((100. - pc) / 100.) * (rsi(FbpFuelType::D1, isi, pc, pdf))
+            (pc / hundred) * (rsi(FbpFuelType::C2, isi, pc, pdf))
+                + c::<T>(0.2) * ((hundred - pc) / hundred) * (rsi(FbpFuelType::D1, isi, pc, pdf))
         }
         FbpFuelType::M3 => {
             let params_100 = rate_of_spread_params(fuel_type);
             let rsi_100 = default_rsi_calc(params_100.a, params_100.b, params_100.c, isi);
 
-            (pdf / 100.) * rsi_100 + (1. - (pdf / 100.)) * rsi(FbpFuelType::D1, isi, pc, pdf)
+            (pdf / hundred) * rsi_100
+                + (T::one() - (pdf / hundred)) * rsi(FbpFuelType::D1, isi, pc, pdf)
         }
         FbpFuelType::M4 => {
             let params_100 = rate_of_spread_params(fuel_type);
             let rsi_100 = default_rsi_calc(params_100.a, params_100.b, params_100.c, isi);
 
-            (pdf / 100.) * rsi_100 + 0.2 * (1. - pdf / 100.) * rsi(FbpFuelType::D1, isi, pc, pdf)
+            (pdf / hundred) * rsi_100
+                + c::<T>(0.2) * (T::one() - pdf / hundred) * rsi(FbpFuelType::D1, isi, pc, pdf)
         }
-        FbpFuelType::C6 => 30. * (1. - consts::E.powf(-0.08 * isi)).powf(3.0),
+        FbpFuelType::C6 => c6_rsi(isi),
+        // Green aspen spreads at 20% of the rate of leafless aspen
+        FbpFuelType::D2 => c::<T>(0.2) * rsi(FbpFuelType::D1, isi, pc, pdf),
 
-        FbpFuelType::NonFuel => 0.,
+        FbpFuelType::NonFuel => T::zero(),
     }
 }
 
+/// Rate of spread index for C6 (intermediate surface fire spread)
+fn c6_rsi<T: Float>(isi: T) -> T {
+    c::<T>(30.) * (T::one() - c::<T>(consts::E).powf(c::<T>(-0.08) * isi)).powf(c(3.0))
+}
+
 /// Critical surface intensity
-fn csi(fmc: f64, cbh: f64) -> f64 {
-    0.001 * cbh.powf(1.5) * (460. + 25.9 * fmc).powf(1.5)
+pub(crate) fn csi<T: Float>(fmc: T, cbh: T) -> T {
+    c::<T>(0.001) * cbh.powf(c(1.5)) * (c::<T>(460.) + c::<T>(25.9) * fmc).powf(c(1.5))
 }
 
 /// Surface fire rate of spread
-pub fn rso(csi: f64, sfc: f64) -> f64 {
-    csi / (300. * sfc)
+pub fn rso<T: Float>(csi: T, sfc: T) -> T {
+    csi / (c::<T>(300.) * sfc)
 }
 
-pub struct ExtendedRateOfSpread {
+pub struct ExtendedRateOfSpread<T = f64> {
     /// Rate of spread (m/min)
-    pub ros: f64,
+    pub ros: T,
     /// Crown fraction burned
-    pub cfb: f64,
+    pub cfb: T,
     /// Critical surface intensity
-    pub csi: f64,
+    pub csi: T,
     /// Surface fire rate of spread
-    pub rso: f64,
+    pub rso: T,
 }
 
 /// Rate of spread calculation, returning ROS, as well as CFB, CSI and RSO
 ///
 /// See [rate_of_spread()] for a more information
 #[allow(clippy::too_many_arguments)]
-pub fn rate_of_spread_extended(
+pub fn rate_of_spread_extended<T: Float>(
     fuel_type: FbpFuelType,
-    isi: f64,
-    bui: f64,
-    fmc: f64,
-    sfc: f64,
-    pc: f64,
-    pdf: f64,
-    cc: f64,
-    cbh: f64,
-) -> ExtendedRateOfSpread {
+    isi: T,
+    bui: T,
+    fmc: T,
+    sfc: T,
+    pc: T,
+    pdf: T,
+    cc: T,
+    cbh: T,
+) -> ExtendedRateOfSpread<T> {
     let rsi = rsi(fuel_type, isi, pc, pdf);
 
-    let cf = if cc < 58.8 {
-        0.005 * (consts::E.powf(0.061 * cc) - 1.)
+    let cf = if cc < c(58.8) {
+        c::<T>(0.005) * (c::<T>(consts::E).powf(c::<T>(0.061) * cc) - T::one())
     } else {
-        0.176 + 0.02 * (cc - 58.8)
+        c::<T>(0.176) + c::<T>(0.02) * (cc - c(58.8))
     };
 
     let rsi = match fuel_type {
@@ -142,7 +156,7 @@ pub fn rate_of_spread_extended(
     let rso = rso(csi, sfc);
 
     let rsi = match fuel_type {
-        FbpFuelType::C6 => 30. * (1. - consts::E.powf(-0.08 * isi)).powf(3.0),
+        FbpFuelType::C6 => c6_rsi(isi),
         _ => rsi,
     };
 
@@ -150,9 +164,15 @@ pub fn rate_of_spread_extended(
     let rsc = match fuel_type {
         FbpFuelType::C6 => {
             // Average foliar moisture effect
-            let fme_avg = 0.778;
-            let fme = ((1.5 - 0.00275 * fmc).powf(4.0) / (460. + (25.9 * fmc))) * 1000.;
-            Some(60. * (1. - consts::E.powf(-0.0497 * isi)) * (fme / fme_avg))
+            let fme_avg = c::<T>(0.778);
+            let fme = ((c::<T>(1.5) - c::<T>(0.00275) * fmc).powf(c(4.0))
+                / (c::<T>(460.) + (c::<T>(25.9) * fmc)))
+                * c(1000.);
+            Some(
+                c::<T>(60.)
+                    * (T::one() - c::<T>(consts::E).powf(c::<T>(-0.0497) * isi))
+                    * (fme / fme_avg),
+            )
         }
         _ => None,
     };
@@ -164,7 +184,7 @@ pub fn rate_of_spread_extended(
             if rsc.unwrap() > rss && !rso.is_nan() && rss > rso {
                 crown_fraction_burned(rss, rso)
             } else {
-                0.
+                T::zero()
             }
         }
         _ => crown_fraction_burned(rss, rso),
@@ -181,7 +201,7 @@ pub fn rate_of_spread_extended(
         _ => rss,
     };
 
-    let ros = if ros <= 0. { 0.000001 } else { ros };
+    let ros = if ros <= T::zero() { c(0.000001) } else { ros };
 
     ExtendedRateOfSpread { ros, cfb, csi, rso }
 }
@@ -223,29 +243,33 @@ pub fn rate_of_spread_extended(
 ///     2.1900055814792427
 /// );
 /// assert_eq!(
+///     rate_of_spread(FbpFuelType::D2, 30., 60., 0., 0., 0., 0., 0., 0.),
+///     0.2 * rate_of_spread(FbpFuelType::D1, 30., 60., 0., 0., 0., 0., 0., 0.)
+/// );
+/// assert_eq!(
 ///     rate_of_spread(FbpFuelType::S2, 151.2, 437.4, 0., 6561., 27., 0., 0., 0.),
 ///     48.52361319847542
 /// );
 /// ```
 #[allow(clippy::too_many_arguments)]
-pub fn rate_of_spread(
+pub fn rate_of_spread<T: Float>(
     fuel_type: FbpFuelType,
-    isi: f64,
-    bui: f64,
-    fmc: f64,
-    sfc: f64,
-    pc: f64,
-    pdf: f64,
-    cc: f64,
-    cbh: f64,
-) -> f64 {
+    isi: T,
+    bui: T,
+    fmc: T,
+    sfc: T,
+    pc: T,
+    pdf: T,
+    cc: T,
+    cbh: T,
+) -> T {
     rate_of_spread_extended(fuel_type, isi, bui, fmc, sfc, pc, pdf, cc, cbh).ros
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -285,4 +309,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_rate_of_spread_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/rate_of_spread.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                rate_of_spread(
+                    record.fuel_type,
+                    record.isi as f32,
+                    record.bui as f32,
+                    record.fmc as f32,
+                    record.sfc as f32,
+                    record.pc as f32,
+                    record.pdf as f32,
+                    record.cc as f32,
+                    record.cbh as f32,
+                ),
+                rate_of_spread(
+                    record.fuel_type,
+                    record.isi,
+                    record.bui,
+                    record.fmc,
+                    record.sfc,
+                    record.pc,
+                    record.pdf,
+                    record.cc,
+                    record.cbh,
+                ),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,4 +1,6 @@
-use std::f64::consts::FRAC_PI_2;
+use core::f64::consts::FRAC_PI_2;
+
+use crate::float::{c, Float};
 
 /// Calculate rate of spread (ROS) at the perimeter of an elliptically shaped fire
 /// at angle theta
@@ -25,9 +27,9 @@ use std::f64::consts::FRAC_PI_2;
 /// assert_eq!(rate_of_spread_at_theta(349.92, 590.49, 393.66, -163.17_f64.to_radians()), 403.5940759749405);
 /// assert_eq!(rate_of_spread_at_theta(58.32, 10.0, 590.49, -360_f64.to_radians()), 58.32000000000005);
 /// ```
-pub fn rate_of_spread_at_theta(ros: f64, fros: f64, bros: f64, theta: f64) -> f64 {
-    let theta = if theta == FRAC_PI_2 || theta == 3. * FRAC_PI_2 {
-        theta + 0.0001
+pub fn rate_of_spread_at_theta<T: Float>(ros: T, fros: T, bros: T, theta: T) -> T {
+    let theta = if theta == c(FRAC_PI_2) || theta == c(3. * FRAC_PI_2) {
+        theta + c(0.0001)
     } else {
         theta
     };
@@ -36,16 +38,18 @@ pub fn rate_of_spread_at_theta(ros: f64, fros: f64, bros: f64, theta: f64) -> f6
 
     // Eq. 94 (https://cfs.nrcan.gc.ca/pubwarehouse/pdfs/31414.pdf)
 
-    (ros - bros) / (2. * c1)
-        + ((ros + bros) / (2. * c1))
+    let two = c::<T>(2.);
+
+    (ros - bros) / (two * c1)
+        + ((ros + bros) / (two * c1))
             * ((fros * c1 * (fros.powi(2) * c1.powi(2) + (ros * bros) * s1.powi(2)).sqrt()
-                - (((ros.powi(2) - bros.powi(2)) / 4.) * s1.powi(2)))
-                / (fros.powi(2) * c1.powi(2) + ((ros + bros) / 2.0).powi(2) * s1.powi(2)))
+                - (((ros.powi(2) - bros.powi(2)) / c(4.)) * s1.powi(2)))
+                / (fros.powi(2) * c1.powi(2) + ((ros + bros) / two).powi(2) * s1.powi(2)))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_within, precision_f64};
 
     use super::*;
 
@@ -77,4 +81,34 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_rate_of_spread_at_theta_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/ros_at_theta.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            // Convert in f64, so 90 and 270 degrees still hit the special case for the singularity.
+            // Right next to it, the equation cancels out most of the f32 precision
+            assert_f32_within(
+                rate_of_spread_at_theta(
+                    record.ros as f32,
+                    record.fros as f32,
+                    record.bros as f32,
+                    record.theta.to_radians() as f32,
+                ),
+                rate_of_spread_at_theta(
+                    record.ros,
+                    record.fros,
+                    record.bros,
+                    record.theta.to_radians(),
+                ),
+                0.005,
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,169 @@
+//! Maximum spotting distance predictions
+//!
+//! Implements Albini's model for spotting from wind-driven surface fires, in the form used by
+//! BehavePlus. The model is defined in imperial units; the functions here take and return metric
+//! values.
+//!
+//! Spotting from torching trees and crown fires (Albini 1979, Albini et al. 2012) isn't modelled.
+//! For crowning fires, [fbp_spotting_distance()] only uses the intensity of the surface fire, so
+//! the distance is a lower bound.
+//!
+//! References:
+//!
+//! Albini, F.A. 1983. Potential spotting distance from wind-driven surface fires. USDA Forest
+//! Service, Intermountain Forest and Range Experiment Station, Research Paper INT-309. 27 p.
+//!
+//! Chase, C.H. 1984. Spotting distance from wind-driven surface fires - extensions of equations
+//! for pocket calculators. USDA Forest Service, Intermountain Forest and Range Experiment Station,
+//! Research Note INT-346. 21 p.
+
+use crate::float::Float;
+
+#[cfg(feature = "std")]
+use crate::weather::Weather;
+use crate::weather::{
+    wind_speed_at_height, OPEN_ROUGHNESS_LENGTH, STANDARD_WIND_HEIGHT, TWENTY_FOOT_HEIGHT,
+};
+
+#[cfg(feature = "std")]
+use super::{fire_intensity, FbpValues};
+
+/// Metres in a foot
+const M_PER_FT: f64 = 0.3048;
+/// Metres in a mile
+const M_PER_MI: f64 = 1609.344;
+/// kW/m in a BTU/ft/s
+const KW_PER_M_PER_BTU_PER_FT_PER_S: f64 = 3.46414;
+
+/// Height firebrands are lofted to by a wind-driven surface fire
+///
+/// * `fi` - Fire intensity (kW/m)
+/// * `ws` - 10m open wind speed (km/h)
+///
+/// Returns firebrand height (m)
+///
+/// ```
+/// # use cffdrs::fbp_system::spotting::firebrand_height;
+/// assert_eq!(firebrand_height(5000., 20.), 93.64464748704981);
+/// assert_eq!(firebrand_height(0., 20.), 0.);
+/// ```
+pub fn firebrand_height(fi: f64, ws: f64) -> f64 {
+    let fi = fi / KW_PER_M_PER_BTU_PER_FT_PER_S;
+    let ws = wind_speed_20ft_mph(ws);
+
+    if fi <= 0. || ws <= 0. {
+        return 0.;
+    }
+
+    let f = 322. * Float::powf(0.474 * ws, -1.01) * fi;
+
+    1.055 * Float::sqrt(f) * M_PER_FT
+}
+
+/// Maximum spotting distance from a wind-driven surface fire over flat terrain
+///
+/// * `fi` - Fire intensity (kW/m)
+/// * `ws` - 10m open wind speed (km/h)
+/// * `canopy_height` - Height of the cover downwind of the fire (m). For open (non-continuous)
+///   cover, use half the cover height
+///
+/// Returns maximum spotting distance (m)
+///
+/// ```
+/// # use cffdrs::fbp_system::spotting::spotting_distance;
+/// let distance = spotting_distance(5000., 20., 15.);
+/// assert_eq!(distance, 446.4414741322052);
+///
+/// // Higher intensity fires loft firebrands higher, and spot further
+/// assert!(spotting_distance(20000., 20., 15.) > distance);
+/// assert_eq!(spotting_distance(0., 20., 15.), 0.);
+/// ```
+pub fn spotting_distance(fi: f64, ws: f64, canopy_height: f64) -> f64 {
+    let z = firebrand_height(fi, ws) / M_PER_FT;
+    let h = canopy_height / M_PER_FT;
+    let ws = wind_speed_20ft_mph(ws);
+
+    if z <= 0. || h <= 0. || ws <= 0. {
+        return 0.;
+    }
+
+    // Distance firebrands drift while falling through the canopy (mi)
+    let drift = 0.000278 * ws * Float::powf(z, 0.643);
+    // Distance travelled above the canopy (mi)
+    let flat =
+        0.000718 * ws * Float::sqrt(h) * (0.362 + Float::sqrt(z / h) / 2. * Float::ln(z / h));
+
+    ((drift + flat) * M_PER_MI).max(0.)
+}
+
+/// Maximum spotting distance for the head fire predicted by the FBP system
+///
+/// Uses the surface fire intensity at the head (from surface fuel consumption only) and the 10m
+/// open wind speed. See [spotting_distance()]. The model is for surface fires, so crown fuel
+/// consumption isn't included when the fire is crowning, and the distance is a lower bound.
+///
+/// * `fbp` - FBP values (see [calculate_fbp()][crate::fbp_system::calculate_fbp()])
+/// * `weather` - Weather used to calculate `fbp`
+/// * `canopy_height` - Height of the cover downwind of the fire (m)
+///
+/// Returns maximum spotting distance (m)
+#[cfg(feature = "std")]
+pub fn fbp_spotting_distance(fbp: &FbpValues, weather: &Weather, canopy_height: f64) -> f64 {
+    spotting_distance(fire_intensity(fbp.sfc, fbp.ros), weather.ws, canopy_height)
+}
+
+/// Convert 10m open wind speed (km/h) to 20ft wind speed (mi/h), using the same wind profile as
+/// [open_wind_speed()][crate::weather::open_wind_speed()]
+fn wind_speed_20ft_mph(ws: f64) -> f64 {
+    let ws = wind_speed_at_height(
+        ws,
+        STANDARD_WIND_HEIGHT,
+        TWENTY_FOOT_HEIGHT,
+        OPEN_ROUGHNESS_LENGTH,
+    );
+
+    ws * 1000. / M_PER_MI
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::*;
+    use crate::{
+        fbp_system::{calculate_fbp, FbpFuelType},
+        fwi_system::{calculate_hourly, StartingFwiValues},
+    };
+
+    #[test]
+    fn test_fbp_spotting_distance_crown_fire() {
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 9, 10, 11).unwrap(),
+            location: geo::Point::new(-122., 37.),
+            temp: 35.,
+            rh: 5.,
+            ws: 35.,
+            wd: 45.,
+            precip: 0.,
+        };
+        let fwi_values = calculate_hourly(
+            &weather,
+            Some(&StartingFwiValues {
+                dc: 60.,
+                dmc: 60.,
+                ffmc: 90.,
+            }),
+        );
+        let fbp = calculate_fbp(FbpFuelType::C2, &fwi_values, &weather, Default::default());
+        assert!(fbp.cfb > 0.9);
+
+        // Only the surface fire lofts firebrands
+        let distance = fbp_spotting_distance(&fbp, &weather, 15.);
+        assert_eq!(
+            distance,
+            spotting_distance(300. * fbp.sfc * fbp.ros, weather.ws, 15.)
+        );
+        assert!(distance > 0.);
+        assert!(distance < spotting_distance(fbp.fi, weather.ws, 15.));
+    }
+}
@@ -0,0 +1,366 @@
+//! Raster cell-based fire growth using minimum travel time (MTT)
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{fwi_system::HourlyFwiValues, grid::Grid, weather::Weather};
+
+use super::{
+    calculate_fbp, fire_intensity, ros::rate_of_spread_at_theta, FbpFuelType, FbpOptions, FbpValues,
+};
+
+/// Which neighbouring cells fire can travel to directly from a cell
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Connectivity {
+    /// Orthogonal and diagonal neighbours
+    Eight,
+    /// Orthogonal and diagonal neighbours, plus "knight's move" neighbours. Produces rounder,
+    /// less angular fire shapes than [`Connectivity::Eight`]
+    Sixteen,
+}
+
+impl Connectivity {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        const EIGHT: [(isize, isize); 8] = [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        const SIXTEEN: [(isize, isize); 16] = [
+            (0, -1),
+            (1, -2),
+            (1, -1),
+            (2, -1),
+            (1, 0),
+            (2, 1),
+            (1, 1),
+            (1, 2),
+            (0, 1),
+            (-1, 2),
+            (-1, 1),
+            (-2, 1),
+            (-1, 0),
+            (-2, -1),
+            (-1, -1),
+            (-1, -2),
+        ];
+
+        match self {
+            Connectivity::Eight => &EIGHT,
+            Connectivity::Sixteen => &SIXTEEN,
+        }
+    }
+}
+
+/// Output of [minimum_travel_time()]
+#[derive(Debug, Clone)]
+pub struct SpreadGrids {
+    /// Fire arrival time at each cell (min since ignition). Cells the fire never reaches are
+    /// [`f64::INFINITY`]
+    pub arrival_time: Grid<f64>,
+    /// Fire intensity in the direction the fire arrived from (kW/m). Cells the fire never reaches
+    /// are `0`
+    pub intensity: Grid<f64>,
+}
+
+/// Entry in the travel time priority queue, ordered so the earliest arrival is popped first
+#[derive(PartialEq)]
+struct Arrival {
+    time: f64,
+    index: usize,
+}
+
+impl Eq for Arrival {}
+
+impl Ord for Arrival {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .total_cmp(&self.time)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Arrival {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Calculate fire arrival times across a grid using the minimum travel time method
+///
+/// FBP values are calculated for each cell from its fuel type, slope and aspect, then fire is
+/// spread outward from the ignition cells using Dijkstra's algorithm. The rate of spread between
+/// neighbouring cells is direction-dependent (see
+/// [rate_of_spread_at_theta()][crate::fbp_system::ros::rate_of_spread_at_theta()]), and the
+/// travel time between two cells is the time to cross half of each cell at its own rate.
+///
+/// * `fuel_types` - Fuel type of each cell. [`FbpFuelType::NonFuel`] cells never burn
+/// * `slope` - Ground slope of each cell (%)
+/// * `aspect` - Slope azimuth of each cell (degrees)
+/// * `fwi` - FWI values, applied to every cell
+/// * `weather` - Weather, applied to every cell
+/// * `options` - FBP options applied to every cell. `slope` and `aspect` are taken from the grids
+/// * `ignitions` - Ignition cells as `(col, row)`, burning at time 0
+/// * `connectivity` - Which neighbours fire can travel to from each cell
+/// * `max_time` - Stop spreading after this many minutes (optional)
+///
+/// # Panics
+///
+/// Panics if the grids don't all have the same dimensions
+///
+/// # Examples
+///
+/// ```
+/// # use cffdrs::fbp_system::{minimum_travel_time, Connectivity, FbpFuelType, FbpOptions};
+/// # use cffdrs::fwi_system::HourlyFwiValues;
+/// # use cffdrs::grid::Grid;
+/// # use cffdrs::weather::Weather;
+/// # use chrono::prelude::*;
+/// let weather = Weather {
+///     time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+///     location: geo::Point::new(-120.34, 50.69),
+///     temp: 28.,
+///     rh: 20.,
+///     ws: 20.,
+///     wd: 270.,
+///     precip: 0.,
+/// };
+/// let fwi = HourlyFwiValues { ffmc: 92., dmc: 40., dc: 300., isi: 12.6, bui: 62., fwi: 27. };
+///
+/// let fuel_types = Grid::filled(21, 21, 100., FbpFuelType::C2);
+/// let flat = Grid::filled(21, 21, 100., 0.);
+///
+/// let spread = minimum_travel_time(
+///     &fuel_types,
+///     &flat,
+///     &flat,
+///     &fwi,
+///     &weather,
+///     &FbpOptions::default(),
+///     &[(10, 10)],
+///     Connectivity::Sixteen,
+///     None,
+/// );
+///
+/// // Wind from the west pushes the fire east faster than it backs west
+/// let east = spread.arrival_time.get(15, 10).unwrap();
+/// let west = spread.arrival_time.get(5, 10).unwrap();
+/// assert!(east < west);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimum_travel_time(
+    fuel_types: &Grid<FbpFuelType>,
+    slope: &Grid<f64>,
+    aspect: &Grid<f64>,
+    fwi: &HourlyFwiValues,
+    weather: &Weather,
+    options: &FbpOptions,
+    ignitions: &[(usize, usize)],
+    connectivity: Connectivity,
+    max_time: Option<f64>,
+) -> SpreadGrids {
+    assert!(
+        fuel_types.same_shape(slope) && fuel_types.same_shape(aspect),
+        "fuel type, slope and aspect grids must have the same dimensions"
+    );
+
+    let cols = fuel_types.cols;
+    let rows = fuel_types.rows;
+    let cell_size = fuel_types.cell_size;
+    let max_time = max_time.unwrap_or(f64::INFINITY);
+
+    let fbp: Vec<Option<FbpValues>> = fuel_types
+        .data
+        .iter()
+        .zip(slope.data.iter().zip(aspect.data.iter()))
+        .map(|(&fuel_type, (&slope, &aspect))| match fuel_type {
+            FbpFuelType::NonFuel => None,
+            _ => Some(calculate_fbp(
+                fuel_type,
+                fwi,
+                weather,
+                FbpOptions {
+                    slope,
+                    aspect,
+                    ..options.clone()
+                },
+            )),
+        })
+        .collect();
+
+    // Rate of spread of a cell towards a neighbour at azimuth `azimuth` (radians)
+    let ros_towards = |values: &FbpValues, azimuth: f64| {
+        rate_of_spread_at_theta(values.ros, values.fros, values.bros, azimuth - values.raz).max(0.)
+    };
+
+    let mut arrival_time = Grid::filled(cols, rows, cell_size, f64::INFINITY);
+    let mut intensity = Grid::filled(cols, rows, cell_size, 0.);
+    let mut queue = BinaryHeap::new();
+
+    for &(col, row) in ignitions {
+        if col < cols && row < rows {
+            let index = arrival_time.index(col, row);
+            if fbp[index].is_some() {
+                arrival_time.data[index] = 0.;
+                queue.push(Arrival { time: 0., index });
+            }
+        }
+    }
+
+    while let Some(Arrival { time, index }) = queue.pop() {
+        if time > arrival_time.data[index] {
+            // Already reached sooner from another cell
+            continue;
+        }
+
+        let Some(from) = &fbp[index] else {
+            continue;
+        };
+        let col = (index % cols) as isize;
+        let row = (index / cols) as isize;
+
+        for &(dx, dy) in connectivity.offsets() {
+            let (ncol, nrow) = (col + dx, row + dy);
+            if ncol < 0 || nrow < 0 || ncol >= cols as isize || nrow >= rows as isize {
+                continue;
+            }
+            let neighbour = nrow as usize * cols + ncol as usize;
+            let Some(to) = &fbp[neighbour] else {
+                continue;
+            };
+
+            // Rows increase southwards, so north is -y
+            let azimuth = (dx as f64).atan2(-dy as f64);
+            let half_distance = cell_size * ((dx * dx + dy * dy) as f64).sqrt() / 2.;
+
+            let ros_from = ros_towards(from, azimuth);
+            let ros_to = ros_towards(to, azimuth);
+            if ros_from <= 0. || ros_to <= 0. {
+                continue;
+            }
+
+            let arrival = time + half_distance / ros_from + half_distance / ros_to;
+
+            if arrival < arrival_time.data[neighbour] && arrival <= max_time {
+                arrival_time.data[neighbour] = arrival;
+                intensity.data[neighbour] = fire_intensity(to.tfc, ros_to);
+                queue.push(Arrival {
+                    time: arrival,
+                    index: neighbour,
+                });
+            }
+        }
+    }
+
+    for (index, values) in fbp.iter().enumerate() {
+        if let Some(values) = values {
+            if arrival_time.data[index] == 0. {
+                intensity.data[index] = values.fi;
+            }
+        }
+    }
+
+    SpreadGrids {
+        arrival_time,
+        intensity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::*;
+
+    fn weather() -> Weather {
+        Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 28.,
+            rh: 20.,
+            ws: 20.,
+            wd: 270.,
+            precip: 0.,
+        }
+    }
+
+    fn fwi() -> HourlyFwiValues {
+        HourlyFwiValues {
+            ffmc: 92.,
+            dmc: 40.,
+            dc: 300.,
+            isi: 12.6,
+            bui: 62.,
+            fwi: 27.,
+        }
+    }
+
+    #[test]
+    fn test_minimum_travel_time_non_fuel_barrier() {
+        let mut fuel_types = Grid::filled(9, 9, 100., FbpFuelType::C2);
+        for row in 0..9 {
+            let index = fuel_types.index(4, row);
+            fuel_types.data[index] = FbpFuelType::NonFuel;
+        }
+        let flat = Grid::filled(9, 9, 100., 0.);
+
+        let spread = minimum_travel_time(
+            &fuel_types,
+            &flat,
+            &flat,
+            &fwi(),
+            &weather(),
+            &FbpOptions::default(),
+            &[(1, 4)],
+            Connectivity::Eight,
+            None,
+        );
+
+        assert_eq!(*spread.arrival_time.get(1, 4).unwrap(), 0.);
+        assert!(spread.arrival_time.get(3, 4).unwrap().is_finite());
+        assert!(spread.arrival_time.get(4, 4).unwrap().is_infinite());
+        assert!(spread.arrival_time.get(7, 4).unwrap().is_infinite());
+        assert_eq!(*spread.intensity.get(7, 4).unwrap(), 0.);
+    }
+
+    #[test]
+    fn test_minimum_travel_time_head_fire() {
+        let fuel_types = Grid::filled(11, 11, 50., FbpFuelType::C2);
+        let flat = Grid::filled(11, 11, 50., 0.);
+        let weather = weather();
+        let fwi = fwi();
+
+        let spread = minimum_travel_time(
+            &fuel_types,
+            &flat,
+            &flat,
+            &fwi,
+            &weather,
+            &FbpOptions::default(),
+            &[(5, 5)],
+            Connectivity::Eight,
+            Some(60.),
+        );
+
+        let fbp = calculate_fbp(FbpFuelType::C2, &fwi, &weather, FbpOptions::default());
+
+        // Straight downwind the fire should travel at the head fire rate of spread
+        let east = *spread.arrival_time.get(10, 5).unwrap();
+        approx::assert_relative_eq!(east, 250. / fbp.ros, max_relative = 1e-6);
+
+        // Back fire spreads slowest, flank in between
+        let west = *spread.arrival_time.get(0, 5).unwrap();
+        let north = *spread.arrival_time.get(5, 0).unwrap();
+        assert!(east < north);
+        assert!(north < west || west.is_infinite());
+
+        for time in &spread.arrival_time.data {
+            assert!(*time <= 60. || time.is_infinite());
+        }
+    }
+}
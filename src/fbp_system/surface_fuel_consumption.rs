@@ -1,4 +1,6 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{c, Float};
 
 use super::FbpFuelType;
 
@@ -6,47 +8,150 @@ use super::FbpFuelType;
 ///
 /// * `ffmc` - Fine fuel moisture code
 /// * `bui` - Buildup index
-pub fn surface_fuel_consumption(fuel_type: FbpFuelType, ffmc: f64, bui: f64) -> f64 {
+/// * `pc` - Percent conifer (%), only used for M1/M2. M3/M4 use the C2 equation regardless of
+///   percent dead balsam fir, as specified by the standard (ST-X-3)
+/// * `gfl` - Grass fuel load (kg/m^2), only used for O1a/O1b (standard = 0.3 kg/m^2)
+///
+/// # Examples
+///
+/// ```
+/// # use cffdrs::fbp_system::{surface_fuel_consumption, FbpFuelType};
+/// let sfc = surface_fuel_consumption(FbpFuelType::M1, 90., 60., 75., 0.3);
+/// assert_eq!(sfc, 2.1190131814829067);
+///
+/// // 100% conifer M1 consumes the same as C2, 0% conifer the same as D1
+/// assert_eq!(
+///     surface_fuel_consumption(FbpFuelType::M1, 90., 60., 100., 0.3),
+///     surface_fuel_consumption(FbpFuelType::C2, 90., 60., 100., 0.3)
+/// );
+/// assert_eq!(
+///     surface_fuel_consumption(FbpFuelType::M2, 90., 60., 0., 0.3),
+///     surface_fuel_consumption(FbpFuelType::D1, 90., 60., 0., 0.3)
+/// );
+/// ```
+pub fn surface_fuel_consumption<T: Float>(
+    fuel_type: FbpFuelType,
+    ffmc: T,
+    bui: T,
+    pc: T,
+    gfl: T,
+) -> T {
+    let e = c::<T>(consts::E);
+    let one = T::one();
+
     match fuel_type {
-        FbpFuelType::C1 => (1.5 * (1. - consts::E.powf(-0.230 * (ffmc - 81.)))).max(0.),
+        FbpFuelType::C1 => {
+            (c::<T>(1.5) * (one - e.powf(c::<T>(-0.230) * (ffmc - c(81.))))).max(T::zero())
+        }
         FbpFuelType::C2 | FbpFuelType::M3 | FbpFuelType::M4 => {
-            5.0 * (1. - consts::E.powf(-0.0115 * bui)).powf(1.)
+            c::<T>(5.0) * (one - e.powf(c::<T>(-0.0115) * bui)).powf(one)
+        }
+        FbpFuelType::C3 | FbpFuelType::C4 => {
+            c::<T>(5.0) * (one - e.powf(c::<T>(-0.0164) * bui)).powf(c(2.24))
+        }
+        FbpFuelType::C5 | FbpFuelType::C6 => {
+            c::<T>(5.0) * (one - e.powf(c::<T>(-0.0149) * bui)).powf(c(2.48))
         }
-        FbpFuelType::C3 | FbpFuelType::C4 => 5.0 * (1. - consts::E.powf(-0.0164 * bui)).powf(2.24),
-        FbpFuelType::C5 | FbpFuelType::C6 => 5.0 * (1. - consts::E.powf(-0.0149 * bui)).powf(2.48),
         FbpFuelType::C7 => {
-            let ffc = (2. * (1. - consts::E.powf(-0.104 * (ffmc - 70.)))).max(0.);
-            let wfc = 1.5 * (1. - consts::E.powf(-0.0201 * bui));
+            let ffc =
+                (c::<T>(2.) * (one - e.powf(c::<T>(-0.104) * (ffmc - c(70.))))).max(T::zero());
+            let wfc = c::<T>(1.5) * (one - e.powf(c::<T>(-0.0201) * bui));
             ffc + wfc
         }
-        FbpFuelType::D1 => 1.5 * (1. - consts::E.powf(-0.0183 * bui)),
+        FbpFuelType::D1 | FbpFuelType::D2 => c::<T>(1.5) * (one - e.powf(c::<T>(-0.0183) * bui)),
         FbpFuelType::M1 | FbpFuelType::M2 => {
-            let pc = 50.;
-            let ph = 50.;
-            (pc / 100. * surface_fuel_consumption(FbpFuelType::C2, ffmc, bui))
-                + (ph / 100. * surface_fuel_consumption(FbpFuelType::D1, ffmc, bui))
-        }
-        FbpFuelType::O1b | FbpFuelType::O1a => {
-            // Grass fuel load (standard = 0.3km/m^2)
-            
-            0.3
+            // Percent hardwood
+            let ph = c::<T>(100.) - pc;
+            (pc / c(100.) * surface_fuel_consumption(FbpFuelType::C2, ffmc, bui, pc, gfl))
+                + (ph / c(100.) * surface_fuel_consumption(FbpFuelType::D1, ffmc, bui, pc, gfl))
         }
+        FbpFuelType::O1b | FbpFuelType::O1a => gfl,
         FbpFuelType::S1 => {
-            let ffc = 4.0 * (1. - consts::E.powf(-0.025 * bui));
-            let wfc = 4.0 * (1. - consts::E.powf(-0.034 * bui));
+            let ffc = c::<T>(4.0) * (one - e.powf(c::<T>(-0.025) * bui));
+            let wfc = c::<T>(4.0) * (one - e.powf(c::<T>(-0.034) * bui));
             ffc + wfc
         }
         FbpFuelType::S2 => {
-            let ffc = 10.0 * (1. - consts::E.powf(-0.013 * bui));
-            let wfc = 6.0 * (1. - consts::E.powf(-0.060 * bui));
+            let ffc = c::<T>(10.0) * (one - e.powf(c::<T>(-0.013) * bui));
+            let wfc = c::<T>(6.0) * (one - e.powf(c::<T>(-0.060) * bui));
             ffc + wfc
         }
         FbpFuelType::S3 => {
-            let ffc = 12.0 * (1. - consts::E.powf(-0.0166 * bui));
-            let wfc = 20.0 * (1. - consts::E.powf(-0.0210 * bui));
+            let ffc = c::<T>(12.0) * (one - e.powf(c::<T>(-0.0166) * bui));
+            let wfc = c::<T>(20.0) * (one - e.powf(c::<T>(-0.0210) * bui));
             ffc + wfc
         }
 
-        FbpFuelType::NonFuel => 0.,
+        FbpFuelType::NonFuel => T::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_f32_close, precision_f64};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct TestRow {
+        fuel_type: FbpFuelType,
+        ffmc: f64,
+        bui: f64,
+        pc: f64,
+        gfl: f64,
+        sfc: f64,
+    }
+
+    // Expected values are ST-X-3 (Forestry Canada Fire Danger Group 1992) equations 9 to 25,
+    // calculated separately from this implementation. They match R cffdrs `.SFCcalc()` except
+    // for C1, where R uses the revised Wotton et al. (2009) equation, and zero consumption, which
+    // R raises to 0.000001.
+    #[test]
+    fn test_surface_fuel_consumption() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/surface_fuel_consumption.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+            let sfc = surface_fuel_consumption(
+                record.fuel_type,
+                record.ffmc,
+                record.bui,
+                record.pc,
+                record.gfl,
+            );
+
+            assert_eq!(precision_f64(sfc, 4), record.sfc);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_surface_fuel_consumption_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/surface_fuel_consumption.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                surface_fuel_consumption(
+                    record.fuel_type,
+                    record.ffmc as f32,
+                    record.bui as f32,
+                    record.pc as f32,
+                    record.gfl as f32,
+                ),
+                surface_fuel_consumption(
+                    record.fuel_type,
+                    record.ffmc,
+                    record.bui,
+                    record.pc,
+                    record.gfl,
+                ),
+            );
+        }
+
+        Ok(())
     }
 }
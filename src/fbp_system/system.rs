@@ -1,12 +1,11 @@
-use std::f64::consts::PI;
+use core::f64::consts::PI;
 
 use crate::{fwi_system::HourlyFwiValues, weather::Weather};
 
 use super::{
-    crown_fuel_consumption, crown_fuel_load, fire_intensity, foliar_moisture_content,
-    length_to_breadth,
-    ros::{back_rate_of_spread, flank_rate_of_spread, rate_of_spread_extended},
-    slope_adjustment, surface_fuel_consumption, total_fuel_consumption, FbpFuelType,
+    fire_intensity, foliar_moisture_content,
+    ros::{back_initial_spread_index, flank_rate_of_spread},
+    seasonal_fuel_type, total_fuel_consumption, FbpFuelType, FuelModel, LeafOnSeason,
 };
 
 #[cfg(feature = "serde")]
@@ -45,6 +44,7 @@ pub struct FbpValues {
     pub fi: f64,
 }
 
+#[derive(Debug, Clone)]
 pub struct FbpOptions {
     pub elevation: Option<f64>,
     pub date_of_minimum_fmc: Option<i32>,
@@ -52,13 +52,21 @@ pub struct FbpOptions {
     pub slope: f64,
     /// Slope azimuth (degrees)
     pub aspect: f64,
-    /// Curing constant (%)
+    /// Degree of curing for grass fuel types (%)
     pub curing: f64,
+    /// Grass fuel load (kg/m^2), used for O1a/O1b fuel consumption and intensity
+    pub grass_fuel_load: f64,
     /// Percent conifer (%)
     pub percent_conifer: f64,
     /// Percent dead balsam fir (%)
     pub percent_dead_balsam: f64,
-    /// Crown base height (m) - can be calculated with the [`crown_base_height()`][crate::fbp_system::crown_base_height()] function
+    /// Leaf-on season. When set, D1/D2, M1/M2 and M3/M4 fuel types are selected automatically from
+    /// the date (see [`seasonal_fuel_type()`][crate::fbp_system::seasonal_fuel_type()])
+    pub leaf_on_season: Option<LeafOnSeason>,
+    /// Crown base height (m), e.g. from
+    /// [`crown_base_height()`][crate::fbp_system::crown_base_height()]. When 0 (the default) or
+    /// less, the fuel type's default crown base height is used (see
+    /// [`FuelModel::crown_base_height()`]), as in the R cffdrs package
     pub crown_base_height: f64,
 }
 
@@ -70,8 +78,10 @@ impl Default for FbpOptions {
             slope: 0.,
             aspect: 0.,
             curing: 80.,
+            grass_fuel_load: 0.3,
             percent_conifer: 50.,
             percent_dead_balsam: 35.,
+            leaf_on_season: None,
             crown_base_height: 0.,
         }
     }
@@ -84,22 +94,53 @@ pub fn calculate_fbp(
     weather: &Weather,
     options: FbpOptions,
 ) -> FbpValues {
-    let julian_date = julian::Date::from(weather.time.naive_utc().date());
+    let fuel_type = match &options.leaf_on_season {
+        Some(season) => seasonal_fuel_type(fuel_type, day_of_year(weather), season),
+        None => fuel_type,
+    };
+
+    calculate_fbp_for_fuel(&fuel_type, fwi, weather, options)
+}
+
+/// Calculate all FBP values from weather and FWI values for any [FuelModel], including custom
+/// fuel types described by [FuelParameters][crate::fbp_system::FuelParameters]
+///
+/// Unlike [calculate_fbp()], [`FbpOptions::leaf_on_season`] isn't applied.
+pub fn calculate_fbp_for_fuel<F: FuelModel + ?Sized>(
+    fuel: &F,
+    fwi: &HourlyFwiValues,
+    weather: &Weather,
+    options: FbpOptions,
+) -> FbpValues {
     let lat = weather.location.y();
     let long = weather.location.x();
 
+    let day_of_year = day_of_year(weather);
+
     let fmc = foliar_moisture_content(
         lat,
         long,
-        julian_date.ordinal() as i32,
+        day_of_year,
         options.elevation,
         options.date_of_minimum_fmc,
     );
 
-    let sfc = surface_fuel_consumption(fuel_type, fwi.ffmc, fwi.bui);
+    let cc = options.curing;
+
+    let cbh = if options.crown_base_height > 0. {
+        options.crown_base_height
+    } else {
+        fuel.crown_base_height()
+    };
 
-    let (wsz, wsv) = slope_adjustment(
-        fuel_type,
+    let sfc = fuel.surface_fuel_consumption(
+        fwi.ffmc,
+        fwi.bui,
+        options.percent_conifer,
+        options.grass_fuel_load,
+    );
+
+    let (wsz, wsv) = fuel.slope_adjustment(
         fwi.ffmc,
         weather.ws,
         weather.wd.to_radians(),
@@ -109,39 +150,37 @@ pub fn calculate_fbp(
         sfc,
         options.percent_conifer,
         options.percent_dead_balsam,
-        options.curing,
-        options.crown_base_height,
+        cc,
+        cbh,
     );
 
-    let ros = rate_of_spread_extended(
-        fuel_type,
+    let ros = fuel.rate_of_spread_extended(
         fwi.isi,
         fwi.bui,
         fmc,
         sfc,
         options.percent_conifer,
         options.percent_dead_balsam,
-        options.curing,
-        options.crown_base_height,
-    );
-    let bros = back_rate_of_spread(
-        fuel_type,
-        fwi.ffmc,
-        fwi.bui,
-        wsv,
-        fmc,
-        sfc,
-        options.percent_conifer,
-        options.percent_dead_balsam,
-        options.curing,
-        options.crown_base_height,
+        cc,
+        cbh,
     );
-    let lb = length_to_breadth(fuel_type, wsv);
+    let bros = fuel
+        .rate_of_spread_extended(
+            back_initial_spread_index(fwi.ffmc, wsv),
+            fwi.bui,
+            fmc,
+            sfc,
+            options.percent_conifer,
+            options.percent_dead_balsam,
+            cc,
+            cbh,
+        )
+        .ros;
+    let lb = fuel.length_to_breadth(wsv);
     let fros = flank_rate_of_spread(ros.ros, bros, lb);
 
-    let cfl = crown_fuel_load(fuel_type);
-    let cfc = crown_fuel_consumption(
-        fuel_type,
+    let cfl = fuel.crown_fuel_load();
+    let cfc = fuel.crown_fuel_consumption(
         cfl,
         ros.cfb,
         options.percent_conifer,
@@ -164,13 +203,18 @@ pub fn calculate_fbp(
         cfc,
         tfc,
         cfl,
-        cbh: options.crown_base_height,
+        cbh,
         wsv,
         wsz,
         fi,
     }
 }
 
+/// Julian day of year of the weather
+pub(crate) fn day_of_year(weather: &Weather) -> i32 {
+    julian::Date::from(weather.time.naive_utc().date()).ordinal() as i32
+}
+
 #[cfg(test)]
 mod tests {
     use approx::assert_ulps_eq;
@@ -178,7 +222,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        fbp_system::crown_base_height,
+        fbp_system::{crown_base_height, FuelParameters},
         fwi_system::{calculate_hourly, StartingFwiValues},
     };
 
@@ -230,4 +274,159 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_calculate_fbp_grass() {
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 8, 20, 20, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 30.,
+            rh: 15.,
+            ws: 20.,
+            wd: 270.,
+            precip: 0.,
+        };
+        let fwi_values = calculate_hourly(&weather, None);
+
+        let standard = calculate_fbp(FbpFuelType::O1b, &fwi_values, &weather, Default::default());
+        let heavy = calculate_fbp(
+            FbpFuelType::O1b,
+            &fwi_values,
+            &weather,
+            FbpOptions {
+                grass_fuel_load: 0.6,
+                ..Default::default()
+            },
+        );
+
+        assert_ulps_eq!(standard.sfc, 0.3);
+        assert_ulps_eq!(heavy.sfc, 0.6);
+        assert_ulps_eq!(heavy.ros, standard.ros);
+        assert_ulps_eq!(heavy.fi, 2. * standard.fi);
+
+        // Fully cured grass spreads faster than 80% curing
+        let cured = calculate_fbp(
+            FbpFuelType::O1b,
+            &fwi_values,
+            &weather,
+            FbpOptions {
+                curing: 100.,
+                ..Default::default()
+            },
+        );
+        assert!(cured.ros > standard.ros);
+    }
+
+    #[test]
+    fn test_calculate_fbp_leaf_on_season() {
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 30.,
+            rh: 15.,
+            ws: 20.,
+            wd: 270.,
+            precip: 0.,
+        };
+        let fwi_values = calculate_hourly(&weather, None);
+        let season = LeafOnSeason {
+            green_up: 150,
+            leaf_fall: 270,
+        };
+
+        let seasonal = calculate_fbp(
+            FbpFuelType::D1,
+            &fwi_values,
+            &weather,
+            FbpOptions {
+                leaf_on_season: Some(season),
+                ..Default::default()
+            },
+        );
+        let green = calculate_fbp(FbpFuelType::D2, &fwi_values, &weather, Default::default());
+        let leafless = calculate_fbp(FbpFuelType::D1, &fwi_values, &weather, Default::default());
+
+        assert_ulps_eq!(seasonal.ros, green.ros);
+        assert!(green.ros < leafless.ros);
+    }
+
+    #[test]
+    fn test_calculate_fbp_for_fuel_parameters() {
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 30.,
+            rh: 15.,
+            ws: 20.,
+            wd: 270.,
+            precip: 0.,
+        };
+        let fwi_values = calculate_hourly(&weather, None);
+        let options = FbpOptions {
+            slope: 20.,
+            aspect: 45.,
+            crown_base_height: 3.,
+            ..Default::default()
+        };
+
+        let expected = calculate_fbp(FbpFuelType::C3, &fwi_values, &weather, options.clone());
+        let custom = calculate_fbp_for_fuel(
+            &FuelParameters::from_fuel_type(FbpFuelType::C3),
+            &fwi_values,
+            &weather,
+            options,
+        );
+
+        assert_ulps_eq!(custom.ros, expected.ros);
+        assert_ulps_eq!(custom.bros, expected.bros);
+        assert_ulps_eq!(custom.fros, expected.fros);
+        assert_ulps_eq!(custom.cfb, expected.cfb);
+        assert_ulps_eq!(custom.fi, expected.fi);
+        assert_ulps_eq!(custom.raz, expected.raz);
+    }
+
+    #[test]
+    fn test_calculate_fbp_for_fuel_parameters_cbh() {
+        let weather = Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            location: geo::Point::new(-120.34, 50.69),
+            temp: 35.,
+            rh: 5.,
+            ws: 40.,
+            wd: 270.,
+            precip: 0.,
+        };
+        let fwi_values = calculate_hourly(
+            &weather,
+            Some(&StartingFwiValues {
+                dc: 300.,
+                dmc: 60.,
+                ffmc: 92.,
+            }),
+        );
+
+        let expected = calculate_fbp(
+            FbpFuelType::C3,
+            &fwi_values,
+            &weather,
+            FbpOptions {
+                crown_base_height: 6.,
+                ..Default::default()
+            },
+        );
+        let custom = calculate_fbp_for_fuel(
+            &FuelParameters {
+                cbh: 6.,
+                ..FuelParameters::from_fuel_type(FbpFuelType::C3)
+            },
+            &fwi_values,
+            &weather,
+            Default::default(),
+        );
+
+        assert!(expected.cfb > 0.);
+        assert_ulps_eq!(custom.cbh, 6.);
+        assert_ulps_eq!(custom.cfb, expected.cfb);
+        assert_ulps_eq!(custom.fi, expected.fi);
+    }
 }
@@ -0,0 +1,602 @@
+//! C ABI for embedding the FWI and FBP systems in other languages
+//!
+//! Mirrors [Weather], [StartingFwiValues], [HourlyFwiValues], [FbpOptions] and [FbpValues] with
+//! `#[repr(C)]` structs, and exposes the FWI equations, [calculate_hourly()] and
+//! [calculate_fbp()] as `extern "C"` functions. The C header is at `include/cffdrs.h`, and is
+//! generated with [cbindgen](https://github.com/mozilla/cbindgen):
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/cffdrs.h
+//! ```
+//!
+//! Requires the `ffi` feature.
+
+use std::{
+    ffi::{c_char, CStr},
+    ptr,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    fbp_system::{calculate_fbp, FbpFuelType, FbpOptions, FbpValues, LeafOnSeason},
+    fwi_system::{
+        buildup_index, calculate_hourly, drought_code, duff_moisture_code, fire_weather_index,
+        hourly_fine_fuel_moisture_code, initial_spread_index, HourlyFwiValues, StartingFwiValues,
+    },
+    weather::Weather,
+};
+
+/// Result of an FFI call
+#[repr(C)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum CffdrsStatus {
+    Ok = 0,
+    /// A required pointer was null
+    NullPointer = 1,
+    /// The fuel type isn't a valid [CffdrsFuelType]
+    InvalidFuelType = 2,
+    /// The weather timestamp is out of range
+    InvalidTime = 3,
+}
+
+/// FBP fuel types, in the same order as [FbpFuelType]
+#[repr(C)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum CffdrsFuelType {
+    C1 = 0,
+    C2,
+    C3,
+    C4,
+    C5,
+    C6,
+    C7,
+    M1,
+    M2,
+    M3,
+    M4,
+    D1,
+    D2,
+    S1,
+    S2,
+    S3,
+    O1a,
+    O1b,
+    NonFuel,
+}
+
+impl CffdrsFuelType {
+    const ALL: [CffdrsFuelType; 19] = [
+        CffdrsFuelType::C1,
+        CffdrsFuelType::C2,
+        CffdrsFuelType::C3,
+        CffdrsFuelType::C4,
+        CffdrsFuelType::C5,
+        CffdrsFuelType::C6,
+        CffdrsFuelType::C7,
+        CffdrsFuelType::M1,
+        CffdrsFuelType::M2,
+        CffdrsFuelType::M3,
+        CffdrsFuelType::M4,
+        CffdrsFuelType::D1,
+        CffdrsFuelType::D2,
+        CffdrsFuelType::S1,
+        CffdrsFuelType::S2,
+        CffdrsFuelType::S3,
+        CffdrsFuelType::O1a,
+        CffdrsFuelType::O1b,
+        CffdrsFuelType::NonFuel,
+    ];
+}
+
+/// C mirror of [Weather]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CffdrsWeather {
+    /// UTC time the weather was sampled (seconds since the Unix epoch)
+    pub timestamp: i64,
+    /// Latitude (degrees)
+    pub lat: f64,
+    /// Longitude (degrees)
+    pub long: f64,
+    /// Temperature (Celcius)
+    pub temp: f64,
+    /// Relative humidity (%)
+    pub rh: f64,
+    /// Wind speed (at 10m height, km/h)
+    pub ws: f64,
+    /// Wind direction (degrees, 0 = wind from north, 90 = from east, etc.)
+    pub wd: f64,
+    /// Precipitation (mm)
+    pub precip: f64,
+}
+
+/// C mirror of [StartingFwiValues]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CffdrsStartingFwiValues {
+    pub dc: f64,
+    pub dmc: f64,
+    pub ffmc: f64,
+}
+
+/// C mirror of [HourlyFwiValues]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CffdrsHourlyFwiValues {
+    pub isi: f64,
+    pub dc: f64,
+    pub dmc: f64,
+    pub ffmc: f64,
+    pub bui: f64,
+    pub fwi: f64,
+}
+
+/// C mirror of [FbpOptions]. Use [cffdrs_fbp_options_default()] for the defaults
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CffdrsFbpOptions {
+    /// Elevation (m), or NaN if unknown
+    pub elevation: f64,
+    /// Julian day of minimum foliar moisture content, or 0 to calculate it from the location
+    pub date_of_minimum_fmc: i32,
+    /// Slope (%)
+    pub slope: f64,
+    /// Slope azimuth (degrees)
+    pub aspect: f64,
+    /// Degree of curing for grass fuel types (%)
+    pub curing: f64,
+    /// Grass fuel load (kg/m^2)
+    pub grass_fuel_load: f64,
+    /// Percent conifer (%)
+    pub percent_conifer: f64,
+    /// Percent dead balsam fir (%)
+    pub percent_dead_balsam: f64,
+    /// Julian day of green-up, or 0 to not switch fuel types seasonally
+    pub green_up: i32,
+    /// Julian day of leaf fall, or 0 to not switch fuel types seasonally
+    pub leaf_fall: i32,
+    /// Crown base height (m), or 0 to use the fuel type's default
+    pub crown_base_height: f64,
+}
+
+/// C mirror of [FbpValues]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CffdrsFbpValues {
+    /// Crown fraction burned (0-1)
+    pub cfb: f64,
+    /// Direction of spread (radians)
+    pub raz: f64,
+    /// Head rate of spread (m/min)
+    pub ros: f64,
+    /// Flank rate of spread (m/min)
+    pub fros: f64,
+    /// Back rate of spread  (m/min)
+    pub bros: f64,
+    /// Foliar moisture content
+    pub fmc: f64,
+    /// Surface fuel consumption (kg/m^2)
+    pub sfc: f64,
+    /// Crown fuel consumption (kg/m^2)
+    pub cfc: f64,
+    /// Total fuel consumption (kg/m^2)
+    pub tfc: f64,
+    /// Crown fuel load
+    pub cfl: f64,
+    /// Crown base height (m)
+    pub cbh: f64,
+    /// Effective wind speed (km/h)
+    pub wsv: f64,
+    /// Effective wind direction (radians)
+    pub wsz: f64,
+    /// Head fire intensity (kW/m)
+    pub fi: f64,
+}
+
+impl TryFrom<&CffdrsWeather> for Weather {
+    type Error = CffdrsStatus;
+
+    fn try_from(weather: &CffdrsWeather) -> Result<Self, Self::Error> {
+        Ok(Weather {
+            time: DateTime::<Utc>::from_timestamp(weather.timestamp, 0)
+                .ok_or(CffdrsStatus::InvalidTime)?,
+            location: geo::Point::new(weather.long, weather.lat),
+            temp: weather.temp,
+            rh: weather.rh,
+            ws: weather.ws,
+            wd: weather.wd,
+            precip: weather.precip,
+        })
+    }
+}
+
+impl From<&CffdrsHourlyFwiValues> for HourlyFwiValues {
+    fn from(values: &CffdrsHourlyFwiValues) -> Self {
+        HourlyFwiValues {
+            isi: values.isi,
+            dc: values.dc,
+            dmc: values.dmc,
+            ffmc: values.ffmc,
+            bui: values.bui,
+            fwi: values.fwi,
+        }
+    }
+}
+
+impl From<HourlyFwiValues> for CffdrsHourlyFwiValues {
+    fn from(values: HourlyFwiValues) -> Self {
+        CffdrsHourlyFwiValues {
+            isi: values.isi,
+            dc: values.dc,
+            dmc: values.dmc,
+            ffmc: values.ffmc,
+            bui: values.bui,
+            fwi: values.fwi,
+        }
+    }
+}
+
+impl From<&CffdrsFbpOptions> for FbpOptions {
+    fn from(options: &CffdrsFbpOptions) -> Self {
+        FbpOptions {
+            elevation: (!options.elevation.is_nan()).then_some(options.elevation),
+            date_of_minimum_fmc: (options.date_of_minimum_fmc > 0)
+                .then_some(options.date_of_minimum_fmc),
+            slope: options.slope,
+            aspect: options.aspect,
+            curing: options.curing,
+            grass_fuel_load: options.grass_fuel_load,
+            percent_conifer: options.percent_conifer,
+            percent_dead_balsam: options.percent_dead_balsam,
+            leaf_on_season: (options.green_up > 0 && options.leaf_fall > 0).then_some(
+                LeafOnSeason {
+                    green_up: options.green_up,
+                    leaf_fall: options.leaf_fall,
+                },
+            ),
+            crown_base_height: options.crown_base_height,
+        }
+    }
+}
+
+impl From<FbpValues> for CffdrsFbpValues {
+    fn from(values: FbpValues) -> Self {
+        CffdrsFbpValues {
+            cfb: values.cfb,
+            raz: values.raz,
+            ros: values.ros,
+            fros: values.fros,
+            bros: values.bros,
+            fmc: values.fmc,
+            sfc: values.sfc,
+            cfc: values.cfc,
+            tfc: values.tfc,
+            cfl: values.cfl,
+            cbh: values.cbh,
+            wsv: values.wsv,
+            wsz: values.wsz,
+            fi: values.fi,
+        }
+    }
+}
+
+/// Fuel type for a [CffdrsFuelType] value
+fn fuel_type_from_c(fuel_type: i32) -> Result<FbpFuelType, CffdrsStatus> {
+    usize::try_from(fuel_type)
+        .ok()
+        .and_then(|index| FbpFuelType::ALL.get(index))
+        .copied()
+        .ok_or(CffdrsStatus::InvalidFuelType)
+}
+
+/// Hourly Fine Fuel Moisture Code. See
+/// [hourly_fine_fuel_moisture_code()][crate::fwi_system::hourly_fine_fuel_moisture_code()]
+#[no_mangle]
+pub extern "C" fn cffdrs_hourly_fine_fuel_moisture_code(
+    temp: f64,
+    rh: f64,
+    ws: f64,
+    precip: f64,
+    previous_ffmc: f64,
+    timestep: f64,
+) -> f64 {
+    hourly_fine_fuel_moisture_code(temp, rh, ws, precip, previous_ffmc, Some(timestep))
+}
+
+/// Duff Moisture Code. See [duff_moisture_code()][crate::fwi_system::duff_moisture_code()]
+#[no_mangle]
+pub extern "C" fn cffdrs_duff_moisture_code(
+    prev_dmc: f64,
+    temp: f64,
+    rh: f64,
+    precip: f64,
+    lat: f64,
+    mon: i32,
+    lat_adjustment: bool,
+) -> f64 {
+    duff_moisture_code(
+        prev_dmc,
+        temp,
+        rh,
+        precip,
+        lat,
+        mon.max(1) as usize,
+        Some(lat_adjustment),
+    )
+}
+
+/// Drought Code. See [drought_code()][crate::fwi_system::drought_code()]
+#[no_mangle]
+pub extern "C" fn cffdrs_drought_code(
+    prev_dc: f64,
+    temp: f64,
+    precip: f64,
+    lat: f64,
+    mon: i32,
+    lat_adjustment: bool,
+) -> f64 {
+    drought_code(
+        prev_dc,
+        temp,
+        precip,
+        lat,
+        mon.max(1) as usize,
+        Some(lat_adjustment),
+    )
+}
+
+/// Initial Spread Index. See [initial_spread_index()][crate::fwi_system::initial_spread_index()]
+#[no_mangle]
+pub extern "C" fn cffdrs_initial_spread_index(ffmc: f64, ws: f64) -> f64 {
+    initial_spread_index(ffmc, ws)
+}
+
+/// Buildup Index. See [buildup_index()][crate::fwi_system::buildup_index()]
+#[no_mangle]
+pub extern "C" fn cffdrs_buildup_index(dmc: f64, dc: f64) -> f64 {
+    buildup_index(dmc, dc)
+}
+
+/// Fire Weather Index. See [fire_weather_index()][crate::fwi_system::fire_weather_index()]
+#[no_mangle]
+pub extern "C" fn cffdrs_fire_weather_index(isi: f64, bui: f64) -> f64 {
+    fire_weather_index(isi, bui)
+}
+
+/// Default FBP options
+#[no_mangle]
+pub extern "C" fn cffdrs_fbp_options_default() -> CffdrsFbpOptions {
+    let defaults = FbpOptions::default();
+
+    CffdrsFbpOptions {
+        elevation: defaults.elevation.unwrap_or(f64::NAN),
+        date_of_minimum_fmc: defaults.date_of_minimum_fmc.unwrap_or(0),
+        slope: defaults.slope,
+        aspect: defaults.aspect,
+        curing: defaults.curing,
+        grass_fuel_load: defaults.grass_fuel_load,
+        percent_conifer: defaults.percent_conifer,
+        percent_dead_balsam: defaults.percent_dead_balsam,
+        green_up: 0,
+        leaf_fall: 0,
+        crown_base_height: defaults.crown_base_height,
+    }
+}
+
+/// Parse a fuel type code such as `"C-2"` or `"o1a"` (see [FbpFuelType]'s `FromStr`)
+///
+/// # Safety
+///
+/// `code` must be a valid null-terminated string, and `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn cffdrs_parse_fuel_type(
+    code: *const c_char,
+    out: *mut CffdrsFuelType,
+) -> CffdrsStatus {
+    if code.is_null() || out.is_null() {
+        return CffdrsStatus::NullPointer;
+    }
+
+    let fuel_type = CStr::from_ptr(code)
+        .to_str()
+        .ok()
+        .and_then(|code| code.parse::<FbpFuelType>().ok());
+
+    match fuel_type.and_then(|fuel_type| FbpFuelType::ALL.iter().position(|&f| f == fuel_type)) {
+        Some(index) => {
+            ptr::write(out, CffdrsFuelType::ALL[index]);
+            CffdrsStatus::Ok
+        }
+        None => CffdrsStatus::InvalidFuelType,
+    }
+}
+
+/// Calculate all values for the FWI system from hourly weather. See
+/// [calculate_hourly()][crate::fwi_system::calculate_hourly()]
+///
+/// * `previous` - FWI values from the previous hour. May be null
+///
+/// # Safety
+///
+/// `weather` must be valid for reads, `previous` must be null or valid for reads, and `out` must
+/// be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn cffdrs_calculate_hourly(
+    weather: *const CffdrsWeather,
+    previous: *const CffdrsStartingFwiValues,
+    out: *mut CffdrsHourlyFwiValues,
+) -> CffdrsStatus {
+    if weather.is_null() || out.is_null() {
+        return CffdrsStatus::NullPointer;
+    }
+
+    let weather = match Weather::try_from(&*weather) {
+        Ok(weather) => weather,
+        Err(status) => return status,
+    };
+    let previous = previous.as_ref().map(|previous| StartingFwiValues {
+        dc: previous.dc,
+        dmc: previous.dmc,
+        ffmc: previous.ffmc,
+    });
+
+    ptr::write(out, calculate_hourly(&weather, previous.as_ref()).into());
+
+    CffdrsStatus::Ok
+}
+
+/// Calculate all FBP values from weather and FWI values. See
+/// [calculate_fbp()][crate::fbp_system::calculate_fbp()]
+///
+/// * `fuel_type` - A [CffdrsFuelType]
+/// * `options` - FBP options. May be null to use the defaults
+///
+/// # Safety
+///
+/// `fwi` and `weather` must be valid for reads, `options` must be null or valid for reads, and
+/// `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn cffdrs_calculate_fbp(
+    fuel_type: i32,
+    fwi: *const CffdrsHourlyFwiValues,
+    weather: *const CffdrsWeather,
+    options: *const CffdrsFbpOptions,
+    out: *mut CffdrsFbpValues,
+) -> CffdrsStatus {
+    if fwi.is_null() || weather.is_null() || out.is_null() {
+        return CffdrsStatus::NullPointer;
+    }
+
+    let fuel_type = match fuel_type_from_c(fuel_type) {
+        Ok(fuel_type) => fuel_type,
+        Err(status) => return status,
+    };
+    let weather = match Weather::try_from(&*weather) {
+        Ok(weather) => weather,
+        Err(status) => return status,
+    };
+    let options = options.as_ref().map(FbpOptions::from).unwrap_or_default();
+
+    ptr::write(
+        out,
+        calculate_fbp(fuel_type, &HourlyFwiValues::from(&*fwi), &weather, options).into(),
+    );
+
+    CffdrsStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::MaybeUninit;
+
+    use chrono::prelude::*;
+
+    use super::*;
+
+    fn weather() -> CffdrsWeather {
+        CffdrsWeather {
+            timestamp: Utc
+                .with_ymd_and_hms(2023, 7, 8, 9, 10, 11)
+                .unwrap()
+                .timestamp(),
+            lat: 50.69,
+            long: -120.34,
+            temp: 28.,
+            rh: 12.,
+            ws: 16.,
+            wd: 95.,
+            precip: 0.,
+        }
+    }
+
+    #[test]
+    fn test_ffi_calculate() {
+        let weather = weather();
+        let mut fwi = MaybeUninit::uninit();
+        let status = unsafe { cffdrs_calculate_hourly(&weather, ptr::null(), fwi.as_mut_ptr()) };
+        assert_eq!(status, CffdrsStatus::Ok);
+        let fwi = unsafe { fwi.assume_init() };
+
+        let expected = calculate_hourly(&Weather::try_from(&weather).unwrap(), None);
+        assert_eq!(fwi.ffmc, expected.ffmc);
+        assert_eq!(fwi.fwi, expected.fwi);
+
+        let options = CffdrsFbpOptions {
+            slope: 30.,
+            ..cffdrs_fbp_options_default()
+        };
+        let mut fbp = MaybeUninit::uninit();
+        let status = unsafe {
+            cffdrs_calculate_fbp(
+                CffdrsFuelType::C2 as i32,
+                &fwi,
+                &weather,
+                &options,
+                fbp.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, CffdrsStatus::Ok);
+        let fbp = unsafe { fbp.assume_init() };
+
+        let expected = calculate_fbp(
+            FbpFuelType::C2,
+            &HourlyFwiValues::from(&fwi),
+            &Weather::try_from(&weather).unwrap(),
+            FbpOptions {
+                slope: 30.,
+                ..FbpOptions::default()
+            },
+        );
+        assert_eq!(fbp.ros, expected.ros);
+        assert_eq!(fbp.fi, expected.fi);
+    }
+
+    #[test]
+    fn test_ffi_errors() {
+        let weather = weather();
+        let fwi = CffdrsHourlyFwiValues::from(calculate_hourly(
+            &Weather::try_from(&weather).unwrap(),
+            None,
+        ));
+        let mut fbp = MaybeUninit::uninit();
+
+        unsafe {
+            assert_eq!(
+                cffdrs_calculate_fbp(19, &fwi, &weather, ptr::null(), fbp.as_mut_ptr()),
+                CffdrsStatus::InvalidFuelType
+            );
+            assert_eq!(
+                cffdrs_calculate_fbp(0, ptr::null(), &weather, ptr::null(), fbp.as_mut_ptr()),
+                CffdrsStatus::NullPointer
+            );
+            assert_eq!(
+                cffdrs_calculate_hourly(
+                    &CffdrsWeather {
+                        timestamp: i64::MAX,
+                        ..weather
+                    },
+                    ptr::null(),
+                    &mut CffdrsHourlyFwiValues::from(calculate_hourly(
+                        &Weather::try_from(&weather).unwrap(),
+                        None
+                    )),
+                ),
+                CffdrsStatus::InvalidTime
+            );
+        }
+    }
+
+    #[test]
+    fn test_ffi_fuel_types() {
+        for (index, fuel_type) in FbpFuelType::ALL.iter().enumerate() {
+            assert_eq!(fuel_type_from_c(index as i32), Ok(*fuel_type));
+
+            let code = std::ffi::CString::new(fuel_type.code()).unwrap();
+            let mut parsed = MaybeUninit::uninit();
+            let status = unsafe { cffdrs_parse_fuel_type(code.as_ptr(), parsed.as_mut_ptr()) };
+            assert_eq!(status, CffdrsStatus::Ok);
+            assert_eq!(unsafe { parsed.assume_init() } as usize, index);
+        }
+    }
+}
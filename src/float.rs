@@ -0,0 +1,23 @@
+//! Floating point support for the equations
+//!
+//! The FWI and FBP equations are generic over [Float], so they can be calculated with either
+//! `f32` or `f64`. Constants are converted from `f64`, so `f64` results are unchanged, and number
+//! literals still default to `f64`.
+//!
+//! `f32` results stay within 0.1% of the `f64` results (or 0.001 for results smaller than 1),
+//! which is checked against the test fixtures, with two exceptions:
+//!
+//! * [rate_of_spread_at_theta()][crate::fbp_system::ros::rate_of_spread_at_theta()] within
+//!   0.0001 radians of the flanks (90 and 270 degrees) is only within 0.5%
+//! * [slope_adjustment()][crate::fbp_system::slope_adjustment()] needs an FFMC of at least 40,
+//!   below which the ISI with no wind rounds the rate of spread down to zero
+//!
+//! Without the `std` feature, the floating point functions are provided by `libm`.
+
+pub use num_traits::Float;
+
+/// Convert an `f64` constant to `T`
+#[inline(always)]
+pub(crate) fn c<T: Float>(value: f64) -> T {
+    T::from(value).expect("f64 constants are representable by every float type")
+}
@@ -1,18 +1,24 @@
+use crate::float::{c, Float};
+
 /// Calculate Buildup Index (BUI)
 ///
 /// * `dmc` - Duff moisture code
 /// * `dc` - Drought code
-pub fn buildup_index(dmc: f64, dc: f64) -> f64 {
-    let bui1 = if dmc == 0. && dc == 0. {
-        0.
+pub fn buildup_index<T: Float>(dmc: T, dc: T) -> T {
+    let bui1 = if dmc == T::zero() && dc == T::zero() {
+        T::zero()
     } else {
-        0.8 * dc * dmc / (dmc + 0.4 * dc)
+        c::<T>(0.8) * dc * dmc / (dmc + c::<T>(0.4) * dc)
     };
 
-    let p = if dmc == 0. { 0. } else { (dmc - bui1) / dmc };
-    let cc = 0.92 + (0.0114 * dmc).powf(1.7);
+    let p = if dmc == T::zero() {
+        T::zero()
+    } else {
+        (dmc - bui1) / dmc
+    };
+    let cc = c::<T>(0.92) + (c::<T>(0.0114) * dmc).powf(c(1.7));
 
-    let bui0 = (dmc - cc * p).max(0.);
+    let bui0 = (dmc - cc * p).max(T::zero());
 
     if bui1 < dmc {
         bui0
@@ -24,7 +30,7 @@ pub fn buildup_index(dmc: f64, dc: f64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -47,4 +53,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_buildup_index_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/bui.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                buildup_index(record.dmc as f32, record.dc as f32),
+                buildup_index(record.dmc, record.dc),
+            );
+        }
+
+        Ok(())
+    }
 }
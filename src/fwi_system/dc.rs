@@ -1,4 +1,6 @@
-use std::f64::consts::E;
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
 
 /// Day length factor, north of 20 degrees N
 const FL01: [f64; 12] = [
@@ -17,27 +19,27 @@ const FL02: [f64; 12] = [
 /// * `lat` - Latitutde (decimal degrees)
 /// * `mon` - Month (1-12)
 /// * `lat_adjustment` - Whether to apply latitude adjustment (optional, default = `true`)
-pub fn drought_code(
-    prev_dc: f64,
-    temp: f64,
-    precip: f64,
-    lat: f64,
+pub fn drought_code<T: Float>(
+    prev_dc: T,
+    temp: T,
+    precip: T,
+    lat: T,
     mon: usize,
     lat_adjustment: Option<bool>,
-) -> f64 {
+) -> T {
     let lat_adjustment = lat_adjustment.unwrap_or(true);
 
-    let temp = temp.max(-2.8);
+    let temp = temp.max(c(-2.8));
 
     let index = (mon).clamp(1, 12) - 1;
 
-    let pe = (0.36 * (temp + 2.8) + FL01[index]) / 2.;
+    let pe = (c::<T>(0.36) * (temp + c(2.8)) + c(FL01[index])) / c(2.);
 
     let pe = if lat_adjustment {
-        if lat <= -20. {
-            (0.36 * (temp + 2.8) + FL02[index]) / 2.
-        } else if lat > -20. && lat <= 20. {
-            (0.36 * (temp + 2.8) + 1.4) / 2.
+        if lat <= c(-20.) {
+            (c::<T>(0.36) * (temp + c(2.8)) + c(FL02[index])) / c(2.)
+        } else if lat > c(-20.) && lat <= c(20.) {
+            (c::<T>(0.36) * (temp + c(2.8)) + c(1.4)) / c(2.)
         } else {
             pe
         }
@@ -45,24 +47,24 @@ pub fn drought_code(
         pe
     };
 
-    let pe = pe.max(0.);
+    let pe = pe.max(T::zero());
 
     let ra = precip;
-    let rw = 0.83 * ra - 1.27;
+    let rw = c::<T>(0.83) * ra - c(1.27);
 
-    let smi = 800. * E.powf(-prev_dc / 400.);
+    let smi = c::<T>(800.) * c::<T>(E).powf(-prev_dc / c(400.));
 
-    let dr0 = (prev_dc - 400. * (1. + 3.937 * rw / smi).ln()).max(0.);
+    let dr0 = (prev_dc - c::<T>(400.) * (T::one() + c::<T>(3.937) * rw / smi).ln()).max(T::zero());
 
-    let dr = if precip <= 2.8 { prev_dc } else { dr0 };
+    let dr = if precip <= c(2.8) { prev_dc } else { dr0 };
 
-    (dr + pe).max(0.)
+    (dr + pe).max(T::zero())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -96,4 +98,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_drought_code_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/dc.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                drought_code(
+                    record.prev_dc as f32,
+                    record.temp as f32,
+                    record.precip as f32,
+                    record.lat as f32,
+                    record.mon,
+                    record.lat_adjustment,
+                ),
+                drought_code(
+                    record.prev_dc,
+                    record.temp,
+                    record.precip,
+                    record.lat,
+                    record.mon,
+                    record.lat_adjustment,
+                ),
+            );
+        }
+
+        Ok(())
+    }
 }
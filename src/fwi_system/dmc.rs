@@ -1,4 +1,6 @@
-use std::f64::consts::E;
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
 
 /// 46N: Canadian standard, latitude >= 30N   (Van Wagner 1987)
 const ELL01: [f64; 12] = [6.5, 7.5, 9., 12.8, 13.9, 13.9, 12.4, 10.9, 9.4, 8., 7., 6.];
@@ -20,32 +22,32 @@ const ELL04: [f64; 12] = [
 /// * `lat` - Latitutde (decimal degrees)
 /// * `mon` - Month (1-12)
 /// * `lat_adjustment` - Whether to apply latitude adjustment (optional, default = `true`)
-pub fn duff_moisture_code(
-    prev_dmc: f64,
-    temp: f64,
-    rh: f64,
-    precip: f64,
-    lat: f64,
+pub fn duff_moisture_code<T: Float>(
+    prev_dmc: T,
+    temp: T,
+    rh: T,
+    precip: T,
+    lat: T,
     mon: usize,
     lat_adjustment: Option<bool>,
-) -> f64 {
+) -> T {
     let lat_adjustment = lat_adjustment.unwrap_or(true);
 
     let index = (mon).clamp(1, 12) - 1;
 
-    let temp = temp.max(-1.1);
+    let temp = temp.max(c(-1.1));
 
-    let rk = 1.894 * (temp + 1.1) * (100. - rh) * ELL01[index] * 1e-4;
+    let rk = c::<T>(1.894) * (temp + c(1.1)) * (c::<T>(100.) - rh) * c(ELL01[index]) * c(1e-4);
 
     let rk = if lat_adjustment {
-        if lat <= 30. && lat > 10. {
-            1.894 * (temp + 1.1) * (100. - rh) * ELL02[index] * 1e-4
-        } else if lat <= -10. && lat > -30. {
-            1.894 * (temp + 1.1) * (100. - rh) * ELL03[index] * 1e-4
-        } else if (-90. ..=-30.).contains(&lat) {
-            1.894 * (temp + 1.1) * (100. - rh) * ELL04[index] * 1e-4
-        } else if lat <= 10. && lat > -10. {
-            1.894 * (temp + 1.1) * (100. - rh) * 9. * 1e-4
+        if lat <= c(30.) && lat > c(10.) {
+            c::<T>(1.894) * (temp + c(1.1)) * (c::<T>(100.) - rh) * c(ELL02[index]) * c(1e-4)
+        } else if lat <= c(-10.) && lat > c(-30.) {
+            c::<T>(1.894) * (temp + c(1.1)) * (c::<T>(100.) - rh) * c(ELL03[index]) * c(1e-4)
+        } else if (c(-90.)..=c(-30.)).contains(&lat) {
+            c::<T>(1.894) * (temp + c(1.1)) * (c::<T>(100.) - rh) * c(ELL04[index]) * c(1e-4)
+        } else if lat <= c(10.) && lat > c(-10.) {
+            c::<T>(1.894) * (temp + c(1.1)) * (c::<T>(100.) - rh) * c(9.) * c(1e-4)
         } else {
             rk
         }
@@ -53,35 +55,35 @@ pub fn duff_moisture_code(
         rk
     };
 
-    let pr = if precip <= 1.5 {
+    let pr = if precip <= c(1.5) {
         prev_dmc
     } else {
         let ra = precip;
 
-        let rw = 0.92 * ra - 1.27;
-        let wmi = 20. + 280. / E.powf(0.023 * prev_dmc);
-        let b = if prev_dmc <= 33. {
-            100. / (0.5 + 0.3 * prev_dmc)
-        } else if prev_dmc <= 65. {
-            14. - 1.3 * prev_dmc.ln()
+        let rw = c::<T>(0.92) * ra - c(1.27);
+        let wmi = c::<T>(20.) + c::<T>(280.) / c::<T>(E).powf(c::<T>(0.023) * prev_dmc);
+        let b = if prev_dmc <= c(33.) {
+            c::<T>(100.) / (c::<T>(0.5) + c::<T>(0.3) * prev_dmc)
+        } else if prev_dmc <= c(65.) {
+            c::<T>(14.) - c::<T>(1.3) * prev_dmc.ln()
         } else {
-            6.2 * prev_dmc.ln() - 17.2
+            c::<T>(6.2) * prev_dmc.ln() - c(17.2)
         };
 
-        let wmr = wmi + 1000. * rw / (48.77 + b * rw);
+        let wmr = wmi + c::<T>(1000.) * rw / (c::<T>(48.77) + b * rw);
 
-        43.43 * (5.6348 - (wmr - 20.).ln())
+        c::<T>(43.43) * (c::<T>(5.6348) - (wmr - c(20.)).ln())
     };
 
-    let pr = pr.max(0.);
+    let pr = pr.max(T::zero());
 
-    (pr + rk).max(0.)
+    (pr + rk).max(T::zero())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -117,4 +119,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_duff_moisture_code_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/dmc.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                duff_moisture_code(
+                    record.prev_dmc as f32,
+                    record.temp as f32,
+                    record.rh as f32,
+                    record.precip as f32,
+                    record.lat as f32,
+                    record.mon,
+                    record.lat_adjustment,
+                ),
+                duff_moisture_code(
+                    record.prev_dmc,
+                    record.temp,
+                    record.rh,
+                    record.precip,
+                    record.lat,
+                    record.mon,
+                    record.lat_adjustment,
+                ),
+            );
+        }
+
+        Ok(())
+    }
 }
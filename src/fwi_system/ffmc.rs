@@ -0,0 +1,103 @@
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
+
+/// Fine fuel moisture code (FFMC), calculated daily from noon weather (Van Wagner 1987)
+///
+/// Converts between FFMC and moisture content with the original coefficient of 147.2, like R
+/// cffdrs `fwi()`, rather than the 147.27723 used by the hourly FFMC and the FBP system.
+///
+/// * `temp` - Noon temperature (Celcius)
+/// * `rh` - Noon relative humidity (%)
+/// * `ws` - Noon 10m height wind speed (km/h)
+/// * `precip` - 24-hour rainfall (mm)
+/// * `previous_ffmc` - Yesterday's FFMC
+///
+/// ```
+/// # use cffdrs::fwi_system::fine_fuel_moisture_code;
+/// let ffmc = fine_fuel_moisture_code(17., 42., 25., 0., 85.);
+/// assert_eq!(ffmc, 87.69298009277445);
+///
+/// // Rain wets the fine fuels
+/// assert!(fine_fuel_moisture_code(17., 42., 25., 10., 85.) < 85.);
+/// ```
+pub fn fine_fuel_moisture_code<T: Float>(temp: T, rh: T, ws: T, precip: T, previous_ffmc: T) -> T {
+    let e = c::<T>(E);
+
+    let mo = c::<T>(147.2) * (c::<T>(101.) - previous_ffmc) / (c::<T>(59.5) + previous_ffmc);
+
+    // Rain is reduced by 0.5mm for interception by the canopy
+    let mo = if precip > c(0.5) {
+        let rf = precip - c(0.5);
+        let mr = mo
+            + c::<T>(42.5)
+                * rf
+                * e.powf(c::<T>(-100.) / (c::<T>(251.) - mo))
+                * (T::one() - e.powf(c::<T>(-6.93) / rf));
+        let mr = if mo > c(150.) {
+            mr + c::<T>(0.0015) * (mo - c(150.)).powi(2) * rf.sqrt()
+        } else {
+            mr
+        };
+
+        mr.min(c(250.))
+    } else {
+        mo
+    };
+
+    let ed = c::<T>(0.942) * rh.powf(c(0.679))
+        + c::<T>(11.) * e.powf((rh - c(100.)) / c(10.))
+        + c::<T>(0.18) * (c::<T>(21.1) - temp) * (T::one() - e.powf(c::<T>(-0.115) * rh));
+
+    let ew = c::<T>(0.618) * rh.powf(c(0.753))
+        + c::<T>(10.) * e.powf((rh - c(100.)) / c(10.))
+        + c::<T>(0.18) * (c::<T>(21.1) - temp) * (T::one() - e.powf(c::<T>(-0.115) * rh));
+
+    let m = if mo > ed {
+        let ko = c::<T>(0.424) * (T::one() - (rh / c(100.)).powf(c(1.7)))
+            + c::<T>(0.0694) * ws.sqrt() * (T::one() - (rh / c(100.)).powi(8));
+        let kd = ko * c(0.581) * e.powf(c::<T>(0.0365) * temp);
+
+        ed + (mo - ed) * c::<T>(10.).powf(-kd)
+    } else if mo < ew {
+        let k1 = c::<T>(0.424) * (T::one() - ((c::<T>(100.) - rh) / c(100.)).powf(c(1.7)))
+            + c::<T>(0.0694) * ws.sqrt() * (T::one() - ((c::<T>(100.) - rh) / c(100.)).powi(8));
+        let kw = k1 * c(0.581) * e.powf(c::<T>(0.0365) * temp);
+
+        ew - (ew - mo) * c::<T>(10.).powf(-kw)
+    } else {
+        mo
+    };
+
+    (c::<T>(59.5) * (c::<T>(250.) - m) / (c::<T>(147.2) + m)).clamp(T::zero(), c(101.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_f32_close;
+
+    #[test]
+    fn test_fine_fuel_moisture_code_f32() {
+        for temp in [-10., 5., 20., 35.] {
+            for rh in [5., 30., 60., 95.] {
+                for ws in [0., 15., 40.] {
+                    for precip in [0., 0.4, 2., 20.] {
+                        for previous_ffmc in [20., 60., 85., 95.] {
+                            assert_f32_close(
+                                fine_fuel_moisture_code(
+                                    temp as f32,
+                                    rh as f32,
+                                    ws as f32,
+                                    precip as f32,
+                                    previous_ffmc as f32,
+                                ),
+                                fine_fuel_moisture_code(temp, rh, ws, precip, previous_ffmc),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
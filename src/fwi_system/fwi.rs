@@ -1,27 +1,31 @@
-use std::f64::consts::E;
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
 
 /// Calculate fire weather index (FWI)
 ///
 /// * `isi` - Initial spread index (see [initial_spread_index](crate::fwi_system::initial_spread_index))
 /// * `bui` - Buildup index (see [buildup_index](crate::fwi_system::buildup_index))
-pub fn fire_weather_index(isi: f64, bui: f64) -> f64 {
-    let bb = if bui > 80. {
-        0.1 * isi * (1000. / (25. + 108.64 / E.powf(0.023 * bui)))
+pub fn fire_weather_index<T: Float>(isi: T, bui: T) -> T {
+    let bb = if bui > c(80.) {
+        c::<T>(0.1)
+            * isi
+            * (c::<T>(1000.) / (c::<T>(25.) + c::<T>(108.64) / c::<T>(E).powf(c::<T>(0.023) * bui)))
     } else {
-        0.1 * isi * (0.626 * bui.powf(0.809) + 2.)
+        c::<T>(0.1) * isi * (c::<T>(0.626) * bui.powf(c(0.809)) + c(2.))
     };
 
-    if bb <= 1. {
+    if bb <= T::one() {
         bb
     } else {
-        E.powf(2.72 * (0.434 * bb.ln()).powf(0.647))
+        c::<T>(E).powf(c::<T>(2.72) * (c::<T>(0.434) * bb.ln()).powf(c(0.647)))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -44,4 +48,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_fire_weather_index_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/fwi.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                fire_weather_index(record.isi as f32, record.bui as f32),
+                fire_weather_index(record.isi, record.bui),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,4 +1,6 @@
-use std::f64::consts::E;
+use core::f64::consts::E;
+
+use crate::float::{c, Float};
 
 /// Hourly fine fuel moisture code (HFFMC)
 ///
@@ -9,60 +11,67 @@ use std::f64::consts::E;
 /// * `previous_hffmc` - Previous hour's HFFMC
 /// * `timestep` - Timestep between calculations, in hours (default 1 hour, optional). Note that
 ///   using a different value than the default may result in inaccuracies
-pub fn hourly_fine_fuel_moisture_code(
-    temp: f64,
-    rh: f64,
-    ws: f64,
-    precip: f64,
-    previous_hffmc: f64,
-    timestep: Option<f64>,
-) -> f64 {
-    let t0 = timestep.unwrap_or(1.);
-
-    let mo = 147.27723 * (101. - previous_hffmc) / (59.5 + previous_hffmc);
+pub fn hourly_fine_fuel_moisture_code<T: Float>(
+    temp: T,
+    rh: T,
+    ws: T,
+    precip: T,
+    previous_hffmc: T,
+    timestep: Option<T>,
+) -> T {
+    let e = c::<T>(E);
+    let t0 = timestep.unwrap_or(T::one());
+
+    let mo = c::<T>(147.27723) * (c::<T>(101.) - previous_hffmc) / (c::<T>(59.5) + previous_hffmc);
 
     let rf = precip;
 
-    let mr = if mo <= 150. {
-        mo + 42.5 * rf * E.powf(-100. / (251. - mo)) * (1. - E.powf(-6.93 / rf))
+    let mr = if mo <= c(150.) {
+        mo + c::<T>(42.5)
+            * rf
+            * e.powf(c::<T>(-100.) / (c::<T>(251.) - mo))
+            * (T::one() - e.powf(c::<T>(-6.93) / rf))
     } else {
-        mo + 42.5 * rf * E.powf(-100. / (251. - mo)) * (1. - E.powf(-6.93 / rf))
-            + 0.0015 * ((mo - 150.).powi(2)) * (rf.powf(0.5))
+        mo + c::<T>(42.5)
+            * rf
+            * e.powf(c::<T>(-100.) / (c::<T>(251.) - mo))
+            * (T::one() - e.powf(c::<T>(-6.93) / rf))
+            + c::<T>(0.0015) * ((mo - c(150.)).powi(2)) * (rf.powf(c(0.5)))
     };
 
-    let mr = mr.min(250.);
-    let mo = if precip > 0.0 { mr } else { mo };
+    let mr = mr.min(c(250.));
+    let mo = if precip > T::zero() { mr } else { mo };
 
-    let ed = 0.942 * rh.powf(0.679)
-        + 11. * E.powf((rh - 100.) / 10.)
-        + 0.18 * (21.1 - temp) * (1. - E.powf(-0.115 * rh));
+    let ed = c::<T>(0.942) * rh.powf(c(0.679))
+        + c::<T>(11.) * e.powf((rh - c(100.)) / c(10.))
+        + c::<T>(0.18) * (c::<T>(21.1) - temp) * (T::one() - e.powf(c::<T>(-0.115) * rh));
 
-    let ko =
-        0.424 * (1. - (rh / 100.).powf(1.7)) + 0.0694 * ws.powf(0.5) * (1. - (rh / 100.).powi(8));
+    let ko = c::<T>(0.424) * (T::one() - (rh / c(100.)).powf(c(1.7)))
+        + c::<T>(0.0694) * ws.powf(c(0.5)) * (T::one() - (rh / c(100.)).powi(8));
 
-    let kd = ko * 0.0579 * E.powf(0.0365 * temp);
-    let md = ed + (mo - ed) * 10f64.powf(-kd * t0);
+    let kd = ko * c(0.0579) * e.powf(c::<T>(0.0365) * temp);
+    let md = ed + (mo - ed) * c::<T>(10.).powf(-kd * t0);
 
-    let ew = 0.618 * rh.powf(0.753)
-        + 10. * E.powf((rh - 100.) / 10.)
-        + 0.18 * (21.1 - temp) * (1. - E.powf(-0.115 * rh));
+    let ew = c::<T>(0.618) * rh.powf(c(0.753))
+        + c::<T>(10.) * e.powf((rh - c(100.)) / c(10.))
+        + c::<T>(0.18) * (c::<T>(21.1) - temp) * (T::one() - e.powf(c::<T>(-0.115) * rh));
 
-    let k1 = 0.424 * (1. - ((100. - rh) / 100.).powf(1.7))
-        + 0.0694 * ws.powf(0.5) * (1. - ((100. - rh) / 100.).powi(8));
+    let k1 = c::<T>(0.424) * (T::one() - ((c::<T>(100.) - rh) / c(100.)).powf(c(1.7)))
+        + c::<T>(0.0694) * ws.powf(c(0.5)) * (T::one() - ((c::<T>(100.) - rh) / c(100.)).powi(8));
 
-    let kw = k1 * 0.0579 * E.powf(0.0365 * temp);
-    let mw = ew - (ew - mo) * 10f64.powf(-kw * t0);
+    let kw = k1 * c(0.0579) * e.powf(c::<T>(0.0365) * temp);
+    let mw = ew - (ew - mo) * c::<T>(10.).powf(-kw * t0);
 
     let m = if mo > ed { md } else { mw };
     let m = if ed >= mo && mo >= ew { mo } else { m };
 
-    (59.5 * (250. - m) / (147.27723 + m)).max(0.)
+    (c::<T>(59.5) * (c::<T>(250.) - m) / (c::<T>(147.27723) + m)).max(T::zero())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -97,4 +106,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_hourly_fine_fuel_moisture_code_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/hffmc.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                hourly_fine_fuel_moisture_code(
+                    record.temp as f32,
+                    record.rh as f32,
+                    record.ws as f32,
+                    record.precip as f32,
+                    record.previous_hffmc as f32,
+                    record.timestep.map(|v| v as f32),
+                ),
+                hourly_fine_fuel_moisture_code(
+                    record.temp,
+                    record.rh,
+                    record.ws,
+                    record.precip,
+                    record.previous_hffmc,
+                    record.timestep,
+                ),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,4 +1,6 @@
-use std::f64::consts;
+use core::f64::consts;
+
+use crate::float::{c, Float};
 
 /// Calculate initial spread index (ISI)
 ///
@@ -18,24 +20,53 @@ use std::f64::consts;
 /// assert_eq!(initial_spread_index(7.2, 48.6), 3.049654948160232e-6);
 /// assert_eq!(initial_spread_index(39.3, 72.9), 1.1899890130951611);
 /// ```
-pub fn initial_spread_index(ffmc: f64, ws: f64) -> f64 {
+pub fn initial_spread_index<T: Float>(ffmc: T, ws: T) -> T {
+    // Moisture content
+    let fm = c::<T>(147.27723) * (c::<T>(101.) - ffmc) / (c::<T>(59.5) + ffmc);
+
+    spread_index(fm, ws)
+}
+
+/// Calculate initial spread index (ISI) from a daily FFMC
+///
+/// Like [initial_spread_index()], but converts the FFMC to moisture content with the same
+/// coefficient as [fine_fuel_moisture_code()][crate::fwi_system::fine_fuel_moisture_code()]
+/// (Van Wagner 1987), as in R cffdrs `fwi()`.
+///
+/// * `ffmc` - Daily fine fuel moisture code
+/// * `ws` - Wind speed (km/h)
+///
+/// Returns ISI
+///
+/// ```
+/// # use cffdrs::fwi_system::daily_initial_spread_index;
+/// assert_eq!(daily_initial_spread_index(87.69298009277445, 25.), 10.853661073655068);
+/// ```
+pub fn daily_initial_spread_index<T: Float>(ffmc: T, ws: T) -> T {
     // Moisture content
-    let fm = 147.27723 * (101. - ffmc) / (59.5 + ffmc);
+    let fm = c::<T>(147.2) * (c::<T>(101.) - ffmc) / (c::<T>(59.5) + ffmc);
 
+    spread_index(fm, ws)
+}
+
+/// ISI from fine fuel moisture content (%) and wind speed (km/h)
+fn spread_index<T: Float>(fm: T, ws: T) -> T {
     // Wind effect
-    let fw = consts::E.powf(0.05039 * ws);
+    let fw = c::<T>(consts::E).powf(c::<T>(0.05039) * ws);
 
     // Fine fuel moisture
-    let ff = 91.9 * consts::E.powf(-0.1386 * fm) * (1. + (fm.powf(5.31) / 49300000.));
+    let ff = c::<T>(91.9)
+        * c::<T>(consts::E).powf(c::<T>(-0.1386) * fm)
+        * (T::one() + (fm.powf(c(5.31)) / c(49300000.)));
 
     // Spread index
-    0.208 * fw * ff
+    c::<T>(0.208) * fw * ff
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::precision_f64;
+    use crate::test_util::{assert_f32_close, precision_f64};
 
     #[derive(Debug, serde::Deserialize)]
     struct TestRow {
@@ -58,4 +89,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_initial_spread_index_f32() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = std::fs::File::open("./tests/fixtures/isi.csv")?;
+        let mut rdr = csv::Reader::from_reader(fixture);
+
+        for result in rdr.deserialize() {
+            let record: TestRow = result?;
+
+            assert_f32_close(
+                initial_spread_index(record.ffmc as f32, record.ws as f32),
+                initial_spread_index(record.ffmc, record.ws),
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,21 +1,26 @@
 //! Fire Weather Index (FWI) System
 //!
 //! This module contains some helper functions for generating all FWI values from weather data.
+//! The equations are available without the `std` feature, but the helpers require it.
 
 mod bui;
 mod dc;
 mod dmc;
+mod ffmc;
 mod fwi;
 mod hffmc;
 mod isi;
+#[cfg(feature = "std")]
 mod system;
 
 pub use bui::*;
 pub use dc::*;
 pub use dmc::*;
+pub use ffmc::*;
 pub use fwi::*;
 pub use hffmc::*;
 pub use isi::*;
+#[cfg(feature = "std")]
 pub use system::*;
 
 /// Default value for FFMC
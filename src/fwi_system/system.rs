@@ -5,8 +5,9 @@ use chrono::Datelike;
 use crate::weather::Weather;
 
 use super::{
-    buildup_index, drought_code, duff_moisture_code, fire_weather_index,
-    hourly_fine_fuel_moisture_code, initial_spread_index,
+    buildup_index, daily_initial_spread_index, drought_code, duff_moisture_code,
+    fine_fuel_moisture_code, fire_weather_index, hourly_fine_fuel_moisture_code,
+    initial_spread_index,
 };
 
 #[cfg(feature = "serde")]
@@ -129,4 +130,77 @@ pub fn calculate_hourly(
     }
 }
 
-// TODO: Add calculate_daily (and maybe calculate_diurnally?)
+/// Calculate all values for the FWI system from daily noon weather
+///
+/// Like [calculate_hourly()], but the FFMC is calculated with the daily equation, as in R cffdrs
+/// `fwi()`.
+///
+/// * `weather` - Noon weather, with 24-hour precipitation
+/// * `previous` - FWI values from the previous day. Providing this argument will improve the
+///   accuracy of calculations
+///
+/// # Examples
+///
+/// ```
+/// use cffdrs::fwi_system::{calculate_daily, StartingFwiValues};
+/// use cffdrs::weather::Weather;
+/// use chrono::prelude::*;
+///
+/// let weather = Weather {
+///     time: Utc.with_ymd_and_hms(2011, 4, 13, 12, 0, 0).unwrap(),
+///     location: geo::Point::new(-77.57, 45.98),
+///     temp: 17.,
+///     rh: 42.,
+///     ws: 25.,
+///     wd: 0.,
+///     precip: 0.,
+/// };
+/// let start = StartingFwiValues { ffmc: 85., dmc: 6., dc: 15. };
+///
+/// let fwi_values = calculate_daily(&weather, Some(&start));
+///
+/// assert_eq!(fwi_values.ffmc, 87.69298009277445);
+/// assert_eq!(fwi_values.isi, 10.853661073655068);
+/// assert_eq!(fwi_values.fwi, 10.096371392382368);
+/// ```
+pub fn calculate_daily(weather: &Weather, previous: Option<&StartingFwiValues>) -> HourlyFwiValues {
+    let previous = previous.unwrap_or(&StartingFwiValues::DEFAULT);
+
+    let ffmc = fine_fuel_moisture_code(
+        weather.temp,
+        weather.rh,
+        weather.ws,
+        weather.precip,
+        previous.ffmc,
+    );
+
+    let dc = drought_code(
+        previous.dc,
+        weather.temp,
+        weather.precip,
+        weather.location.y(),
+        weather.time.month() as usize,
+        Some(true),
+    );
+    let dmc = duff_moisture_code(
+        previous.dmc,
+        weather.temp,
+        weather.rh,
+        weather.precip,
+        weather.location.y(),
+        weather.time.month() as usize,
+        Some(true),
+    );
+    let bui = buildup_index(dmc, dc);
+    let isi = daily_initial_spread_index(ffmc, weather.ws);
+    let fwi = fire_weather_index(isi, bui);
+
+    HourlyFwiValues {
+        ffmc,
+        dmc,
+        dc,
+        isi,
+        bui,
+        fwi,
+    }
+}
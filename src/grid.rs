@@ -0,0 +1,74 @@
+//! Raster grid type used for gridded calculations
+
+/// A regular raster grid of square cells, stored in row-major order starting from the north-west
+/// corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    /// Number of columns
+    pub cols: usize,
+    /// Number of rows
+    pub rows: usize,
+    /// Width (and height) of a single cell (m)
+    pub cell_size: f64,
+    /// Cell values, row by row
+    pub data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from row-major cell values
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` doesn't contain exactly `cols * rows` values
+    pub fn new(cols: usize, rows: usize, cell_size: f64, data: Vec<T>) -> Self {
+        assert_eq!(
+            data.len(),
+            cols * rows,
+            "grid data length must equal cols * rows"
+        );
+
+        Self {
+            cols,
+            rows,
+            cell_size,
+            data,
+        }
+    }
+
+    /// Create a grid with every cell set to `value`
+    pub fn filled(cols: usize, rows: usize, cell_size: f64, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(cols, rows, cell_size, vec![value; cols * rows])
+    }
+
+    /// Index into [`Grid::data`] for the cell at `col`, `row`
+    pub fn index(&self, col: usize, row: usize) -> usize {
+        row * self.cols + col
+    }
+
+    /// Value of the cell at `col`, `row`, or `None` if it's outside the grid
+    pub fn get(&self, col: usize, row: usize) -> Option<&T> {
+        if col < self.cols && row < self.rows {
+            self.data.get(self.index(col, row))
+        } else {
+            None
+        }
+    }
+
+    /// Whether `other` has the same dimensions as this grid
+    pub fn same_shape<U>(&self, other: &Grid<U>) -> bool {
+        self.cols == other.cols && self.rows == other.rows
+    }
+
+    /// Create a new grid with the same dimensions by applying `f` to every cell
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            cols: self.cols,
+            rows: self.rows,
+            cell_size: self.cell_size,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+}
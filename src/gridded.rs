@@ -0,0 +1,669 @@
+//! Gridded FWI and FBP calculations
+//!
+//! Calculates the FWI and FBP systems for every cell of a set of aligned grids. Outputs are
+//! returned as one grid per value (struct-of-arrays), rather than a grid of
+//! [HourlyFwiValues]/[FbpValues].
+//!
+//! With the `parallel` feature enabled, cells are calculated in parallel using
+//! [rayon](https://docs.rs/rayon).
+
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    fbp_system::{calculate_fbp, FbpFuelType, FbpOptions, FbpValues},
+    fwi_system::{calculate_hourly, HourlyFwiValues, StartingFwiValues},
+    grid::Grid,
+    terrain::Terrain,
+    weather::Weather,
+};
+
+/// Hourly weather for every cell of a grid
+#[derive(Debug, Clone)]
+pub struct WeatherGrids {
+    /// UTC time of the weather, shared by every cell
+    pub time: DateTime<Utc>,
+    /// Latitude of each cell (degrees)
+    pub latitude: Grid<f64>,
+    /// Longitude of each cell (degrees)
+    pub longitude: Grid<f64>,
+    /// Temperature (Celcius)
+    pub temp: Grid<f64>,
+    /// Relative humidity (%)
+    pub rh: Grid<f64>,
+    /// Wind speed (at 10m height, km/h)
+    pub ws: Grid<f64>,
+    /// Wind direction (degrees, 0 = wind from north, 90 = from east, etc.)
+    pub wd: Grid<f64>,
+    /// Precipitation (mm)
+    pub precip: Grid<f64>,
+}
+
+impl WeatherGrids {
+    /// Weather for the cell at `index` into [`Grid::data`]
+    pub fn weather(&self, index: usize) -> Weather {
+        Weather {
+            time: self.time,
+            location: geo::Point::new(self.longitude.data[index], self.latitude.data[index]),
+            temp: self.temp.data[index],
+            rh: self.rh.data[index],
+            ws: self.ws.data[index],
+            wd: self.wd.data[index],
+            precip: self.precip.data[index],
+        }
+    }
+
+    fn assert_shape<T>(&self, grid: &Grid<T>) {
+        assert!(
+            [
+                &self.latitude,
+                &self.longitude,
+                &self.temp,
+                &self.rh,
+                &self.ws,
+                &self.wd,
+                &self.precip,
+            ]
+            .iter()
+            .all(|weather| weather.same_shape(grid)),
+            "weather grids must have the same dimensions"
+        );
+    }
+}
+
+/// Starting FWI codes for every cell of a grid (see [StartingFwiValues])
+#[derive(Debug, Clone)]
+pub struct StartingFwiGrids {
+    pub dc: Grid<f64>,
+    pub dmc: Grid<f64>,
+    pub ffmc: Grid<f64>,
+}
+
+impl StartingFwiGrids {
+    /// Starting values for the cell at `index` into [`Grid::data`]
+    pub fn values(&self, index: usize) -> StartingFwiValues {
+        StartingFwiValues {
+            dc: self.dc.data[index],
+            dmc: self.dmc.data[index],
+            ffmc: self.ffmc.data[index],
+        }
+    }
+}
+
+/// FWI values for every cell of a grid (see [HourlyFwiValues])
+#[derive(Debug, Clone)]
+pub struct FwiGrids {
+    pub isi: Grid<f64>,
+    pub dc: Grid<f64>,
+    pub dmc: Grid<f64>,
+    pub ffmc: Grid<f64>,
+    pub bui: Grid<f64>,
+    pub fwi: Grid<f64>,
+}
+
+impl FwiGrids {
+    /// FWI values for the cell at `index` into [`Grid::data`]
+    pub fn values(&self, index: usize) -> HourlyFwiValues {
+        HourlyFwiValues {
+            isi: self.isi.data[index],
+            dc: self.dc.data[index],
+            dmc: self.dmc.data[index],
+            ffmc: self.ffmc.data[index],
+            bui: self.bui.data[index],
+            fwi: self.fwi.data[index],
+        }
+    }
+
+    /// Use these values as the starting codes for the next hour
+    pub fn into_starting_values(self) -> StartingFwiGrids {
+        StartingFwiGrids {
+            dc: self.dc,
+            dmc: self.dmc,
+            ffmc: self.ffmc,
+        }
+    }
+
+    fn empty<T>(shape: &Grid<T>) -> Self {
+        let grid = || Grid::filled(shape.cols, shape.rows, shape.cell_size, f64::NAN);
+
+        Self {
+            isi: grid(),
+            dc: grid(),
+            dmc: grid(),
+            ffmc: grid(),
+            bui: grid(),
+            fwi: grid(),
+        }
+    }
+
+    /// Cell values of each grid, in the same order as [FwiGrids::cell()]
+    fn data_mut(&mut self) -> [&mut [f64]; 6] {
+        [
+            &mut self.isi.data,
+            &mut self.dc.data,
+            &mut self.dmc.data,
+            &mut self.ffmc.data,
+            &mut self.bui.data,
+            &mut self.fwi.data,
+        ]
+    }
+
+    fn cell(values: HourlyFwiValues) -> [f64; 6] {
+        [
+            values.isi,
+            values.dc,
+            values.dmc,
+            values.ffmc,
+            values.bui,
+            values.fwi,
+        ]
+    }
+}
+
+/// Fuel type and fuel-specific FBP options for every cell of a grid
+///
+/// Options that are `None`, or `NaN` in a cell, use the value from [FbpOptions].
+#[derive(Debug, Clone)]
+pub struct FuelGrids {
+    /// Fuel type of each cell
+    pub fuel_type: Grid<FbpFuelType>,
+    /// Percent conifer (%), for M1/M2
+    pub percent_conifer: Option<Grid<f64>>,
+    /// Percent dead balsam fir (%), for M3/M4
+    pub percent_dead_balsam: Option<Grid<f64>>,
+    /// Degree of curing (%), for O1a/O1b
+    pub curing: Option<Grid<f64>>,
+}
+
+impl FuelGrids {
+    /// FBP options for the cell at `index` into [`Grid::data`], using `options` for anything
+    /// without a value in the cell
+    pub fn options(&self, index: usize, options: &FbpOptions) -> FbpOptions {
+        let cell = |grid: &Option<Grid<f64>>| {
+            grid.as_ref()
+                .map(|grid| grid.data[index])
+                .filter(|value| !value.is_nan())
+        };
+
+        FbpOptions {
+            percent_conifer: cell(&self.percent_conifer).unwrap_or(options.percent_conifer),
+            percent_dead_balsam: cell(&self.percent_dead_balsam)
+                .unwrap_or(options.percent_dead_balsam),
+            curing: cell(&self.curing).unwrap_or(options.curing),
+            ..options.clone()
+        }
+    }
+
+    fn assert_shape(&self) {
+        assert!(
+            [
+                &self.percent_conifer,
+                &self.percent_dead_balsam,
+                &self.curing
+            ]
+            .iter()
+            .all(|grid| grid
+                .as_ref()
+                .is_none_or(|grid| grid.same_shape(&self.fuel_type))),
+            "fuel option grids must have the same dimensions as the fuel type grid"
+        );
+    }
+}
+
+impl From<Grid<FbpFuelType>> for FuelGrids {
+    /// Fuel types without per-cell options
+    fn from(fuel_type: Grid<FbpFuelType>) -> Self {
+        Self {
+            fuel_type,
+            percent_conifer: None,
+            percent_dead_balsam: None,
+            curing: None,
+        }
+    }
+}
+
+/// FBP values for every cell of a grid (see [FbpValues])
+#[derive(Debug, Clone)]
+pub struct FbpGrids {
+    /// Crown fraction burned (0-1)
+    pub cfb: Grid<f64>,
+    /// Direction of spread (radians)
+    pub raz: Grid<f64>,
+    /// Head rate of spread (m/min)
+    pub ros: Grid<f64>,
+    /// Flank rate of spread (m/min)
+    pub fros: Grid<f64>,
+    /// Back rate of spread  (m/min)
+    pub bros: Grid<f64>,
+    /// Foliar moisture content
+    pub fmc: Grid<f64>,
+    /// Surface fuel consumption (kg/m^2)
+    pub sfc: Grid<f64>,
+    /// Crown fuel consumption (kg/m^2)
+    pub cfc: Grid<f64>,
+    /// Total fuel consumption (kg/m^2)
+    pub tfc: Grid<f64>,
+    /// Crown fuel load
+    pub cfl: Grid<f64>,
+    /// Crown base height (m)
+    pub cbh: Grid<f64>,
+    /// Effective wind speed (km/h)
+    pub wsv: Grid<f64>,
+    /// Effective wind direction (radians)
+    pub wsz: Grid<f64>,
+    /// Head fire intensity (kW/m)
+    pub fi: Grid<f64>,
+}
+
+impl FbpGrids {
+    fn empty<T>(shape: &Grid<T>) -> Self {
+        let grid = || Grid::filled(shape.cols, shape.rows, shape.cell_size, f64::NAN);
+
+        Self {
+            cfb: grid(),
+            raz: grid(),
+            ros: grid(),
+            fros: grid(),
+            bros: grid(),
+            fmc: grid(),
+            sfc: grid(),
+            cfc: grid(),
+            tfc: grid(),
+            cfl: grid(),
+            cbh: grid(),
+            wsv: grid(),
+            wsz: grid(),
+            fi: grid(),
+        }
+    }
+
+    /// Cell values of each grid, in the same order as [FbpGrids::cell()]
+    fn data_mut(&mut self) -> [&mut [f64]; 14] {
+        [
+            &mut self.cfb.data,
+            &mut self.raz.data,
+            &mut self.ros.data,
+            &mut self.fros.data,
+            &mut self.bros.data,
+            &mut self.fmc.data,
+            &mut self.sfc.data,
+            &mut self.cfc.data,
+            &mut self.tfc.data,
+            &mut self.cfl.data,
+            &mut self.cbh.data,
+            &mut self.wsv.data,
+            &mut self.wsz.data,
+            &mut self.fi.data,
+        ]
+    }
+
+    fn cell(values: FbpValues) -> [f64; 14] {
+        [
+            values.cfb,
+            values.raz,
+            values.ros,
+            values.fros,
+            values.bros,
+            values.fmc,
+            values.sfc,
+            values.cfc,
+            values.tfc,
+            values.cfl,
+            values.cbh,
+            values.wsv,
+            values.wsz,
+            values.fi,
+        ]
+    }
+}
+
+/// Calculate hourly FWI values for every cell of a grid (see
+/// [calculate_hourly()][crate::fwi_system::calculate_hourly()])
+///
+/// * `weather` - Hourly weather for each cell
+/// * `previous` - FWI codes for each cell from the previous hour (optional)
+///
+/// # Panics
+///
+/// Panics if the grids don't all have the same dimensions
+///
+/// # Examples
+///
+/// ```
+/// # use cffdrs::gridded::{calculate_hourly_grid, WeatherGrids};
+/// # use cffdrs::grid::Grid;
+/// # use chrono::prelude::*;
+/// let grid = |value| Grid::filled(2, 2, 2000., value);
+/// let weather = WeatherGrids {
+///     time: Utc.with_ymd_and_hms(2023, 7, 8, 9, 10, 11).unwrap(),
+///     latitude: grid(50.69),
+///     longitude: grid(-120.34),
+///     temp: grid(28.),
+///     rh: grid(12.),
+///     ws: grid(16.),
+///     wd: grid(95.),
+///     precip: grid(0.),
+/// };
+///
+/// let fwi = calculate_hourly_grid(&weather, None);
+///
+/// assert_eq!(fwi.ffmc.get(1, 1), Some(&57.90482214144054));
+///
+/// // Continue into the next hour
+/// let fwi = calculate_hourly_grid(&weather, Some(&fwi.into_starting_values()));
+/// ```
+pub fn calculate_hourly_grid(
+    weather: &WeatherGrids,
+    previous: Option<&StartingFwiGrids>,
+) -> FwiGrids {
+    let shape = &weather.temp;
+    weather.assert_shape(shape);
+    if let Some(previous) = previous {
+        assert!(
+            [&previous.dc, &previous.dmc, &previous.ffmc]
+                .iter()
+                .all(|grid| grid.same_shape(shape)),
+            "previous FWI grids must have the same dimensions as the weather grids"
+        );
+    }
+
+    let mut fwi = FwiGrids::empty(shape);
+    fill_cells(fwi.data_mut(), |index| {
+        let previous = previous.map(|previous| previous.values(index));
+        FwiGrids::cell(calculate_hourly(&weather.weather(index), previous.as_ref()))
+    });
+
+    fwi
+}
+
+/// Calculate FBP values for every cell of a grid (see
+/// [calculate_fbp()][crate::fbp_system::calculate_fbp()])
+///
+/// * `fuels` - Fuel type and fuel-specific options of each cell
+/// * `terrain` - Elevation, slope and aspect of each cell (optional). Without terrain, the
+///   elevation, slope and aspect from `options` are used for every cell
+/// * `fwi` - FWI values for each cell
+/// * `weather` - Hourly weather for each cell
+/// * `options` - FBP options applied to every cell, except where set by `fuels` or `terrain`
+///
+/// # Panics
+///
+/// Panics if the grids don't all have the same dimensions
+///
+/// # Examples
+///
+/// ```
+/// # use cffdrs::fbp_system::{FbpFuelType, FbpOptions};
+/// # use cffdrs::gridded::{calculate_fbp_grid, calculate_hourly_grid, WeatherGrids};
+/// # use cffdrs::grid::Grid;
+/// # use chrono::prelude::*;
+/// let grid = |value| Grid::filled(2, 1, 2000., value);
+/// let weather = WeatherGrids {
+///     time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+///     latitude: grid(50.69),
+///     longitude: grid(-120.34),
+///     temp: grid(30.),
+///     rh: grid(15.),
+///     ws: grid(20.),
+///     wd: grid(270.),
+///     precip: grid(0.),
+/// };
+/// let fwi = calculate_hourly_grid(&weather, None);
+/// let fuel_types = Grid::new(2, 1, 2000., vec![FbpFuelType::C2, FbpFuelType::NonFuel]);
+///
+/// let fbp = calculate_fbp_grid(&fuel_types.into(), None, &fwi, &weather, &FbpOptions::default());
+///
+/// assert_eq!(fbp.ros.data[0], 0.5100619539048786);
+/// assert_eq!(fbp.fi.data[0], 361.6171606111537);
+///
+/// // Non-fuel cells don't burn
+/// assert_eq!(fbp.tfc.data[1], 0.);
+/// assert_eq!(fbp.fi.data[1], 0.);
+/// ```
+pub fn calculate_fbp_grid(
+    fuels: &FuelGrids,
+    terrain: Option<&Terrain>,
+    fwi: &FwiGrids,
+    weather: &WeatherGrids,
+    options: &FbpOptions,
+) -> FbpGrids {
+    let fuel_types = &fuels.fuel_type;
+    weather.assert_shape(fuel_types);
+    fuels.assert_shape();
+    assert!(
+        [&fwi.isi, &fwi.dc, &fwi.dmc, &fwi.ffmc, &fwi.bui, &fwi.fwi]
+            .iter()
+            .all(|grid| grid.same_shape(fuel_types)),
+        "FWI grids must have the same dimensions as the fuel type grid"
+    );
+    if let Some(terrain) = terrain {
+        assert!(
+            terrain.elevation.same_shape(fuel_types),
+            "terrain grids must have the same dimensions as the fuel type grid"
+        );
+    }
+
+    let mut fbp = FbpGrids::empty(fuel_types);
+    fill_cells(fbp.data_mut(), |index| {
+        let options = fuels.options(index, options);
+        let options = match terrain {
+            Some(terrain) => FbpOptions {
+                elevation: Some(terrain.elevation.data[index]),
+                slope: terrain.slope.data[index],
+                aspect: terrain.aspect.data[index],
+                ..options
+            },
+            None => options,
+        };
+
+        FbpGrids::cell(calculate_fbp(
+            fuel_types.data[index],
+            &fwi.values(index),
+            &weather.weather(index),
+            options,
+        ))
+    });
+
+    fbp
+}
+
+/// Number of cells calculated together, e.g. by one thread
+const CHUNK_SIZE: usize = 4096;
+
+/// Calculate the values of each cell index with `f`, writing them straight into the `N` output
+/// grids. Cells are calculated in parallel with the `parallel` feature
+#[cfg(feature = "parallel")]
+fn fill_cells<const N: usize, F: Fn(usize) -> [f64; N] + Sync + Send>(
+    outputs: [&mut [f64]; N],
+    f: F,
+) {
+    chunks(outputs)
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(chunk, outputs)| fill_chunk(chunk, outputs, &f));
+}
+
+/// Calculate the values of each cell index with `f`, writing them straight into the `N` output
+/// grids. Cells are calculated in parallel with the `parallel` feature
+#[cfg(not(feature = "parallel"))]
+fn fill_cells<const N: usize, F: Fn(usize) -> [f64; N]>(outputs: [&mut [f64]; N], f: F) {
+    chunks(outputs)
+        .into_iter()
+        .enumerate()
+        .for_each(|(chunk, outputs)| fill_chunk(chunk, outputs, &f));
+}
+
+/// Split each output into [CHUNK_SIZE] cells, returning the matching chunks of every output
+/// together
+fn chunks<const N: usize>(outputs: [&mut [f64]; N]) -> Vec<[&mut [f64]; N]> {
+    let count = outputs
+        .first()
+        .map_or(0, |output| output.len().div_ceil(CHUNK_SIZE));
+    let mut outputs = outputs.map(|output| output.chunks_mut(CHUNK_SIZE));
+
+    (0..count)
+        .map(|_| outputs.each_mut().map(|output| output.next().unwrap()))
+        .collect()
+}
+
+fn fill_chunk<const N: usize>(
+    chunk: usize,
+    mut outputs: [&mut [f64]; N],
+    f: impl Fn(usize) -> [f64; N],
+) {
+    for offset in 0..outputs[0].len() {
+        let values = f(chunk * CHUNK_SIZE + offset);
+        for (output, value) in outputs.iter_mut().zip(values) {
+            output[offset] = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::*;
+    use crate::terrain::GridCoordinates;
+
+    fn weather_grids(cols: usize, rows: usize) -> WeatherGrids {
+        let grid = |f: fn(usize) -> f64| {
+            Grid::new(
+                cols,
+                rows,
+                2000.,
+                (0..cols * rows).map(f).collect::<Vec<_>>(),
+            )
+        };
+
+        WeatherGrids {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            latitude: grid(|i| 45. + i as f64 * 0.5),
+            longitude: grid(|i| -120. + i as f64 * 0.25),
+            temp: grid(|i| 20. + i as f64),
+            rh: grid(|i| 50. - i as f64 * 3.),
+            ws: grid(|i| 5. + i as f64 * 2.),
+            wd: grid(|i| i as f64 * 30.),
+            precip: grid(|i| if i % 3 == 0 { 1. } else { 0. }),
+        }
+    }
+
+    #[test]
+    fn test_grids_match_single_cell() {
+        let weather = weather_grids(4, 3);
+        let previous = calculate_hourly_grid(&weather, None).into_starting_values();
+        let fwi = calculate_hourly_grid(&weather, Some(&previous));
+
+        let fuel_types = Grid::new(
+            4,
+            3,
+            2000.,
+            FbpFuelType::ALL.iter().copied().cycle().take(12).collect(),
+        );
+        let elevation = weather.temp.map(|temp| temp * 10.);
+        let terrain = Terrain::from_elevation(elevation, GridCoordinates::Projected);
+        let options = FbpOptions::default();
+
+        let fuels = FuelGrids {
+            percent_conifer: Some(weather.temp.map(|temp| temp * 3.)),
+            curing: Some(
+                weather
+                    .rh
+                    .map(|&rh| if rh > 30. { rh + 40. } else { f64::NAN }),
+            ),
+            ..FuelGrids::from(fuel_types.clone())
+        };
+
+        let fbp = calculate_fbp_grid(&fuels, Some(&terrain), &fwi, &weather, &options);
+
+        for row in 0..3 {
+            for col in 0..4 {
+                let index = fuel_types.index(col, row);
+                let cell_weather = weather.weather(index);
+                let cell_fwi = calculate_hourly(&cell_weather, Some(&previous.values(index)));
+                assert_eq!(fwi.values(index).fwi, cell_fwi.fwi);
+                assert_eq!(fwi.values(index).ffmc, cell_fwi.ffmc);
+
+                let cell_fbp = calculate_fbp(
+                    fuel_types.data[index],
+                    &cell_fwi,
+                    &cell_weather,
+                    terrain
+                        .options(col, row, &fuels.options(index, &options))
+                        .unwrap(),
+                );
+                assert_eq!(fbp.ros.data[index], cell_fbp.ros);
+                assert_eq!(fbp.fi.data[index], cell_fbp.fi);
+                assert_eq!(fbp.raz.data[index], cell_fbp.raz);
+                assert_eq!(fbp.fmc.data[index], cell_fbp.fmc);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grids_larger_than_chunk() {
+        let (cols, rows) = (100, 50);
+        let grid =
+            |f: fn(usize) -> f64| Grid::new(cols, rows, 2000., (0..cols * rows).map(f).collect());
+        let weather = WeatherGrids {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            latitude: grid(|i| 45. + (i % 100) as f64 * 0.1),
+            longitude: grid(|i| -120. + (i / 100) as f64 * 0.1),
+            temp: grid(|i| 15. + (i % 17) as f64),
+            rh: grid(|i| 15. + (i % 41) as f64),
+            ws: grid(|i| (i % 31) as f64),
+            wd: grid(|i| (i % 360) as f64),
+            precip: grid(|_| 0.),
+        };
+        let fwi = calculate_hourly_grid(&weather, None);
+        let fuels = FuelGrids {
+            percent_conifer: Some(grid(|i| (i % 101) as f64)),
+            ..FuelGrids::from(Grid::filled(cols, rows, 2000., FbpFuelType::M1))
+        };
+        let options = FbpOptions::default();
+
+        let fbp = calculate_fbp_grid(&fuels, None, &fwi, &weather, &options);
+
+        for index in [0, CHUNK_SIZE - 1, CHUNK_SIZE, cols * rows - 1] {
+            let cell_weather = weather.weather(index);
+            let cell_fwi = calculate_hourly(&cell_weather, None);
+            assert_eq!(fwi.values(index).isi, cell_fwi.isi);
+
+            let cell_fbp = calculate_fbp(
+                FbpFuelType::M1,
+                &cell_fwi,
+                &cell_weather,
+                FbpOptions {
+                    percent_conifer: (index % 101) as f64,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(fbp.ros.data[index], cell_fbp.ros);
+            assert_eq!(fbp.fi.data[index], cell_fbp.fi);
+        }
+    }
+
+    #[test]
+    fn test_fuel_options() {
+        let fuels = FuelGrids {
+            curing: Some(Grid::new(2, 1, 2000., vec![60., f64::NAN])),
+            ..FuelGrids::from(Grid::filled(2, 1, 2000., FbpFuelType::O1b))
+        };
+        let options = FbpOptions::default();
+
+        assert_eq!(fuels.options(0, &options).curing, 60.);
+        assert_eq!(fuels.options(1, &options).curing, options.curing);
+        assert_eq!(fuels.options(1, &options).percent_conifer, 50.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mismatched_grids() {
+        let mut weather = weather_grids(2, 2);
+        weather.rh = Grid::filled(3, 2, 2000., 30.);
+
+        calculate_hourly_grid(&weather, None);
+    }
+}
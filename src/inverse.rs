@@ -0,0 +1,277 @@
+//! Inverse FBP calculations: the input value at which an output reaches a threshold
+//!
+//! Answers questions like "at what wind speed does this stand start crowning?" by solving for
+//! the value of one input (with the others fixed) at which rate of spread, head fire intensity or
+//! crown fraction burned reaches a target.
+//!
+//! The output is first sampled over the search range to check that it only increases or only
+//! decreases, so that there's a single threshold. The threshold is then found by bisection, which
+//! also works where the output jumps or has kinks (e.g. at the start of crowning).
+//!
+//! # Examples
+//!
+//! ```
+//! use cffdrs::fbp_system::{FbpFuelType, FbpOptions};
+//! use cffdrs::inverse::solve_input;
+//! use cffdrs::sensitivity::{FbpInput, FbpOutput, FbpPoint};
+//! use cffdrs::weather::Weather;
+//! use chrono::prelude::*;
+//!
+//! let point = FbpPoint {
+//!     fuel_type: FbpFuelType::C3,
+//!     ffmc: 90.,
+//!     bui: 80.,
+//!     weather: Weather {
+//!         time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+//!         location: geo::Point::new(-120.34, 50.69),
+//!         temp: 28.,
+//!         rh: 20.,
+//!         ws: 0.,
+//!         wd: 270.,
+//!         precip: 0.,
+//!     },
+//!     options: FbpOptions { crown_base_height: 8., ..Default::default() },
+//! };
+//!
+//! // Wind speed (km/h) at which crowning starts (CFB of 0.1)
+//! let ws = solve_input(&point, FbpInput::WindSpeed, FbpOutput::Cfb, 0.1, (0., 100.))?
+//!     .expect("crowns below 100 km/h");
+//! assert!((point.with_value(FbpInput::WindSpeed, ws).outputs().cfb - 0.1).abs() < 1e-6);
+//! # Ok::<(), cffdrs::inverse::NotMonotonicError>(())
+//! ```
+
+use std::fmt;
+
+use crate::sensitivity::{FbpInput, FbpOutput, FbpPoint};
+
+/// Number of values the output is sampled at to check that it's monotonic
+const MONOTONICITY_SAMPLES: usize = 100;
+
+/// Bisection stops when the bracket is smaller than this, relative to the search range
+const TOLERANCE: f64 = 1e-10;
+
+/// Error returned when an output both increases and decreases over the search range, so it can
+/// cross a threshold more than once
+#[derive(PartialEq, Clone, Debug)]
+pub struct NotMonotonicError {
+    /// Input value where the output first moves against its overall direction
+    pub value: f64,
+}
+
+impl fmt::Display for NotMonotonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "output isn't monotonic over the search range (it changes direction near {})",
+            self.value
+        )
+    }
+}
+
+impl std::error::Error for NotMonotonicError {}
+
+/// Find the input value in `range` (min, max) at which a monotonic function of it reaches
+/// `target`
+///
+/// For an increasing function, this is the lowest value where `f(x) >= target`, and for a
+/// decreasing function the lowest value where `f(x) <= target`. Returns `Ok(None)` when `f`
+/// doesn't reach `target` anywhere in `range`, and an error when `f` isn't monotonic.
+///
+/// This can be used with any FBP equation, e.g.
+/// [rate_of_spread_extended()][crate::fbp_system::ros::rate_of_spread_extended()]:
+///
+/// ```
+/// # use cffdrs::fbp_system::{ros::rate_of_spread_extended, FbpFuelType};
+/// # use cffdrs::inverse::solve_threshold;
+/// // ISI at which a C2 stand with a 3m crown base height starts crowning
+/// let isi = solve_threshold(
+///     |isi| rate_of_spread_extended(FbpFuelType::C2, isi, 60., 97., 2., 0., 0., 0., 3.).cfb,
+///     0.1,
+///     (0., 50.),
+/// )?;
+/// assert!(isi.is_some());
+/// # Ok::<(), cffdrs::inverse::NotMonotonicError>(())
+/// ```
+pub fn solve_threshold<F: Fn(f64) -> f64>(
+    f: F,
+    target: f64,
+    (min, max): (f64, f64),
+) -> Result<Option<f64>, NotMonotonicError> {
+    assert!(min < max, "search range must have min < max");
+
+    let samples: Vec<(f64, f64)> = (0..=MONOTONICITY_SAMPLES)
+        .map(|i| {
+            let x = min + (max - min) * i as f64 / MONOTONICITY_SAMPLES as f64;
+            (x, f(x))
+        })
+        .collect();
+
+    let increasing = samples.last().unwrap().1 >= samples[0].1;
+    let reached = |y: f64| if increasing { y >= target } else { y <= target };
+
+    // The output must not move against its overall direction between samples
+    for window in samples.windows(2) {
+        let ((_, previous), (x, y)) = (window[0], window[1]);
+        let tolerance = 1e-12 * previous.abs().max(y.abs()).max(1.);
+        let backwards = if increasing {
+            y < previous - tolerance
+        } else {
+            y > previous + tolerance
+        };
+        if backwards {
+            return Err(NotMonotonicError { value: x });
+        }
+    }
+
+    if reached(samples[0].1) {
+        return Ok(Some(min));
+    }
+    let Some(first) = samples.iter().position(|&(_, y)| reached(y)) else {
+        return Ok(None);
+    };
+
+    // Bisect between the last sample below the target and the first that reaches it
+    let (mut below, mut above) = (samples[first - 1].0, samples[first].0);
+    while above - below > TOLERANCE * (max - min) {
+        let middle = below + (above - below) / 2.;
+        if reached(f(middle)) {
+            above = middle;
+        } else {
+            below = middle;
+        }
+    }
+
+    Ok(Some(above))
+}
+
+/// Find the value of `input` in `range` (min, max) at which `output` reaches `target`, with the
+/// other inputs fixed at `point`
+///
+/// See [solve_threshold()]. Returns `Ok(None)` when `output` doesn't reach `target` in `range`.
+pub fn solve_input(
+    point: &FbpPoint,
+    input: FbpInput,
+    output: FbpOutput,
+    target: f64,
+    range: (f64, f64),
+) -> Result<Option<f64>, NotMonotonicError> {
+    solve_threshold(
+        |value| point.with_value(input, value).outputs().get(output),
+        target,
+        range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::*;
+    use crate::{
+        fbp_system::{FbpFuelType, FbpOptions},
+        weather::Weather,
+    };
+
+    fn point() -> FbpPoint {
+        FbpPoint {
+            fuel_type: FbpFuelType::C3,
+            ffmc: 90.,
+            bui: 80.,
+            weather: Weather {
+                time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+                location: geo::Point::new(-120.34, 50.69),
+                temp: 28.,
+                rh: 20.,
+                ws: 10.,
+                wd: 270.,
+                precip: 0.,
+            },
+            options: FbpOptions {
+                crown_base_height: 8.,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_solve_wind_speed_for_ros() {
+        let point = point();
+        let ws = solve_input(&point, FbpInput::WindSpeed, FbpOutput::Ros, 10., (0., 100.))
+            .unwrap()
+            .unwrap();
+
+        let ros = point.with_value(FbpInput::WindSpeed, ws).outputs().ros;
+        assert!((ros - 10.).abs() < 1e-6, "{ros}");
+    }
+
+    #[test]
+    fn test_solve_ffmc_for_hfi() {
+        let point = point();
+        let ffmc = solve_input(&point, FbpInput::Ffmc, FbpOutput::Hfi, 4000., (0., 101.))
+            .unwrap()
+            .unwrap();
+
+        let hfi = point.with_value(FbpInput::Ffmc, ffmc).outputs().hfi;
+        assert!((hfi - 4000.).abs() < 1e-3, "{hfi}");
+    }
+
+    #[test]
+    fn test_solve_decreasing_output() {
+        // Crowning is harder with a higher crown base height
+        let point = point().with_value(FbpInput::WindSpeed, 30.);
+        let cbh = solve_input(
+            &point,
+            FbpInput::CrownBaseHeight,
+            FbpOutput::Cfb,
+            0.1,
+            (0.5, 30.),
+        )
+        .unwrap()
+        .unwrap();
+
+        let cfb = |cbh: f64| {
+            point
+                .with_value(FbpInput::CrownBaseHeight, cbh)
+                .outputs()
+                .cfb
+        };
+        assert!((cfb(cbh) - 0.1).abs() < 1e-6);
+        assert!(cfb(cbh - 0.1) > 0.1);
+    }
+
+    #[test]
+    fn test_already_reached() {
+        assert_eq!(
+            solve_input(
+                &point(),
+                FbpInput::WindSpeed,
+                FbpOutput::Ros,
+                0.,
+                (0., 100.)
+            ),
+            Ok(Some(0.))
+        );
+    }
+
+    #[test]
+    fn test_unreachable() {
+        assert_eq!(
+            solve_input(
+                &point(),
+                FbpInput::WindSpeed,
+                FbpOutput::Ros,
+                1e6,
+                (0., 100.)
+            ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_not_monotonic() {
+        // Increases overall, but decreases until 3
+        let err = solve_threshold(|x| (x - 3.).powi(2), 1., (0., 10.)).unwrap_err();
+
+        assert!(err.value <= 3.);
+    }
+}
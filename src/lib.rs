@@ -1,5 +1,34 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or `libm` feature must be enabled");
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod batch;
+#[cfg(feature = "ensemble")]
+pub mod ensemble;
 pub mod fbp_system;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod float;
 pub mod fwi_system;
+#[cfg(feature = "std")]
+pub mod grid;
+#[cfg(feature = "std")]
+pub mod gridded;
+#[cfg(feature = "std")]
+pub mod inverse;
+#[cfg(feature = "raster")]
+pub mod raster;
+#[cfg(feature = "std")]
+pub mod sensitivity;
+#[cfg(feature = "std")]
+pub mod terrain;
+#[cfg(feature = "units")]
+pub mod units;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod weather;
 
 #[cfg(test)]
@@ -28,4 +57,21 @@ pub mod test_util {
             (x * shift_factor).round() / shift_factor
         }
     }
+
+    /// Assert that an `f32` result is within the documented tolerance of the `f64` result (see
+    /// [crate::float]): 0.1%, or 0.001 for results smaller than 1.
+    pub fn assert_f32_close(f32_result: f32, f64_result: f64) {
+        assert_f32_within(f32_result, f64_result, 0.001);
+    }
+
+    /// Assert that an `f32` result is within `relative_tolerance` of the `f64` result (an absolute
+    /// tolerance for results smaller than 1)
+    pub fn assert_f32_within(f32_result: f32, f64_result: f64, relative_tolerance: f64) {
+        let tolerance = relative_tolerance * f64_result.abs().max(1.);
+
+        assert!(
+            (f32_result as f64 - f64_result).abs() <= tolerance,
+            "f32 result {f32_result} differs from f64 result {f64_result} by more than {tolerance}"
+        );
+    }
 }
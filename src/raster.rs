@@ -0,0 +1,690 @@
+//! Raster input and output for gridded calculations
+//!
+//! Reads and writes [Grid]s as GeoTIFF or ESRI ASCII Grid files, keeping the georeferencing of
+//! the input so outputs line up with it. No data cells are read as `NaN`, and `NaN` cells are
+//! written as the raster's no data value.
+//!
+//! Requires the `raster` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use cffdrs::fbp_system::{FbpOptions, FuelLookupTable, LeafOnSeason};
+//! # use cffdrs::gridded::{calculate_fbp_grid, FwiGrids, WeatherGrids};
+//! # use cffdrs::raster::Raster;
+//! # use chrono::Datelike;
+//! # fn example(fwi: &FwiGrids, weather: &WeatherGrids) -> Result<(), Box<dyn std::error::Error>> {
+//! let lookup = FuelLookupTable::from_reader(std::fs::File::open("fuel_lookup.csv")?)?;
+//! let fuel = Raster::read("fuel.tif")?;
+//! let season = LeafOnSeason { green_up: 150, leaf_fall: 270 };
+//!
+//! let fbp = calculate_fbp_grid(
+//!     &fuel.fuels(&lookup, weather.time.ordinal() as i32, Some(&season)),
+//!     None,
+//!     fwi,
+//!     weather,
+//!     &FbpOptions::default(),
+//! );
+//!
+//! fuel.with_grid(fbp.ros).write("ros.tif")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+
+use tiff::{
+    decoder::{Decoder, DecodingResult},
+    encoder::{colortype::Gray32Float, TiffEncoder},
+    tags::Tag,
+    ColorType, TiffError,
+};
+
+use crate::{
+    fbp_system::{FbpFuelType, FuelLookupEntry, FuelLookupTable, LeafOnSeason},
+    grid::Grid,
+    gridded::FuelGrids,
+};
+
+/// No data value used when writing ESRI ASCII grids that don't have one
+const DEFAULT_NODATA: f64 = -9999.;
+
+/// Error returned when a raster can't be read or written
+#[derive(Debug)]
+pub enum RasterError {
+    /// The raster couldn't be read or written
+    Io(std::io::Error),
+    /// The GeoTIFF couldn't be decoded or encoded
+    Tiff(TiffError),
+    /// The raster's header or georeferencing is invalid
+    InvalidHeader(String),
+    /// The raster's cell values are invalid
+    InvalidData(String),
+    /// The file extension isn't a supported raster format
+    UnsupportedFormat(String),
+}
+
+impl fmt::Display for RasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RasterError::Io(err) => write!(f, "failed to read or write raster: {err}"),
+            RasterError::Tiff(err) => write!(f, "invalid GeoTIFF: {err}"),
+            RasterError::InvalidHeader(message) => write!(f, "invalid raster header: {message}"),
+            RasterError::InvalidData(message) => write!(f, "invalid raster data: {message}"),
+            RasterError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported raster format \"{extension}\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RasterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RasterError::Io(err) => Some(err),
+            RasterError::Tiff(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RasterError {
+    fn from(err: std::io::Error) -> Self {
+        RasterError::Io(err)
+    }
+}
+
+impl From<TiffError> for RasterError {
+    fn from(err: TiffError) -> Self {
+        RasterError::Tiff(err)
+    }
+}
+
+/// GeoTIFF coordinate reference system keys, copied as-is from an input GeoTIFF
+#[derive(PartialEq, Clone, Debug)]
+pub struct GeoKeys {
+    /// GeoKeyDirectory tag
+    pub directory: Vec<u16>,
+    /// GeoDoubleParams tag
+    pub double_params: Option<Vec<f64>>,
+    /// GeoAsciiParams tag
+    pub ascii_params: Option<String>,
+}
+
+/// Location of a raster, in the units of its coordinate reference system. The cell size is
+/// stored in [`Grid::cell_size`]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Georeference {
+    /// X coordinate (e.g. easting or longitude) of the western edge of the raster
+    pub west: f64,
+    /// Y coordinate (e.g. northing or latitude) of the northern edge of the raster
+    pub north: f64,
+    /// Value of cells with no data
+    pub nodata: Option<f64>,
+    /// Coordinate reference system, for GeoTIFFs
+    pub geo_keys: Option<GeoKeys>,
+}
+
+/// A georeferenced grid
+#[derive(PartialEq, Clone, Debug)]
+pub struct Raster {
+    pub grid: Grid<f64>,
+    pub georeference: Georeference,
+}
+
+impl Raster {
+    /// Read a GeoTIFF (`.tif`, `.tiff`) or ESRI ASCII Grid (`.asc`) file
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, RasterError> {
+        let file = File::open(&path)?;
+
+        match RasterFormat::from_path(path.as_ref())? {
+            RasterFormat::GeoTiff => Self::from_geotiff(BufReader::new(file)),
+            RasterFormat::AsciiGrid => Self::from_ascii_grid(BufReader::new(file)),
+        }
+    }
+
+    /// Write a GeoTIFF (`.tif`, `.tiff`) or ESRI ASCII Grid (`.asc`) file
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), RasterError> {
+        let format = RasterFormat::from_path(path.as_ref())?;
+        let mut file = BufWriter::new(File::create(&path)?);
+
+        match format {
+            RasterFormat::GeoTiff => self.write_geotiff(&mut file)?,
+            RasterFormat::AsciiGrid => self.write_ascii_grid(&mut file)?,
+        }
+
+        file.flush()?;
+        Ok(())
+    }
+
+    /// A raster with the same georeferencing as this one, e.g. for writing outputs calculated
+    /// from it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `grid` doesn't have the same dimensions as this raster
+    pub fn with_grid(&self, grid: Grid<f64>) -> Self {
+        assert!(
+            self.grid.same_shape(&grid),
+            "grid must have the same dimensions as the raster"
+        );
+
+        Self {
+            grid,
+            georeference: self.georeference.clone(),
+        }
+    }
+
+    /// Fuel types of a fuel grid, looked up from each cell's grid code. No data cells and codes
+    /// missing from `table` are [`FbpFuelType::NonFuel`]
+    pub fn fuel_types(&self, table: &FuelLookupTable) -> Grid<FbpFuelType> {
+        self.entries(table)
+            .map(|entry| entry.map_or(FbpFuelType::NonFuel, |entry| entry.fuel_type))
+    }
+
+    /// Fuel types and fuel-specific options (percent conifer, percent dead balsam fir and curing)
+    /// of a fuel grid, looked up from each cell's grid code, for
+    /// [calculate_fbp_grid()][crate::gridded::calculate_fbp_grid()]
+    ///
+    /// No data cells and codes missing from `table` are [`FbpFuelType::NonFuel`]. Options that no
+    /// entry sets are `None`, and cells whose entry doesn't set an option are `NaN`.
+    ///
+    /// * `day_of_year` - Julian day of year the fuel types are for
+    /// * `season` - Leaf-on season. Seasonal pairs such as `M-1/M-2` use the green fuel type
+    ///   during the season, and the leafless fuel type otherwise or without a season (see
+    ///   [FuelLookupEntry::fuel_type_on()]). Codes for a single fuel type are never switched, so
+    ///   don't also set
+    ///   [`FbpOptions::leaf_on_season`][crate::fbp_system::FbpOptions::leaf_on_season] for the
+    ///   calculation
+    pub fn fuels(
+        &self,
+        table: &FuelLookupTable,
+        day_of_year: i32,
+        season: Option<&LeafOnSeason>,
+    ) -> FuelGrids {
+        let entries = self.entries(table);
+        let option = |f: fn(&FuelLookupEntry) -> Option<f64>| {
+            entries
+                .data
+                .iter()
+                .any(|entry| entry.and_then(f).is_some())
+                .then(|| entries.map(|entry| entry.and_then(f).unwrap_or(f64::NAN)))
+        };
+
+        FuelGrids {
+            fuel_type: entries.map(|entry| {
+                entry.map_or(FbpFuelType::NonFuel, |entry| {
+                    entry.fuel_type_on(day_of_year, season)
+                })
+            }),
+            percent_conifer: option(|entry| entry.percent_conifer),
+            percent_dead_balsam: option(|entry| entry.percent_dead_balsam),
+            curing: option(|entry| entry.curing),
+        }
+    }
+
+    /// Lookup table entry for each cell's grid code
+    fn entries<'a>(&self, table: &'a FuelLookupTable) -> Grid<Option<&'a FuelLookupEntry>> {
+        self.grid.map(|&code| {
+            if code.is_finite() {
+                table.get(code.round() as i64)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Read an ESRI ASCII Grid
+    pub fn from_ascii_grid<R: BufRead>(reader: R) -> Result<Self, RasterError> {
+        let mut cols = None;
+        let mut rows = None;
+        let mut x = None;
+        let mut y = None;
+        let mut centre = false;
+        let mut cell_size = None;
+        let mut nodata = None;
+        let mut data = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut fields = line.split_whitespace().peekable();
+
+            let Some(first) = fields.peek() else {
+                continue;
+            };
+
+            if data.is_empty() && first.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                let key = first.to_lowercase();
+                fields.next();
+                let value = fields
+                    .next()
+                    .ok_or_else(|| RasterError::InvalidHeader(format!("missing {key} value")))?;
+                let number = value.parse::<f64>().map_err(|_| {
+                    RasterError::InvalidHeader(format!("\"{value}\" is not a valid {key}"))
+                })?;
+
+                match key.as_str() {
+                    "ncols" => cols = Some(number as usize),
+                    "nrows" => rows = Some(number as usize),
+                    "xllcorner" => x = Some(number),
+                    "yllcorner" => y = Some(number),
+                    "xllcenter" => {
+                        x = Some(number);
+                        centre = true;
+                    }
+                    "yllcenter" => {
+                        y = Some(number);
+                        centre = true;
+                    }
+                    "cellsize" => cell_size = Some(number),
+                    "nodata_value" => nodata = Some(number),
+                    _ => {
+                        return Err(RasterError::InvalidHeader(format!(
+                            "unknown header \"{key}\""
+                        )))
+                    }
+                }
+                continue;
+            }
+
+            for field in fields {
+                let value = field.parse::<f64>().map_err(|_| {
+                    RasterError::InvalidData(format!("\"{field}\" is not a number"))
+                })?;
+                data.push(value);
+            }
+        }
+
+        let missing = |key: &str| RasterError::InvalidHeader(format!("missing {key}"));
+        let cols = cols.ok_or_else(|| missing("ncols"))?;
+        let rows = rows.ok_or_else(|| missing("nrows"))?;
+        let x = x.ok_or_else(|| missing("xllcorner"))?;
+        let y = y.ok_or_else(|| missing("yllcorner"))?;
+        let cell_size = cell_size.ok_or_else(|| missing("cellsize"))?;
+
+        if data.len() != cols * rows {
+            return Err(RasterError::InvalidData(format!(
+                "expected {} values, found {}",
+                cols * rows,
+                data.len()
+            )));
+        }
+
+        // Corners are the outer edge of the lower left cell, rather than its centre
+        let (west, south) = if centre {
+            (x - cell_size / 2., y - cell_size / 2.)
+        } else {
+            (x, y)
+        };
+
+        Ok(Self {
+            grid: Grid::new(cols, rows, cell_size, with_nodata_as_nan(data, nodata)),
+            georeference: Georeference {
+                west,
+                north: south + rows as f64 * cell_size,
+                nodata,
+                geo_keys: None,
+            },
+        })
+    }
+
+    /// Write an ESRI ASCII Grid
+    pub fn write_ascii_grid<W: Write>(&self, mut writer: W) -> Result<(), RasterError> {
+        let grid = &self.grid;
+        let nodata = self.georeference.nodata.unwrap_or(DEFAULT_NODATA);
+
+        writeln!(writer, "ncols {}", grid.cols)?;
+        writeln!(writer, "nrows {}", grid.rows)?;
+        writeln!(writer, "xllcorner {}", self.georeference.west)?;
+        writeln!(
+            writer,
+            "yllcorner {}",
+            self.georeference.north - grid.rows as f64 * grid.cell_size
+        )?;
+        writeln!(writer, "cellsize {}", grid.cell_size)?;
+        writeln!(writer, "NODATA_value {nodata}")?;
+
+        for row in grid.data.chunks(grid.cols.max(1)) {
+            let row = row
+                .iter()
+                .map(|value| if value.is_nan() { nodata } else { *value }.to_string())
+                .collect::<Vec<_>>();
+            writeln!(writer, "{}", row.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the first band of a single-band GeoTIFF
+    ///
+    /// The raster must be north-up, with square cells, and be georeferenced with the
+    /// ModelPixelScale and ModelTiepoint tags.
+    pub fn from_geotiff<R: Read + Seek>(reader: R) -> Result<Self, RasterError> {
+        let mut decoder = Decoder::new(reader)?;
+
+        let (cols, rows) = decoder.dimensions()?;
+        if !matches!(decoder.colortype()?, ColorType::Gray(_)) {
+            return Err(RasterError::InvalidData(
+                "only single-band GeoTIFFs are supported".to_string(),
+            ));
+        }
+
+        let scale = decoder
+            .find_tag(Tag::ModelPixelScaleTag)?
+            .ok_or_else(|| RasterError::InvalidHeader("missing ModelPixelScale tag".to_string()))?
+            .into_f64_vec()?;
+        let tiepoint = decoder
+            .find_tag(Tag::ModelTiepointTag)?
+            .ok_or_else(|| RasterError::InvalidHeader("missing ModelTiepoint tag".to_string()))?
+            .into_f64_vec()?;
+        if scale.len() < 2 || tiepoint.len() < 6 {
+            return Err(RasterError::InvalidHeader(
+                "invalid georeferencing tags".to_string(),
+            ));
+        }
+        if (scale[0] - scale[1]).abs() > scale[0].abs() * 1e-9 {
+            return Err(RasterError::InvalidHeader(format!(
+                "cells must be square, found {} x {}",
+                scale[0], scale[1]
+            )));
+        }
+        let cell_size = scale[0];
+
+        let nodata = decoder
+            .find_tag(Tag::GdalNodata)?
+            .map(|value| value.into_string())
+            .transpose()?
+            .map(|value| {
+                let value = value.trim_end_matches('\0').trim();
+                value.parse::<f64>().map_err(|_| {
+                    RasterError::InvalidHeader(format!("\"{value}\" is not a valid no data value"))
+                })
+            })
+            .transpose()?;
+
+        let geo_keys = match decoder.find_tag(Tag::GeoKeyDirectoryTag)? {
+            Some(directory) => Some(GeoKeys {
+                directory: directory.into_u16_vec()?,
+                double_params: decoder
+                    .find_tag(Tag::GeoDoubleParamsTag)?
+                    .map(|value| value.into_f64_vec())
+                    .transpose()?,
+                ascii_params: decoder
+                    .find_tag(Tag::GeoAsciiParamsTag)?
+                    .map(|value| value.into_string())
+                    .transpose()?,
+            }),
+            None => None,
+        };
+
+        let data: Vec<f64> = match decoder.read_image()? {
+            DecodingResult::U8(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::U16(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::U32(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::U64(data) => data.into_iter().map(|v| v as f64).collect(),
+            DecodingResult::F16(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::F32(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::F64(data) => data,
+            DecodingResult::I8(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::I16(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::I32(data) => data.into_iter().map(f64::from).collect(),
+            DecodingResult::I64(data) => data.into_iter().map(|v| v as f64).collect(),
+        };
+
+        let (cols, rows) = (cols as usize, rows as usize);
+        if data.len() != cols * rows {
+            return Err(RasterError::InvalidData(format!(
+                "expected {} values, found {}",
+                cols * rows,
+                data.len()
+            )));
+        }
+
+        Ok(Self {
+            grid: Grid::new(cols, rows, cell_size, with_nodata_as_nan(data, nodata)),
+            georeference: Georeference {
+                west: tiepoint[3] - tiepoint[0] * cell_size,
+                north: tiepoint[4] + tiepoint[1] * cell_size,
+                nodata,
+                geo_keys,
+            },
+        })
+    }
+
+    /// Write a single-band 32-bit floating point GeoTIFF
+    pub fn write_geotiff<W: Write + Seek>(&self, writer: W) -> Result<(), RasterError> {
+        let grid = &self.grid;
+        let georeference = &self.georeference;
+        let nodata = georeference.nodata;
+
+        let data: Vec<f32> = grid
+            .data
+            .iter()
+            .map(|&value| match nodata {
+                Some(nodata) if value.is_nan() => nodata as f32,
+                _ => value as f32,
+            })
+            .collect();
+
+        let mut encoder = TiffEncoder::new(writer)?;
+        let mut image = encoder.new_image::<Gray32Float>(grid.cols as u32, grid.rows as u32)?;
+        let directory = image.encoder();
+
+        directory.write_tag(
+            Tag::ModelPixelScaleTag,
+            &[grid.cell_size, grid.cell_size, 0.][..],
+        )?;
+        directory.write_tag(
+            Tag::ModelTiepointTag,
+            &[0., 0., 0., georeference.west, georeference.north, 0.][..],
+        )?;
+        if let Some(geo_keys) = &georeference.geo_keys {
+            directory.write_tag(Tag::GeoKeyDirectoryTag, &geo_keys.directory[..])?;
+            if let Some(double_params) = &geo_keys.double_params {
+                directory.write_tag(Tag::GeoDoubleParamsTag, &double_params[..])?;
+            }
+            if let Some(ascii_params) = &geo_keys.ascii_params {
+                directory.write_tag(Tag::GeoAsciiParamsTag, ascii_params.as_str())?;
+            }
+        }
+        if let Some(nodata) = nodata {
+            directory.write_tag(Tag::GdalNodata, nodata.to_string().as_str())?;
+        }
+
+        image.write_data(&data)?;
+
+        Ok(())
+    }
+}
+
+/// Supported raster file formats
+enum RasterFormat {
+    GeoTiff,
+    AsciiGrid,
+}
+
+impl RasterFormat {
+    fn from_path(path: &Path) -> Result<Self, RasterError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match extension.as_str() {
+            "tif" | "tiff" => Ok(RasterFormat::GeoTiff),
+            "asc" => Ok(RasterFormat::AsciiGrid),
+            _ => Err(RasterError::UnsupportedFormat(extension)),
+        }
+    }
+}
+
+/// Replace no data values with `NaN`
+fn with_nodata_as_nan(mut data: Vec<f64>, nodata: Option<f64>) -> Vec<f64> {
+    if let Some(nodata) = nodata {
+        for value in data.iter_mut().filter(|value| **value == nodata) {
+            *value = f64::NAN;
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn raster() -> Raster {
+        Raster {
+            grid: Grid::new(3, 2, 2000., vec![1., 2., f64::NAN, 4., 5.5, 6.]),
+            georeference: Georeference {
+                west: -2_000_000.,
+                north: 1_500_000.,
+                nodata: Some(-9999.),
+                geo_keys: Some(GeoKeys {
+                    directory: vec![1, 1, 0, 1, 3072, 0, 1, 3978],
+                    double_params: None,
+                    ascii_params: None,
+                }),
+            },
+        }
+    }
+
+    fn assert_grids_eq(a: &Grid<f64>, b: &Grid<f64>) {
+        assert!(a.same_shape(b));
+        assert_eq!(a.cell_size, b.cell_size);
+        for (a, b) in a.data.iter().zip(&b.data) {
+            assert!(a == b || (a.is_nan() && b.is_nan()), "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn test_ascii_grid() -> Result<(), Box<dyn std::error::Error>> {
+        let input = "\
+ncols 3
+nrows 2
+xllcenter 1000
+yllcenter 5000
+cellsize 100
+NODATA_value -1
+1 2 -1
+4 5 6
+";
+        let raster = Raster::from_ascii_grid(input.as_bytes())?;
+
+        assert_eq!(raster.georeference.west, 950.);
+        assert_eq!(raster.georeference.north, 5150.);
+        assert_eq!(raster.grid.get(0, 1), Some(&4.));
+        assert!(raster.grid.get(2, 0).unwrap().is_nan());
+
+        let mut output = Vec::new();
+        raster.write_ascii_grid(&mut output)?;
+        let round_trip = Raster::from_ascii_grid(output.as_slice())?;
+
+        assert_grids_eq(&round_trip.grid, &raster.grid);
+        assert_eq!(round_trip.georeference, raster.georeference);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ascii_grid_errors() {
+        assert!(matches!(
+            Raster::from_ascii_grid("ncols 2\nnrows 1\nxllcorner 0\ncellsize 1\n1 2".as_bytes()),
+            Err(RasterError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            Raster::from_ascii_grid(
+                "ncols 2\nnrows 1\nxllcorner 0\nyllcorner 0\ncellsize 1\n1".as_bytes()
+            ),
+            Err(RasterError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_geotiff() -> Result<(), Box<dyn std::error::Error>> {
+        let raster = raster();
+
+        let mut output = Cursor::new(Vec::new());
+        raster.write_geotiff(&mut output)?;
+        output.set_position(0);
+        let round_trip = Raster::from_geotiff(output)?;
+
+        assert_grids_eq(&round_trip.grid, &raster.grid);
+        assert_eq!(round_trip.georeference, raster.georeference);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuel_types() {
+        let mut table = FuelLookupTable::new();
+        table.insert(1, FuelLookupEntry::new(FbpFuelType::C1));
+        table.insert(2, FuelLookupEntry::new(FbpFuelType::C2));
+
+        let fuel_types = raster().fuel_types(&table);
+
+        assert_eq!(
+            fuel_types.data,
+            vec![
+                FbpFuelType::C1,
+                FbpFuelType::C2,
+                FbpFuelType::NonFuel,
+                FbpFuelType::NonFuel,
+                FbpFuelType::NonFuel,
+                FbpFuelType::NonFuel,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fuels() {
+        let mut table = FuelLookupTable::new();
+        table.insert(1, FuelLookupEntry::new(FbpFuelType::C2));
+        table.insert(
+            2,
+            FuelLookupEntry {
+                percent_conifer: Some(25.),
+                leaf_on_fuel_type: Some(FbpFuelType::M2),
+                ..FuelLookupEntry::new(FbpFuelType::M1)
+            },
+        );
+        table.insert(4, FuelLookupEntry::new(FbpFuelType::M1));
+        let season = LeafOnSeason {
+            green_up: 150,
+            leaf_fall: 270,
+        };
+
+        let fuels = raster().fuels(&table, 100, Some(&season));
+
+        assert_eq!(
+            fuels.fuel_type.data[..4],
+            [
+                FbpFuelType::C2,
+                FbpFuelType::M1,
+                FbpFuelType::NonFuel,
+                FbpFuelType::M1
+            ]
+        );
+        let percent_conifer = fuels.percent_conifer.unwrap();
+        assert!(percent_conifer.data[0].is_nan());
+        assert_eq!(percent_conifer.data[1], 25.);
+        assert!(fuels.percent_dead_balsam.is_none());
+        assert!(fuels.curing.is_none());
+
+        // Only the seasonal pair switches during the leaf-on season
+        let fuels = raster().fuels(&table, 200, Some(&season));
+        assert_eq!(fuels.fuel_type.data[1], FbpFuelType::M2);
+        assert_eq!(fuels.fuel_type.data[3], FbpFuelType::M1);
+        assert_eq!(
+            raster().fuels(&table, 200, None).fuel_type.data[1],
+            FbpFuelType::M1
+        );
+    }
+}
@@ -0,0 +1,587 @@
+//! Sensitivity of FBP outputs to their inputs
+//!
+//! Calculates how much head rate of spread, head fire intensity and crown fraction burned change
+//! per unit of each input (e.g. per km/h of wind speed or per point of FFMC), and sweeps each input
+//! over its typical range one at a time.
+//!
+//! Derivatives are calculated with central finite differences, halving the step until Richardson
+//! extrapolated estimates agree. At a kink in an output (e.g. where crowning starts), the result is
+//! between the slopes on either side.
+//!
+//! # Examples
+//!
+//! ```
+//! use cffdrs::fbp_system::{FbpFuelType, FbpOptions};
+//! use cffdrs::sensitivity::{FbpInput, FbpPoint};
+//! use cffdrs::weather::Weather;
+//! use chrono::prelude::*;
+//!
+//! let point = FbpPoint {
+//!     fuel_type: FbpFuelType::C2,
+//!     ffmc: 90.,
+//!     bui: 60.,
+//!     weather: Weather {
+//!         time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+//!         location: geo::Point::new(-120.34, 50.69),
+//!         temp: 28.,
+//!         rh: 20.,
+//!         ws: 15.,
+//!         wd: 270.,
+//!         precip: 0.,
+//!     },
+//!     options: FbpOptions { crown_base_height: 3., ..Default::default() },
+//! };
+//!
+//! // Change in head rate of spread (m/min) per km/h of wind
+//! let ros_per_km_h = point.derivative(FbpInput::WindSpeed).ros;
+//! assert!(ros_per_km_h > 0.);
+//!
+//! println!("{}", point.sensitivity_report(11));
+//! ```
+
+use std::fmt;
+
+use crate::{
+    fbp_system::{calculate_fbp, FbpFuelType, FbpOptions},
+    fwi_system::{initial_spread_index, HourlyFwiValues},
+    weather::Weather,
+};
+
+/// Initial finite difference step, relative to the input's value (or absolute below 1)
+const INITIAL_STEP: f64 = 1e-2;
+
+/// Number of times the step is halved before giving up on convergence
+const MAX_REFINEMENTS: usize = 10;
+
+/// Relative tolerance for successive derivative estimates to agree
+const TOLERANCE: f64 = 1e-7;
+
+/// An input to the FBP system
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum FbpInput {
+    /// Fine Fuel Moisture Code
+    Ffmc,
+    /// Buildup Index
+    Bui,
+    /// Wind speed (km/h)
+    WindSpeed,
+    /// Wind direction (degrees)
+    WindDirection,
+    /// Slope (%)
+    Slope,
+    /// Slope azimuth (degrees)
+    Aspect,
+    /// Percent conifer (%)
+    PercentConifer,
+    /// Percent dead balsam fir (%)
+    PercentDeadBalsam,
+    /// Degree of curing (%)
+    Curing,
+    /// Grass fuel load (kg/m^2)
+    GrassFuelLoad,
+    /// Crown base height (m)
+    CrownBaseHeight,
+    /// Elevation (m). It changes FMC through the date of minimum FMC, which is a whole day, so
+    /// its derivative is usually 0 and its effect shows up in sweeps. Without an
+    /// [`FbpOptions::elevation`], its value is `NaN`
+    Elevation,
+}
+
+impl FbpInput {
+    /// Every input, in declaration order
+    pub const ALL: [FbpInput; 12] = [
+        FbpInput::Ffmc,
+        FbpInput::Bui,
+        FbpInput::WindSpeed,
+        FbpInput::WindDirection,
+        FbpInput::Slope,
+        FbpInput::Aspect,
+        FbpInput::PercentConifer,
+        FbpInput::PercentDeadBalsam,
+        FbpInput::Curing,
+        FbpInput::GrassFuelLoad,
+        FbpInput::CrownBaseHeight,
+        FbpInput::Elevation,
+    ];
+
+    /// Short name of the input, as used by R cffdrs `fbp()`
+    pub fn name(&self) -> &'static str {
+        match self {
+            FbpInput::Ffmc => "FFMC",
+            FbpInput::Bui => "BUI",
+            FbpInput::WindSpeed => "WS",
+            FbpInput::WindDirection => "WD",
+            FbpInput::Slope => "GS",
+            FbpInput::Aspect => "Aspect",
+            FbpInput::PercentConifer => "PC",
+            FbpInput::PercentDeadBalsam => "PDF",
+            FbpInput::Curing => "CC",
+            FbpInput::GrassFuelLoad => "GFL",
+            FbpInput::CrownBaseHeight => "CBH",
+            FbpInput::Elevation => "ELV",
+        }
+    }
+
+    /// Typical range of the input, used by [FbpPoint::sensitivity_report()]
+    pub fn range(&self) -> (f64, f64) {
+        match self {
+            FbpInput::Ffmc => (70., 98.),
+            FbpInput::Bui => (0., 200.),
+            FbpInput::WindSpeed => (0., 60.),
+            FbpInput::WindDirection | FbpInput::Aspect => (0., 360.),
+            FbpInput::Slope => (0., 60.),
+            FbpInput::PercentConifer | FbpInput::PercentDeadBalsam | FbpInput::Curing => (0., 100.),
+            FbpInput::GrassFuelLoad => (0.1, 1.),
+            FbpInput::CrownBaseHeight => (1., 15.),
+            FbpInput::Elevation => (0., 2500.),
+        }
+    }
+
+    /// Valid values of the input. Finite differences don't step outside of these
+    fn domain(&self) -> (f64, f64) {
+        match self {
+            FbpInput::Ffmc => (0., 101.),
+            FbpInput::WindDirection | FbpInput::Aspect => (f64::NEG_INFINITY, f64::INFINITY),
+            FbpInput::PercentConifer | FbpInput::PercentDeadBalsam | FbpInput::Curing => (0., 100.),
+            _ => (0., f64::INFINITY),
+        }
+    }
+}
+
+impl fmt::Display for FbpInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One of the [FbpOutputs]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum FbpOutput {
+    /// Head rate of spread (m/min)
+    Ros,
+    /// Head fire intensity (kW/m)
+    Hfi,
+    /// Crown fraction burned (0-1)
+    Cfb,
+}
+
+/// The FBP outputs that sensitivities are calculated for (or their derivatives)
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct FbpOutputs {
+    /// Head rate of spread (m/min)
+    pub ros: f64,
+    /// Head fire intensity (kW/m)
+    pub hfi: f64,
+    /// Crown fraction burned (0-1)
+    pub cfb: f64,
+}
+
+impl FbpOutputs {
+    /// Value of one output
+    pub fn get(&self, output: FbpOutput) -> f64 {
+        match output {
+            FbpOutput::Ros => self.ros,
+            FbpOutput::Hfi => self.hfi,
+            FbpOutput::Cfb => self.cfb,
+        }
+    }
+
+    /// `(self - other) / step` for each output
+    fn slope(&self, other: &FbpOutputs, step: f64) -> FbpOutputs {
+        FbpOutputs {
+            ros: (self.ros - other.ros) / step,
+            hfi: (self.hfi - other.hfi) / step,
+            cfb: (self.cfb - other.cfb) / step,
+        }
+    }
+
+    /// Richardson extrapolation of estimates with steps `h` (`self`) and `h / 2` (`finer`), with
+    /// errors of the given order
+    fn extrapolate(&self, finer: &FbpOutputs, order: i32) -> FbpOutputs {
+        let factor = 2f64.powi(order);
+        let extrapolate = |coarse: f64, fine: f64| (factor * fine - coarse) / (factor - 1.);
+
+        FbpOutputs {
+            ros: extrapolate(self.ros, finer.ros),
+            hfi: extrapolate(self.hfi, finer.hfi),
+            cfb: extrapolate(self.cfb, finer.cfb),
+        }
+    }
+
+    fn close_to(&self, other: &FbpOutputs) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= TOLERANCE * a.abs().max(b.abs()).max(1e-3);
+
+        close(self.ros, other.ros) && close(self.hfi, other.hfi) && close(self.cfb, other.cfb)
+    }
+}
+
+/// FBP inputs to calculate sensitivities at
+///
+/// ISI is calculated from FFMC and wind speed, so that both change it.
+#[derive(Debug, Clone)]
+pub struct FbpPoint {
+    /// Fuel type
+    pub fuel_type: FbpFuelType,
+    /// Fine Fuel Moisture Code
+    pub ffmc: f64,
+    /// Buildup Index
+    pub bui: f64,
+    /// Weather. Only the time, location, wind speed and direction are used
+    pub weather: Weather,
+    /// FBP options, including the slope, aspect and fuel-specific inputs
+    pub options: FbpOptions,
+}
+
+impl FbpPoint {
+    /// Calculate the FBP outputs
+    pub fn outputs(&self) -> FbpOutputs {
+        let fwi = HourlyFwiValues {
+            ffmc: self.ffmc,
+            bui: self.bui,
+            isi: initial_spread_index(self.ffmc, self.weather.ws),
+            // Not used by the FBP system
+            dc: f64::NAN,
+            dmc: f64::NAN,
+            fwi: f64::NAN,
+        };
+        let values = calculate_fbp(self.fuel_type, &fwi, &self.weather, self.options.clone());
+
+        FbpOutputs {
+            ros: values.ros,
+            hfi: values.fi,
+            cfb: values.cfb,
+        }
+    }
+
+    /// Value of an input
+    pub fn value(&self, input: FbpInput) -> f64 {
+        match input {
+            FbpInput::Ffmc => self.ffmc,
+            FbpInput::Bui => self.bui,
+            FbpInput::WindSpeed => self.weather.ws,
+            FbpInput::WindDirection => self.weather.wd,
+            FbpInput::Slope => self.options.slope,
+            FbpInput::Aspect => self.options.aspect,
+            FbpInput::PercentConifer => self.options.percent_conifer,
+            FbpInput::PercentDeadBalsam => self.options.percent_dead_balsam,
+            FbpInput::Curing => self.options.curing,
+            FbpInput::GrassFuelLoad => self.options.grass_fuel_load,
+            FbpInput::CrownBaseHeight => self.options.crown_base_height,
+            FbpInput::Elevation => self.options.elevation.unwrap_or(f64::NAN),
+        }
+    }
+
+    /// Copy of the point, with one input changed
+    pub fn with_value(&self, input: FbpInput, value: f64) -> Self {
+        let mut point = self.clone();
+        match input {
+            FbpInput::Ffmc => point.ffmc = value,
+            FbpInput::Bui => point.bui = value,
+            FbpInput::WindSpeed => point.weather.ws = value,
+            FbpInput::WindDirection => point.weather.wd = value,
+            FbpInput::Slope => point.options.slope = value,
+            FbpInput::Aspect => point.options.aspect = value,
+            FbpInput::PercentConifer => point.options.percent_conifer = value,
+            FbpInput::PercentDeadBalsam => point.options.percent_dead_balsam = value,
+            FbpInput::Curing => point.options.curing = value,
+            FbpInput::GrassFuelLoad => point.options.grass_fuel_load = value,
+            FbpInput::CrownBaseHeight => point.options.crown_base_height = value,
+            FbpInput::Elevation => point.options.elevation = Some(value),
+        }
+        point
+    }
+
+    /// Partial derivatives of the outputs with respect to an input (change per unit of the input)
+    pub fn derivative(&self, input: FbpInput) -> FbpOutputs {
+        let x = self.value(input);
+        let (min, max) = input.domain();
+        let outputs = |value: f64| self.with_value(input, value).outputs();
+
+        let initial_step = INITIAL_STEP * x.abs().max(1.);
+        // Step forwards or backwards from the edges of the input's domain
+        let (offsets, order) = if x - initial_step < min {
+            ((0., 1.), 1)
+        } else if x + initial_step > max {
+            ((-1., 0.), 1)
+        } else {
+            ((-1., 1.), 2)
+        };
+        let difference = |h: f64| {
+            let (below, above) = (offsets.0 * h, offsets.1 * h);
+            outputs(x + above).slope(&outputs(x + below), above - below)
+        };
+
+        let mut step = initial_step;
+        let mut coarse = difference(step);
+        let mut fine = difference(step / 2.);
+        let mut estimate = coarse.extrapolate(&fine, order);
+
+        for _ in 0..MAX_REFINEMENTS {
+            step /= 2.;
+            coarse = fine;
+            fine = difference(step / 2.);
+
+            let next = coarse.extrapolate(&fine, order);
+            if next.close_to(&estimate) {
+                return next;
+            }
+            estimate = next;
+        }
+
+        estimate
+    }
+
+    /// Partial derivatives of the outputs with respect to every input
+    pub fn derivatives(&self) -> Vec<(FbpInput, FbpOutputs)> {
+        FbpInput::ALL
+            .iter()
+            .map(|&input| (input, self.derivative(input)))
+            .collect()
+    }
+
+    /// Calculate the outputs with one input changed to each of `values`, keeping the others fixed
+    pub fn sweep(
+        &self,
+        input: FbpInput,
+        values: impl IntoIterator<Item = f64>,
+    ) -> Vec<(f64, FbpOutputs)> {
+        values
+            .into_iter()
+            .map(|value| (value, self.with_value(input, value).outputs()))
+            .collect()
+    }
+
+    /// One-at-a-time sensitivity of the outputs to every input, sweeping each over `steps`
+    /// evenly spaced values in its [range][FbpInput::range()]
+    ///
+    /// Rows are sorted by how much the head rate of spread changes over the sweep, largest first.
+    pub fn sensitivity_report(&self, steps: usize) -> SensitivityReport {
+        let mut rows: Vec<SensitivityRow> = FbpInput::ALL
+            .iter()
+            .map(|&input| {
+                let (min, max) = input.range();
+                let values =
+                    (0..steps).map(|i| min + (max - min) * i as f64 / (steps - 1).max(1) as f64);
+
+                SensitivityRow {
+                    input,
+                    value: self.value(input),
+                    derivative: self.derivative(input),
+                    sweep: self.sweep(input, values),
+                }
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let swing = |row: &SensitivityRow| {
+                let (min, max) = row.ros_range();
+                max - min
+            };
+            swing(b).total_cmp(&swing(a))
+        });
+
+        SensitivityReport {
+            base: self.outputs(),
+            rows,
+        }
+    }
+}
+
+/// Sensitivity of the outputs to one input
+#[derive(Debug, Clone)]
+pub struct SensitivityRow {
+    /// Input that's varied
+    pub input: FbpInput,
+    /// Value of the input at the [FbpPoint]
+    pub value: f64,
+    /// Partial derivatives of the outputs at the [FbpPoint]
+    pub derivative: FbpOutputs,
+    /// Outputs for each value of the input over its range
+    pub sweep: Vec<(f64, FbpOutputs)>,
+}
+
+impl SensitivityRow {
+    /// Lowest and highest head rate of spread over the sweep
+    pub fn ros_range(&self) -> (f64, f64) {
+        self.sweep.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), (_, outputs)| (min.min(outputs.ros), max.max(outputs.ros)),
+        )
+    }
+}
+
+/// One-at-a-time sensitivity of the FBP outputs to each input, see
+/// [FbpPoint::sensitivity_report()]
+///
+/// Displays as a table of derivatives and rate of spread ranges.
+#[derive(Debug, Clone)]
+pub struct SensitivityReport {
+    /// Outputs at the [FbpPoint]
+    pub base: FbpOutputs,
+    /// Sensitivity to each input, largest head rate of spread range first
+    pub rows: Vec<SensitivityRow>,
+}
+
+impl fmt::Display for SensitivityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "ROS {:.2} m/min, HFI {:.0} kW/m, CFB {:.2}",
+            self.base.ros, self.base.hfi, self.base.cfb
+        )?;
+        writeln!(
+            f,
+            "{:<8} {:>10} {:>12} {:>12} {:>12} {:>19}",
+            "Input", "Value", "dROS/dx", "dHFI/dx", "dCFB/dx", "ROS range"
+        )?;
+        for row in &self.rows {
+            let (min, max) = row.ros_range();
+            writeln!(
+                f,
+                "{:<8} {:>10.2} {:>12.4} {:>12.2} {:>12.5} {:>9.2} - {:>7.2}",
+                row.input.name(),
+                row.value,
+                row.derivative.ros,
+                row.derivative.hfi,
+                row.derivative.cfb,
+                min,
+                max
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::*;
+
+    fn point(fuel_type: FbpFuelType) -> FbpPoint {
+        FbpPoint {
+            fuel_type,
+            ffmc: 90.,
+            bui: 60.,
+            weather: Weather {
+                time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+                location: geo::Point::new(-120.34, 50.69),
+                temp: 28.,
+                rh: 20.,
+                ws: 15.,
+                wd: 270.,
+                precip: 0.,
+            },
+            options: FbpOptions {
+                elevation: Some(500.),
+                slope: 20.,
+                aspect: 45.,
+                crown_base_height: 3.,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_derivatives_match_secants() {
+        for fuel_type in [FbpFuelType::C2, FbpFuelType::M1, FbpFuelType::O1a] {
+            let point = point(fuel_type);
+
+            for (input, derivative) in point.derivatives() {
+                let x = point.value(input);
+                let h = 1e-4 * x.abs().max(1.);
+                let below = point.with_value(input, (x - h).max(input.domain().0));
+                let above = point.with_value(input, x + h);
+                let secant = above
+                    .outputs()
+                    .slope(&below.outputs(), above.value(input) - below.value(input));
+
+                for (actual, expected) in [
+                    (derivative.ros, secant.ros),
+                    (derivative.hfi, secant.hfi),
+                    (derivative.cfb, secant.cfb),
+                ] {
+                    assert!(
+                        (actual - expected).abs() <= 1e-4 * expected.abs().max(1e-3),
+                        "{fuel_type} {input}: {actual} != {expected}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hfi_is_linear_in_grass_fuel_load() {
+        // Grass fuel load only changes O1a fuel consumption, which HFI is proportional to
+        let point = point(FbpFuelType::O1a);
+        let derivative = point.derivative(FbpInput::GrassFuelLoad);
+        let outputs = point.outputs();
+
+        assert_eq!(derivative.ros, 0.);
+        approx::assert_relative_eq!(
+            derivative.hfi,
+            outputs.hfi / point.options.grass_fuel_load,
+            max_relative = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_derivative_at_domain_edge() {
+        let point = point(FbpFuelType::M1).with_value(FbpInput::PercentConifer, 100.);
+        let derivative = point.derivative(FbpInput::PercentConifer);
+
+        assert!(derivative.ros.is_finite());
+        assert!(derivative.ros > 0.);
+    }
+
+    #[test]
+    fn test_elevation() {
+        // Higher stands reach their minimum FMC later in the year, further from this date, so have
+        // a higher FMC and a lower CFB
+        let point = point(FbpFuelType::C2).with_value(FbpInput::WindSpeed, 25.);
+        let sweep = point.sweep(FbpInput::Elevation, [0., 2500.]);
+
+        assert!(sweep[1].1.cfb < sweep[0].1.cfb);
+        assert!(FbpPoint {
+            options: FbpOptions::default(),
+            ..point
+        }
+        .value(FbpInput::Elevation)
+        .is_nan());
+    }
+
+    #[test]
+    fn test_sensitivity_report() {
+        let point = FbpPoint {
+            options: FbpOptions {
+                crown_base_height: 3.,
+                ..Default::default()
+            },
+            ..point(FbpFuelType::C2)
+        };
+        let report = point.sensitivity_report(11);
+
+        assert_eq!(report.rows.len(), FbpInput::ALL.len());
+        assert_eq!(report.base, point.outputs());
+        assert_eq!(report.rows[0].sweep.len(), 11);
+
+        // Wind speed and FFMC drive spread on flat ground, while C2 ignores the mixedwood and
+        // grass inputs
+        let top: Vec<FbpInput> = report.rows[..2].iter().map(|row| row.input).collect();
+        assert!(top.contains(&FbpInput::WindSpeed) && top.contains(&FbpInput::Ffmc));
+        for row in &report.rows {
+            if matches!(
+                row.input,
+                FbpInput::PercentConifer | FbpInput::Curing | FbpInput::GrassFuelLoad
+            ) {
+                assert_eq!(row.ros_range().0, row.ros_range().1);
+                assert_eq!(row.derivative.ros, 0.);
+            }
+        }
+
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), FbpInput::ALL.len() + 2);
+        assert!(table.lines().nth(2).unwrap().starts_with(top[0].name()));
+    }
+}
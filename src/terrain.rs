@@ -0,0 +1,251 @@
+//! Terrain (slope and aspect) derived from digital elevation models
+
+use core::f64::consts::PI;
+
+use crate::{fbp_system::FbpOptions, grid::Grid};
+
+/// Mean radius of the earth (m)
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Coordinate system of an elevation grid
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum GridCoordinates {
+    /// Projected coordinates, with [`Grid::cell_size`] in metres
+    Projected,
+    /// Geographic coordinates, with [`Grid::cell_size`] in degrees
+    Geographic {
+        /// Latitude of the northern edge of the grid (degrees)
+        north: f64,
+    },
+}
+
+/// Elevation, slope and aspect grids
+#[derive(Debug, Clone)]
+pub struct Terrain {
+    /// Elevation (m)
+    pub elevation: Grid<f64>,
+    /// Ground slope (%)
+    pub slope: Grid<f64>,
+    /// Aspect, the direction the slope faces (degrees, 0 = north, 90 = east, etc.). Flat cells
+    /// have an aspect of 0
+    pub aspect: Grid<f64>,
+}
+
+impl Terrain {
+    /// Calculate slope and aspect from an elevation grid using Horn's method
+    ///
+    /// Cells on the edge of the grid use one-sided differences, by repeating the edge elevations
+    /// and dividing by the narrower span of the window. Cells next to a `NaN` (no data) elevation
+    /// have a `NaN` slope and aspect.
+    ///
+    /// * `elevation` - Elevation (m)
+    /// * `coordinates` - Coordinate system of the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cffdrs::grid::Grid;
+    /// # use cffdrs::terrain::{GridCoordinates, Terrain};
+    /// // Rising 10m per 100m cell to the east, so facing west
+    /// let elevation = Grid::new(3, 3, 100., vec![
+    ///     100., 110., 120.,
+    ///     100., 110., 120.,
+    ///     100., 110., 120.,
+    /// ]);
+    ///
+    /// let terrain = Terrain::from_elevation(elevation, GridCoordinates::Projected);
+    ///
+    /// assert_eq!(*terrain.slope.get(1, 1).unwrap(), 10.);
+    /// assert_eq!(*terrain.aspect.get(1, 1).unwrap(), 270.);
+    /// ```
+    pub fn from_elevation(elevation: Grid<f64>, coordinates: GridCoordinates) -> Self {
+        let cols = elevation.cols;
+        let rows = elevation.rows;
+
+        let mut slope = Grid::filled(cols, rows, elevation.cell_size, 0.);
+        let mut aspect = Grid::filled(cols, rows, elevation.cell_size, 0.);
+
+        for row in 0..rows {
+            let (dx, dy) = cell_dimensions(elevation.cell_size, row, coordinates);
+
+            for col in 0..cols {
+                // Elevation of a neighbour, repeating edge cells
+                let z = |dcol: isize, drow: isize| {
+                    let c = (col as isize + dcol).clamp(0, cols as isize - 1) as usize;
+                    let r = (row as isize + drow).clamp(0, rows as isize - 1) as usize;
+                    elevation.data[elevation.index(c, r)]
+                };
+
+                let (a, b, c) = (z(-1, -1), z(0, -1), z(1, -1));
+                let (d, f) = (z(-1, 0), z(1, 0));
+                let (g, h, i) = (z(-1, 1), z(0, 1), z(1, 1));
+
+                // Number of cells spanned by the window, which is 1 rather than 2 on the edges
+                let col_span = ((col + 1).min(cols - 1) - col.saturating_sub(1)) as f64;
+                let row_span = ((row + 1).min(rows - 1) - row.saturating_sub(1)) as f64;
+
+                // Rate of change eastwards and northwards
+                let dz_dx = if col_span > 0. {
+                    ((c + 2. * f + i) - (a + 2. * d + g)) / (4. * col_span * dx)
+                } else {
+                    0.
+                };
+                let dz_dy = if row_span > 0. {
+                    ((a + 2. * b + c) - (g + 2. * h + i)) / (4. * row_span * dy)
+                } else {
+                    0.
+                };
+
+                let index = slope.index(col, row);
+                slope.data[index] = 100. * (dz_dx.powi(2) + dz_dy.powi(2)).sqrt();
+                aspect.data[index] = if dz_dx == 0. && dz_dy == 0. {
+                    0.
+                } else {
+                    // Direction of steepest descent
+                    (-dz_dx).atan2(-dz_dy).to_degrees().rem_euclid(360.)
+                };
+            }
+        }
+
+        Self {
+            elevation,
+            slope,
+            aspect,
+        }
+    }
+
+    /// FBP options for a cell, with elevation, slope and aspect from the terrain and everything
+    /// else from `options`
+    pub fn options(&self, col: usize, row: usize, options: &FbpOptions) -> Option<FbpOptions> {
+        let index = self.elevation.index(col, row);
+
+        (col < self.elevation.cols && row < self.elevation.rows).then(|| FbpOptions {
+            elevation: Some(self.elevation.data[index]),
+            slope: self.slope.data[index],
+            aspect: self.aspect.data[index],
+            ..options.clone()
+        })
+    }
+}
+
+/// Width and height of cells in a row (m)
+fn cell_dimensions(cell_size: f64, row: usize, coordinates: GridCoordinates) -> (f64, f64) {
+    match coordinates {
+        GridCoordinates::Projected => (cell_size, cell_size),
+        GridCoordinates::Geographic { north } => {
+            let metres_per_degree = EARTH_RADIUS * PI / 180.;
+            // Latitude of the centre of the row
+            let lat = north - (row as f64 + 0.5) * cell_size;
+
+            (
+                cell_size * metres_per_degree * lat.to_radians().cos(),
+                cell_size * metres_per_degree,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_terrain_aspect() {
+        // Cone-shaped hill, so each side faces away from the centre
+        let mut elevation = Grid::filled(5, 5, 30., 0.);
+        for row in 0..5 {
+            for col in 0..5 {
+                let distance = ((col as f64 - 2.).powi(2) + (row as f64 - 2.).powi(2)).sqrt();
+                let index = elevation.index(col, row);
+                elevation.data[index] = 100. - 10. * distance;
+            }
+        }
+
+        let terrain = Terrain::from_elevation(elevation, GridCoordinates::Projected);
+
+        assert_relative_eq!(*terrain.aspect.get(2, 1).unwrap(), 0.);
+        assert_relative_eq!(*terrain.aspect.get(3, 2).unwrap(), 90.);
+        assert_relative_eq!(*terrain.aspect.get(2, 3).unwrap(), 180.);
+        assert_relative_eq!(*terrain.aspect.get(1, 2).unwrap(), 270.);
+        assert_relative_eq!(*terrain.aspect.get(3, 1).unwrap(), 45.);
+        assert_eq!(*terrain.slope.get(2, 2).unwrap(), 0.);
+    }
+
+    #[test]
+    fn test_terrain_geographic() {
+        // Rising 10m per cell to the north at 60N, where a degree of longitude is half as wide as
+        // a degree of latitude
+        let cell_size = 0.001;
+        let elevation = Grid::new(
+            3,
+            3,
+            cell_size,
+            vec![20., 20., 20., 10., 10., 10., 0., 0., 0.],
+        );
+        let north_south = Terrain::from_elevation(
+            elevation,
+            GridCoordinates::Geographic {
+                north: 60. + 1.5 * cell_size,
+            },
+        );
+
+        let elevation = Grid::new(
+            3,
+            3,
+            cell_size,
+            vec![0., 10., 20., 0., 10., 20., 0., 10., 20.],
+        );
+        let east_west = Terrain::from_elevation(
+            elevation,
+            GridCoordinates::Geographic {
+                north: 60. + 1.5 * cell_size,
+            },
+        );
+
+        assert_relative_eq!(*north_south.aspect.get(1, 1).unwrap(), 180.);
+        assert_relative_eq!(*east_west.aspect.get(1, 1).unwrap(), 270.);
+        assert_relative_eq!(
+            *east_west.slope.get(1, 1).unwrap(),
+            2. * *north_south.slope.get(1, 1).unwrap(),
+            max_relative = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_terrain_edges() {
+        let elevation = Grid::new(2, 1, 100., vec![500., 510.]);
+        let terrain = Terrain::from_elevation(elevation, GridCoordinates::Projected);
+
+        for col in 0..2 {
+            assert_relative_eq!(*terrain.slope.get(col, 0).unwrap(), 10.);
+            assert_relative_eq!(*terrain.aspect.get(col, 0).unwrap(), 270.);
+        }
+
+        // A uniform slope to the south has the same slope everywhere, including corners
+        let elevation = Grid::new(
+            3,
+            3,
+            100.,
+            vec![120., 120., 120., 110., 110., 110., 100., 100., 100.],
+        );
+        let terrain = Terrain::from_elevation(elevation, GridCoordinates::Projected);
+
+        for (col, row) in [(0, 0), (1, 0), (2, 2), (0, 1), (2, 1)] {
+            assert_relative_eq!(*terrain.slope.get(col, row).unwrap(), 10.);
+            assert_relative_eq!(*terrain.aspect.get(col, row).unwrap(), 180.);
+        }
+    }
+
+    #[test]
+    fn test_terrain_options() {
+        let elevation = Grid::new(2, 1, 100., vec![500., 510.]);
+        let terrain = Terrain::from_elevation(elevation, GridCoordinates::Projected);
+
+        let options = terrain.options(1, 0, &FbpOptions::default()).unwrap();
+        assert_eq!(options.elevation, Some(510.));
+        assert_eq!(options.aspect, 270.);
+        assert!(terrain.options(2, 0, &FbpOptions::default()).is_none());
+    }
+}
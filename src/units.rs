@@ -0,0 +1,431 @@
+//! Typed physical units for weather, FBP options and FBP values
+//!
+//! [Weather], [FbpOptions] and [FbpValues] mirror the `f64` types of the same names, with each
+//! field stored as a [uom](https://docs.rs/uom) quantity, so values like wind direction (degrees)
+//! and direction of spread (radians) can't be mixed up. Convert to and from the `f64` types with
+//! [From], or calculate directly with [calculate_fbp()].
+//!
+//! Converting between units can change the last digit of a value, so results may differ from the
+//! `f64` functions by a few ulps.
+//!
+//! Requires the `units` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use cffdrs::fbp_system::FbpFuelType;
+//! use cffdrs::fwi_system::calculate_hourly;
+//! use cffdrs::units::{calculate_fbp, meter_per_minute, FbpOptions, Weather};
+//! use chrono::prelude::*;
+//! use uom::si::{
+//!     angle::degree, f64::*, length::millimeter, ratio::percent,
+//!     thermodynamic_temperature::degree_celsius, velocity::kilometer_per_hour,
+//! };
+//!
+//! let weather = Weather {
+//!     time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+//!     location: geo::Point::new(-120.34, 50.69),
+//!     temp: ThermodynamicTemperature::new::<degree_celsius>(28.),
+//!     rh: Ratio::new::<percent>(12.),
+//!     ws: Velocity::new::<kilometer_per_hour>(16.),
+//!     wd: Angle::new::<degree>(270.),
+//!     precip: Length::new::<millimeter>(0.),
+//! };
+//!
+//! let fwi = calculate_hourly(&weather.clone().into(), None);
+//! let fbp = calculate_fbp(FbpFuelType::C2, &fwi, &weather, FbpOptions::default());
+//!
+//! assert!(fbp.ros.get::<meter_per_minute>() > 0.);
+//! // Wind from the west spreads the fire east
+//! assert_eq!(fbp.raz.get::<degree>().rem_euclid(360.).round(), 90.);
+//! ```
+
+use chrono::{DateTime, Utc};
+use geo::Point;
+use uom::si::{
+    angle::{degree, radian},
+    areal_mass_density::kilogram_per_square_meter,
+    f64::{
+        Angle, ArealMassDensity, Length, LinearPowerDensity, Ratio, ThermodynamicTemperature,
+        Velocity,
+    },
+    length::{meter, millimeter},
+    ratio::{percent, ratio},
+    thermodynamic_temperature::degree_celsius,
+    velocity::kilometer_per_hour,
+};
+
+use crate::{
+    fbp_system::{self, FbpFuelType, LeafOnSeason},
+    fwi_system::HourlyFwiValues,
+    weather,
+};
+
+/// Define a unit of a uom quantity, from its coefficient to the quantity's base unit
+///
+/// uom's own `unit!` macro can't be used outside of uom in `cfg(test)` builds, so this implements
+/// its traits directly.
+macro_rules! fbp_unit {
+    (
+        $(#[$attr:meta])* $unit:ident, $quantity:ident, $coefficient:expr,
+        $abbreviation:expr, $singular:expr, $plural:expr
+    ) => {
+        $(#[$attr])*
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy, Debug, Hash)]
+        pub struct $unit;
+
+        impl uom::si::Unit for $unit {
+            fn abbreviation() -> &'static str {
+                $abbreviation
+            }
+
+            fn singular() -> &'static str {
+                $singular
+            }
+
+            fn plural() -> &'static str {
+                $plural
+            }
+        }
+
+        impl uom::si::$quantity::Unit for $unit {}
+
+        impl uom::Conversion<f64> for $unit {
+            type T = f64;
+
+            #[inline(always)]
+            fn coefficient() -> f64 {
+                $coefficient
+            }
+        }
+
+        impl uom::si::$quantity::Conversion<f64> for $unit {}
+    };
+}
+
+fbp_unit!(
+    /// Meters per minute, the unit of FBP rates of spread
+    meter_per_minute,
+    velocity,
+    1. / 60.,
+    "m/min",
+    "meter per minute",
+    "meters per minute"
+);
+
+fbp_unit!(
+    /// Kilowatts per meter, the unit of FBP fire intensities
+    kilowatt_per_meter,
+    linear_power_density,
+    1000.,
+    "kW/m",
+    "kilowatt per meter",
+    "kilowatts per meter"
+);
+
+/// A weather record with typed units, see [weather::Weather]
+#[derive(Debug, Clone)]
+pub struct Weather {
+    /// UTC time when the weather was sampled
+    pub time: DateTime<Utc>,
+    /// Location the weather was sampled at
+    pub location: Point<f64>,
+    /// Temperature
+    pub temp: ThermodynamicTemperature,
+    /// Relative humidity
+    pub rh: Ratio,
+    /// Wind speed (at 10m height)
+    pub ws: Velocity,
+    /// Wind direction (0 = wind from north, 90 degrees = from east, etc.)
+    pub wd: Angle,
+    /// Precipitation
+    pub precip: Length,
+}
+
+impl From<Weather> for weather::Weather {
+    fn from(weather: Weather) -> Self {
+        Self {
+            time: weather.time,
+            location: weather.location,
+            temp: weather.temp.get::<degree_celsius>(),
+            rh: weather.rh.get::<percent>(),
+            ws: weather.ws.get::<kilometer_per_hour>(),
+            wd: weather.wd.get::<degree>(),
+            precip: weather.precip.get::<millimeter>(),
+        }
+    }
+}
+
+impl From<weather::Weather> for Weather {
+    fn from(weather: weather::Weather) -> Self {
+        Self {
+            time: weather.time,
+            location: weather.location,
+            temp: ThermodynamicTemperature::new::<degree_celsius>(weather.temp),
+            rh: Ratio::new::<percent>(weather.rh),
+            ws: Velocity::new::<kilometer_per_hour>(weather.ws),
+            wd: Angle::new::<degree>(weather.wd),
+            precip: Length::new::<millimeter>(weather.precip),
+        }
+    }
+}
+
+/// FBP options with typed units, see [fbp_system::FbpOptions]
+#[derive(Debug, Clone)]
+pub struct FbpOptions {
+    /// Elevation, used to calculate foliar moisture content
+    pub elevation: Option<Length>,
+    /// Date of the year with minimum foliar moisture content, as a Julian day. Calculated from
+    /// the location (and elevation) when `None`
+    pub date_of_minimum_fmc: Option<i32>,
+    /// Slope (rise over run)
+    pub slope: Ratio,
+    /// Slope azimuth
+    pub aspect: Angle,
+    /// Degree of curing for grass fuel types
+    pub curing: Ratio,
+    /// Grass fuel load, used for O1a/O1b fuel consumption and intensity
+    pub grass_fuel_load: ArealMassDensity,
+    /// Percent conifer
+    pub percent_conifer: Ratio,
+    /// Percent dead balsam fir
+    pub percent_dead_balsam: Ratio,
+    /// Leaf-on season, see [`fbp_system::FbpOptions::leaf_on_season`]
+    pub leaf_on_season: Option<LeafOnSeason>,
+    /// Crown base height. When zero, the fuel type's default is used, see
+    /// [`fbp_system::FbpOptions::crown_base_height`]
+    pub crown_base_height: Length,
+}
+
+impl Default for FbpOptions {
+    fn default() -> Self {
+        fbp_system::FbpOptions::default().into()
+    }
+}
+
+impl From<FbpOptions> for fbp_system::FbpOptions {
+    fn from(options: FbpOptions) -> Self {
+        Self {
+            elevation: options.elevation.map(|elevation| elevation.get::<meter>()),
+            date_of_minimum_fmc: options.date_of_minimum_fmc,
+            slope: options.slope.get::<percent>(),
+            aspect: options.aspect.get::<degree>(),
+            curing: options.curing.get::<percent>(),
+            grass_fuel_load: options.grass_fuel_load.get::<kilogram_per_square_meter>(),
+            percent_conifer: options.percent_conifer.get::<percent>(),
+            percent_dead_balsam: options.percent_dead_balsam.get::<percent>(),
+            leaf_on_season: options.leaf_on_season,
+            crown_base_height: options.crown_base_height.get::<meter>(),
+        }
+    }
+}
+
+impl From<fbp_system::FbpOptions> for FbpOptions {
+    fn from(options: fbp_system::FbpOptions) -> Self {
+        Self {
+            elevation: options.elevation.map(Length::new::<meter>),
+            date_of_minimum_fmc: options.date_of_minimum_fmc,
+            slope: Ratio::new::<percent>(options.slope),
+            aspect: Angle::new::<degree>(options.aspect),
+            curing: Ratio::new::<percent>(options.curing),
+            grass_fuel_load: ArealMassDensity::new::<kilogram_per_square_meter>(
+                options.grass_fuel_load,
+            ),
+            percent_conifer: Ratio::new::<percent>(options.percent_conifer),
+            percent_dead_balsam: Ratio::new::<percent>(options.percent_dead_balsam),
+            leaf_on_season: options.leaf_on_season,
+            crown_base_height: Length::new::<meter>(options.crown_base_height),
+        }
+    }
+}
+
+/// FBP values with typed units, see [fbp_system::FbpValues]
+#[derive(Debug, Clone)]
+pub struct FbpValues {
+    /// Crown fraction burned
+    pub cfb: Ratio,
+    /// Direction of spread
+    pub raz: Angle,
+    /// Head rate of spread
+    pub ros: Velocity,
+    /// Flank rate of spread
+    pub fros: Velocity,
+    /// Back rate of spread
+    pub bros: Velocity,
+    /// Foliar moisture content
+    pub fmc: Ratio,
+    /// Surface fuel consumption
+    pub sfc: ArealMassDensity,
+    /// Crown fuel consumption
+    pub cfc: ArealMassDensity,
+    /// Total fuel consumption
+    pub tfc: ArealMassDensity,
+    /// Crown fuel load
+    pub cfl: ArealMassDensity,
+    /// Crown base height
+    pub cbh: Length,
+    /// Effective wind speed
+    pub wsv: Velocity,
+    /// Effective wind direction
+    pub wsz: Angle,
+    /// Head fire intensity
+    pub fi: LinearPowerDensity,
+}
+
+impl From<fbp_system::FbpValues> for FbpValues {
+    fn from(values: fbp_system::FbpValues) -> Self {
+        let load = ArealMassDensity::new::<kilogram_per_square_meter>;
+
+        Self {
+            cfb: Ratio::new::<ratio>(values.cfb),
+            raz: Angle::new::<radian>(values.raz),
+            ros: Velocity::new::<meter_per_minute>(values.ros),
+            fros: Velocity::new::<meter_per_minute>(values.fros),
+            bros: Velocity::new::<meter_per_minute>(values.bros),
+            fmc: Ratio::new::<percent>(values.fmc),
+            sfc: load(values.sfc),
+            cfc: load(values.cfc),
+            tfc: load(values.tfc),
+            cfl: load(values.cfl),
+            cbh: Length::new::<meter>(values.cbh),
+            wsv: Velocity::new::<kilometer_per_hour>(values.wsv),
+            wsz: Angle::new::<radian>(values.wsz),
+            fi: LinearPowerDensity::new::<kilowatt_per_meter>(values.fi),
+        }
+    }
+}
+
+impl From<FbpValues> for fbp_system::FbpValues {
+    fn from(values: FbpValues) -> Self {
+        let load = |load: ArealMassDensity| load.get::<kilogram_per_square_meter>();
+
+        Self {
+            cfb: values.cfb.get::<ratio>(),
+            raz: values.raz.get::<radian>(),
+            ros: values.ros.get::<meter_per_minute>(),
+            fros: values.fros.get::<meter_per_minute>(),
+            bros: values.bros.get::<meter_per_minute>(),
+            fmc: values.fmc.get::<percent>(),
+            sfc: load(values.sfc),
+            cfc: load(values.cfc),
+            tfc: load(values.tfc),
+            cfl: load(values.cfl),
+            cbh: values.cbh.get::<meter>(),
+            wsv: values.wsv.get::<kilometer_per_hour>(),
+            wsz: values.wsz.get::<radian>(),
+            fi: values.fi.get::<kilowatt_per_meter>(),
+        }
+    }
+}
+
+/// Calculate all FBP values from typed weather and FWI values, see
+/// [`fbp_system::calculate_fbp()`]
+pub fn calculate_fbp(
+    fuel_type: FbpFuelType,
+    fwi: &HourlyFwiValues,
+    weather: &Weather,
+    options: FbpOptions,
+) -> FbpValues {
+    fbp_system::calculate_fbp(fuel_type, fwi, &weather.clone().into(), options.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use chrono::TimeZone;
+    use uom::si::velocity::meter_per_second;
+
+    use super::*;
+    use crate::fwi_system::{calculate_hourly, StartingFwiValues};
+
+    fn weather() -> weather::Weather {
+        weather::Weather {
+            time: Utc.with_ymd_and_hms(2023, 7, 8, 20, 0, 0).unwrap(),
+            location: Point::new(-120.34, 50.69),
+            temp: 28.,
+            rh: 12.,
+            ws: 16.,
+            wd: 225.,
+            precip: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_custom_units() {
+        assert_relative_eq!(
+            Velocity::new::<meter_per_minute>(60.).get::<meter_per_second>(),
+            1.
+        );
+        assert_relative_eq!(
+            LinearPowerDensity::new::<kilowatt_per_meter>(2.5)
+                .get::<uom::si::linear_power_density::watt_per_meter>(),
+            2500.
+        );
+    }
+
+    #[test]
+    fn test_weather_round_trip() {
+        let typed = Weather::from(weather());
+        assert_relative_eq!(typed.wd.get::<radian>(), 225f64.to_radians());
+
+        let untyped = weather::Weather::from(typed);
+        assert_relative_eq!(untyped.temp, 28., max_relative = 1e-12);
+        assert_relative_eq!(untyped.rh, 12.);
+        assert_relative_eq!(untyped.ws, 16.);
+        assert_relative_eq!(untyped.wd, 225.);
+        assert_relative_eq!(untyped.precip, 0.5);
+    }
+
+    #[test]
+    fn test_calculate_fbp() {
+        let fwi = calculate_hourly(
+            &weather(),
+            Some(&StartingFwiValues {
+                ffmc: 90.,
+                dmc: 60.,
+                dc: 300.,
+            }),
+        );
+        let options = fbp_system::FbpOptions {
+            slope: 30.,
+            aspect: 90.,
+            crown_base_height: 7.,
+            ..Default::default()
+        };
+
+        let expected =
+            fbp_system::calculate_fbp(FbpFuelType::C3, &fwi, &weather(), options.clone());
+        let actual = calculate_fbp(FbpFuelType::C3, &fwi, &weather().into(), options.into());
+
+        assert_relative_eq!(
+            actual.ros.get::<meter_per_minute>(),
+            expected.ros,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            actual.fi.get::<kilowatt_per_meter>(),
+            expected.fi,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            actual.cfb.get::<ratio>(),
+            expected.cfb,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            actual.raz.get::<radian>(),
+            expected.raz,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            actual.raz.get::<degree>(),
+            expected.raz.to_degrees(),
+            max_relative = 1e-12
+        );
+
+        let untyped = fbp_system::FbpValues::from(actual);
+        assert_relative_eq!(untyped.tfc, expected.tfc, max_relative = 1e-12);
+        assert_relative_eq!(untyped.wsv, expected.wsv, max_relative = 1e-12);
+    }
+}
@@ -0,0 +1,195 @@
+//! JavaScript bindings for running the FWI and FBP systems in the browser
+//!
+//! Values are passed to and from JavaScript as plain objects, with the same field names as the
+//! Rust types. Build a package with `wasm-bindgen` (from `wasm-bindgen-cli`):
+//!
+//! ```text
+//! cargo rustc --release --lib --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/cffdrs.wasm
+//! ```
+//!
+//! ```js
+//! import init, { calculateHourly, calculateFbp } from "./pkg/cffdrs.js";
+//!
+//! await init();
+//! const weather = {
+//!   time: "2023-07-08T21:00:00Z",
+//!   lat: 50.69, long: -120.34,
+//!   temp: 28, rh: 12, ws: 16, wd: 95, precip: 0,
+//! };
+//! const fwi = calculateHourly(weather, { ffmc: 88, dmc: 40, dc: 300 });
+//! const fbp = calculateFbp("C-2", fwi, weather, { slope: 30 });
+//! ```
+//!
+//! Requires the `wasm` feature.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    fbp_system::{
+        self, ros::rate_of_spread_at_theta as ros_at_theta, FbpFuelType, FbpOptions, LeafOnSeason,
+    },
+    fwi_system::{self, HourlyFwiValues, StartingFwiValues},
+    weather::Weather,
+};
+
+/// A weather record, as a JavaScript object
+#[derive(Deserialize, Debug)]
+struct JsWeather {
+    /// UTC time the weather was sampled, as an RFC 3339 string (e.g. `"2023-07-08T21:00:00Z"`)
+    time: String,
+    /// Latitude (degrees)
+    lat: f64,
+    /// Longitude (degrees)
+    long: f64,
+    /// Temperature (Celcius)
+    temp: f64,
+    /// Relative humidity (%)
+    rh: f64,
+    /// Wind speed (at 10m height, km/h)
+    ws: f64,
+    /// Wind direction (degrees, 0 = wind from north, 90 = from east, etc.)
+    wd: f64,
+    /// Precipitation (mm)
+    precip: f64,
+}
+
+impl TryFrom<JsWeather> for Weather {
+    type Error = JsError;
+
+    fn try_from(weather: JsWeather) -> Result<Self, Self::Error> {
+        let time = DateTime::parse_from_rfc3339(&weather.time)
+            .map_err(|err| JsError::new(&format!("invalid time {:?}: {err}", weather.time)))?;
+
+        Ok(Weather {
+            time: time.with_timezone(&Utc),
+            location: geo::Point::new(weather.long, weather.lat),
+            temp: weather.temp,
+            rh: weather.rh,
+            ws: weather.ws,
+            wd: weather.wd,
+            precip: weather.precip,
+        })
+    }
+}
+
+/// FBP options, as a JavaScript object. Missing fields use the [FbpOptions] defaults
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct JsFbpOptions {
+    elevation: Option<f64>,
+    date_of_minimum_fmc: Option<i32>,
+    slope: Option<f64>,
+    aspect: Option<f64>,
+    /// Degree of curing for grass fuel types (%)
+    curing: Option<f64>,
+    grass_fuel_load: Option<f64>,
+    percent_conifer: Option<f64>,
+    percent_dead_balsam: Option<f64>,
+    leaf_on_season: Option<LeafOnSeason>,
+    crown_base_height: Option<f64>,
+}
+
+impl From<JsFbpOptions> for FbpOptions {
+    fn from(options: JsFbpOptions) -> Self {
+        let defaults = FbpOptions::default();
+
+        FbpOptions {
+            elevation: options.elevation,
+            date_of_minimum_fmc: options.date_of_minimum_fmc,
+            slope: options.slope.unwrap_or(defaults.slope),
+            aspect: options.aspect.unwrap_or(defaults.aspect),
+            curing: options.curing.unwrap_or(defaults.curing),
+            grass_fuel_load: options.grass_fuel_load.unwrap_or(defaults.grass_fuel_load),
+            percent_conifer: options.percent_conifer.unwrap_or(defaults.percent_conifer),
+            percent_dead_balsam: options
+                .percent_dead_balsam
+                .unwrap_or(defaults.percent_dead_balsam),
+            leaf_on_season: options.leaf_on_season,
+            crown_base_height: options
+                .crown_base_height
+                .unwrap_or(defaults.crown_base_height),
+        }
+    }
+}
+
+/// Deserialize a JavaScript object, treating `undefined` and `null` as `None`
+fn from_js_optional<T: for<'de> Deserialize<'de>>(value: JsValue) -> Result<Option<T>, JsError> {
+    if value.is_undefined() || value.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_wasm_bindgen::from_value(value)?))
+    }
+}
+
+/// Parse a fuel type code such as `"C-2"` or `"o1a"`
+fn parse_fuel_type(code: &str) -> Result<FbpFuelType, JsError> {
+    Ok(code.parse()?)
+}
+
+/// Calculate all values for the FWI system from hourly weather
+///
+/// `previous` is an object with `ffmc`, `dmc` and `dc` fields, and may be omitted
+#[wasm_bindgen(js_name = calculateHourly)]
+pub fn calculate_hourly(weather: JsValue, previous: JsValue) -> Result<JsValue, JsError> {
+    let weather = Weather::try_from(serde_wasm_bindgen::from_value::<JsWeather>(weather)?)?;
+    let previous = from_js_optional::<StartingFwiValues>(previous)?;
+
+    Ok(serde_wasm_bindgen::to_value(
+        &fwi_system::calculate_hourly(&weather, previous.as_ref()),
+    )?)
+}
+
+/// Calculate all FBP values from weather and FWI values
+///
+/// `options` may be omitted, and missing options use the defaults
+#[wasm_bindgen(js_name = calculateFbp)]
+pub fn calculate_fbp(
+    fuel_type: &str,
+    fwi: JsValue,
+    weather: JsValue,
+    options: JsValue,
+) -> Result<JsValue, JsError> {
+    let fuel_type = parse_fuel_type(fuel_type)?;
+    let fwi = serde_wasm_bindgen::from_value::<HourlyFwiValues>(fwi)?;
+    let weather = Weather::try_from(serde_wasm_bindgen::from_value::<JsWeather>(weather)?)?;
+    let options = from_js_optional::<JsFbpOptions>(options)?.unwrap_or_default();
+
+    Ok(serde_wasm_bindgen::to_value(&fbp_system::calculate_fbp(
+        fuel_type,
+        &fwi,
+        &weather,
+        options.into(),
+    ))?)
+}
+
+/// Rate of spread (m/min) on the perimeter of the fire ellipse, at `theta` radians from the
+/// direction of spread. See [rate_of_spread_at_theta()][crate::fbp_system::ros::rate_of_spread_at_theta()]
+#[wasm_bindgen(js_name = rateOfSpreadAtTheta)]
+pub fn rate_of_spread_at_theta(ros: f64, fros: f64, bros: f64, theta: f64) -> f64 {
+    ros_at_theta(ros, fros, bros, theta)
+}
+
+/// Length to breadth ratio of the fire ellipse. See
+/// [length_to_breadth()][crate::fbp_system::length_to_breadth()]
+#[wasm_bindgen(js_name = lengthToBreadth)]
+pub fn length_to_breadth(fuel_type: &str, wsv: f64) -> Result<f64, JsError> {
+    Ok(fbp_system::length_to_breadth(
+        parse_fuel_type(fuel_type)?,
+        wsv,
+    ))
+}
+
+/// Head fire spread distance (m) after `time` minutes, accounting for acceleration. See
+/// [distance_at_time()][crate::fbp_system::distance_at_time()]
+#[wasm_bindgen(js_name = distanceAtTime)]
+pub fn distance_at_time(fuel_type: &str, ros: f64, time: f64, cfb: f64) -> Result<f64, JsError> {
+    Ok(fbp_system::distance_at_time(
+        parse_fuel_type(fuel_type)?,
+        ros,
+        time,
+        cfb,
+    ))
+}
@@ -1,9 +1,19 @@
 //! Weather types and functions
 
+use crate::float::Float;
+
+#[cfg(feature = "std")]
 use chrono::{DateTime, Utc};
+#[cfg(feature = "std")]
 use geo::Point;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::fbp_system::FbpFuelType;
+
 /// A weather record representing the weather for a single point-in-time, at a location
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct Weather {
     /// UTC time when the weather was sampled
@@ -14,10 +24,204 @@ pub struct Weather {
     pub temp: f64,
     /// Relative humidity (%)
     pub rh: f64,
-    /// Wind speed (at 10m height, km/h)
+    /// Wind speed (at 10m height, km/h). Winds measured at other heights can be converted with
+    /// [Weather::with_open_wind()]
     pub ws: f64,
     /// Wind direction (degrees, 0 = wind from north, 90 = from east, etc.)
     pub wd: f64,
     /// Precipitation (mm)
     pub precip: f64,
 }
+
+/// Height of the standard (10m open) wind used by the FWI and FBP systems (m)
+pub const STANDARD_WIND_HEIGHT: f64 = 10.;
+
+/// Roughness length of open, flat terrain with low vegetation (m)
+pub const OPEN_ROUGHNESS_LENGTH: f64 = 0.03;
+
+/// Convert a wind speed between heights using a logarithmic wind profile
+///
+/// * `ws` - Wind speed at `from_height` (any unit)
+/// * `from_height` - Height the wind speed was measured at (m)
+/// * `to_height` - Height to convert the wind speed to (m)
+/// * `roughness_length` - Surface roughness length (m), e.g. [OPEN_ROUGHNESS_LENGTH]
+///
+/// Returns wind speed at `to_height`, in the same unit as `ws`
+///
+/// ```
+/// # use cffdrs::weather::{wind_speed_at_height, OPEN_ROUGHNESS_LENGTH};
+/// // 20ft (6.1m) RAWS wind to 10m
+/// let ws = wind_speed_at_height(15., 6.1, 10., OPEN_ROUGHNESS_LENGTH);
+/// assert_eq!(ws, 16.39504397580586);
+/// ```
+pub fn wind_speed_at_height(
+    ws: f64,
+    from_height: f64,
+    to_height: f64,
+    roughness_length: f64,
+) -> f64 {
+    ws * Float::ln(to_height / roughness_length) / Float::ln(from_height / roughness_length)
+}
+
+/// Wind reduction from the 20ft (6.1m) open wind to the midflame wind, based on sheltering of the
+/// fuel by the overstory
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum WindReduction {
+    /// Fuels exposed to the wind, with no overstory
+    Unsheltered,
+    /// Fuels partially sheltered by an overstory
+    PartiallySheltered,
+    /// Fuels fully sheltered by an open overstory
+    FullyShelteredOpen,
+    /// Fuels fully sheltered by a dense overstory
+    FullyShelteredClosed,
+    /// A known wind adjustment factor (0-1)
+    Factor(f64),
+}
+
+impl WindReduction {
+    /// Wind adjustment factor (midflame wind / 20ft open wind)
+    ///
+    /// Values for the sheltering classes are from Rothermel (1983)
+    pub fn factor(&self) -> f64 {
+        match self {
+            WindReduction::Unsheltered => 0.4,
+            WindReduction::PartiallySheltered => 0.3,
+            WindReduction::FullyShelteredOpen => 0.2,
+            WindReduction::FullyShelteredClosed => 0.1,
+            WindReduction::Factor(factor) => *factor,
+        }
+    }
+
+    /// Typical sheltering of an FBP fuel type
+    ///
+    /// Fuel types are assigned to the Rothermel (1983) sheltering classes from the stand
+    /// descriptions in ST-X-3 (Forestry Canada Fire Danger Group 1992): grass, slash and non-fuel
+    /// are unsheltered, leafless aspen (D1) is partially sheltered, the open C1 and C7 stands are
+    /// fully sheltered by an open overstory, and the other stands are fully sheltered by a dense
+    /// overstory. Use [WindReduction::Factor] when the local stand is known to differ.
+    ///
+    /// Rothermel, R.C. 1983. How to predict the spread and intensity of forest and range fires.
+    /// USDA Forest Service, Intermountain Forest and Range Experiment Station, General Technical
+    /// Report INT-143. 161 p.
+    ///
+    /// ```
+    /// # use cffdrs::{fbp_system::FbpFuelType, weather::WindReduction};
+    /// assert_eq!(
+    ///     WindReduction::for_fuel_type(FbpFuelType::C7),
+    ///     WindReduction::FullyShelteredOpen
+    /// );
+    /// assert_eq!(WindReduction::for_fuel_type(FbpFuelType::O1a).factor(), 0.4);
+    /// ```
+    pub fn for_fuel_type(fuel_type: FbpFuelType) -> Self {
+        match fuel_type {
+            FbpFuelType::O1a
+            | FbpFuelType::O1b
+            | FbpFuelType::S1
+            | FbpFuelType::S2
+            | FbpFuelType::S3
+            | FbpFuelType::NonFuel => WindReduction::Unsheltered,
+            FbpFuelType::D1 => WindReduction::PartiallySheltered,
+            FbpFuelType::C1 | FbpFuelType::C7 => WindReduction::FullyShelteredOpen,
+            FbpFuelType::C2
+            | FbpFuelType::C3
+            | FbpFuelType::C4
+            | FbpFuelType::C5
+            | FbpFuelType::C6
+            | FbpFuelType::D2
+            | FbpFuelType::M1
+            | FbpFuelType::M2
+            | FbpFuelType::M3
+            | FbpFuelType::M4 => WindReduction::FullyShelteredClosed,
+        }
+    }
+}
+
+/// Height and exposure a wind speed was measured or forecast at
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum WindMeasurement {
+    /// Standard 10m open wind
+    Open10m,
+    /// Open wind at a height (m), e.g. 6.1m (20ft) for RAWS stations, or the height of a gridded
+    /// model level
+    Open {
+        /// Height of the wind (m)
+        height: f64,
+        /// Surface roughness length (m), e.g. [OPEN_ROUGHNESS_LENGTH]
+        roughness_length: f64,
+    },
+    /// Midflame wind, reduced from the 20ft open wind by sheltering
+    Midflame(WindReduction),
+}
+
+/// Height of the 20ft open wind (m)
+pub(crate) const TWENTY_FOOT_HEIGHT: f64 = 6.096;
+
+/// Convert a wind speed to the standard 10m open wind speed expected by the FWI and FBP systems
+///
+/// * `ws` - Wind speed (any unit)
+/// * `measurement` - How `ws` was measured
+///
+/// Returns 10m open wind speed, in the same unit as `ws`
+///
+/// ```
+/// # use cffdrs::{
+/// #     fbp_system::FbpFuelType,
+/// #     weather::{open_wind_speed, WindMeasurement, WindReduction},
+/// # };
+/// assert_eq!(open_wind_speed(20., WindMeasurement::Open10m), 20.);
+///
+/// let ws = open_wind_speed(4., WindMeasurement::Midflame(WindReduction::PartiallySheltered));
+/// assert_eq!(ws, 14.575171275272536);
+///
+/// // Midflame wind in a C7 stand
+/// let reduction = WindReduction::for_fuel_type(FbpFuelType::C7);
+/// assert_eq!(open_wind_speed(3., WindMeasurement::Midflame(reduction)), 16.397067684681603);
+/// ```
+pub fn open_wind_speed(ws: f64, measurement: WindMeasurement) -> f64 {
+    match measurement {
+        WindMeasurement::Open10m => ws,
+        WindMeasurement::Open {
+            height,
+            roughness_length,
+        } => wind_speed_at_height(ws, height, STANDARD_WIND_HEIGHT, roughness_length),
+        WindMeasurement::Midflame(reduction) => wind_speed_at_height(
+            ws / reduction.factor(),
+            TWENTY_FOOT_HEIGHT,
+            STANDARD_WIND_HEIGHT,
+            OPEN_ROUGHNESS_LENGTH,
+        ),
+    }
+}
+
+#[cfg(feature = "std")]
+impl Weather {
+    /// Convert the wind speed of this weather record to the standard 10m open wind speed
+    ///
+    /// * `measurement` - How [`Weather::ws`] was measured
+    ///
+    /// ```
+    /// # use cffdrs::weather::{Weather, WindMeasurement};
+    /// # use chrono::prelude::*;
+    /// let weather = Weather {
+    ///     time: Utc.with_ymd_and_hms(2023, 7, 8, 9, 10, 11).unwrap(),
+    ///     location: geo::Point::new(-120.34, 50.69),
+    ///     temp: 28.,
+    ///     rh: 12.,
+    ///     ws: 15.,
+    ///     wd: 95.,
+    ///     precip: 0.,
+    /// }
+    /// .with_open_wind(WindMeasurement::Open { height: 6.1, roughness_length: 0.03 });
+    ///
+    /// assert_eq!(weather.ws, 16.39504397580586);
+    /// ```
+    pub fn with_open_wind(self, measurement: WindMeasurement) -> Self {
+        Self {
+            ws: open_wind_speed(self.ws, measurement),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,13 @@
+#!/bin/sh
+# Build the static library with the `ffi` feature, then compile and run the C test against it
+set -eu
+
+root="$(cd "$(dirname "$0")/../.." && pwd)"
+target="${CARGO_TARGET_DIR:-$root/target}"
+out="$target/ffi-test"
+
+cargo rustc --manifest-path "$root/Cargo.toml" --lib --features ffi --crate-type staticlib
+mkdir -p "$out"
+${CC:-cc} -std=c99 -Wall -Wextra -Werror -I "$root/include" "$root/tests/ffi/test_ffi.c" \
+    "$target/debug/libcffdrs.a" -lpthread -ldl -lm -o "$out/test_ffi"
+"$out/test_ffi"
@@ -0,0 +1,80 @@
+/* Exercises the C ABI. Build and run with tests/ffi/run.sh */
+
+#include <math.h>
+#include <stdio.h>
+
+#include "cffdrs.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                            \
+  do {                                                                         \
+    if (!(cond)) {                                                             \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+      failures++;                                                              \
+    }                                                                          \
+  } while (0)
+
+static int close_to(double a, double b) { return fabs(a - b) < 1e-9; }
+
+int main(void) {
+  /* 2023-07-08T09:10:11Z */
+  CffdrsWeather weather = {
+      .timestamp = 1688807411,
+      .lat = 50.69,
+      .long_ = -120.34,
+      .temp = 28.,
+      .rh = 12.,
+      .ws = 16.,
+      .wd = 95.,
+      .precip = 0.,
+  };
+
+  double isi = cffdrs_initial_spread_index(90., 20.);
+  double bui = cffdrs_buildup_index(40., 300.);
+  CHECK(isi > 0.);
+  CHECK(bui > 40.);
+  CHECK(cffdrs_fire_weather_index(isi, bui) > 0.);
+  CHECK(cffdrs_drought_code(300., 25., 0., 50.69, 7, true) > 300.);
+  CHECK(cffdrs_duff_moisture_code(40., 25., 30., 0., 50.69, 7, true) > 40.);
+  CHECK(cffdrs_hourly_fine_fuel_moisture_code(28., 12., 16., 0., 85., 1.) > 85.);
+
+  CffdrsStartingFwiValues previous = {.dc = 300., .dmc = 40., .ffmc = 88.};
+  CffdrsHourlyFwiValues fwi;
+  CHECK(cffdrs_calculate_hourly(&weather, &previous, &fwi) == CFFDRS_STATUS_OK);
+  CHECK(close_to(fwi.isi, cffdrs_initial_spread_index(fwi.ffmc, weather.ws)));
+  CHECK(close_to(fwi.bui, cffdrs_buildup_index(fwi.dmc, fwi.dc)));
+  CHECK(close_to(fwi.fwi, cffdrs_fire_weather_index(fwi.isi, fwi.bui)));
+
+  CffdrsHourlyFwiValues fwi_without_previous;
+  CHECK(cffdrs_calculate_hourly(&weather, NULL, &fwi_without_previous) == CFFDRS_STATUS_OK);
+
+  CffdrsFuelType fuel_type;
+  CHECK(cffdrs_parse_fuel_type("C-2", &fuel_type) == CFFDRS_STATUS_OK);
+  CHECK(fuel_type == CFFDRS_FUEL_TYPE_C2);
+  CHECK(cffdrs_parse_fuel_type("X-9", &fuel_type) == CFFDRS_STATUS_INVALID_FUEL_TYPE);
+
+  CffdrsFbpOptions options = cffdrs_fbp_options_default();
+  CHECK(isnan(options.elevation));
+
+  CffdrsFbpValues flat, steep;
+  CHECK(cffdrs_calculate_fbp(CFFDRS_FUEL_TYPE_C2, &fwi, &weather, NULL, &flat) ==
+        CFFDRS_STATUS_OK);
+  options.slope = 40.;
+  CHECK(cffdrs_calculate_fbp(CFFDRS_FUEL_TYPE_C2, &fwi, &weather, &options, &steep) ==
+        CFFDRS_STATUS_OK);
+  CHECK(flat.ros > 0.);
+  CHECK(flat.fi > 0.);
+  CHECK(flat.ros > flat.bros);
+  CHECK(steep.wsv > flat.wsv);
+
+  CHECK(cffdrs_calculate_fbp(100, &fwi, &weather, NULL, &flat) ==
+        CFFDRS_STATUS_INVALID_FUEL_TYPE);
+  CHECK(cffdrs_calculate_fbp(CFFDRS_FUEL_TYPE_C2, NULL, &weather, NULL, &flat) ==
+        CFFDRS_STATUS_NULL_POINTER);
+
+  if (failures == 0) {
+    printf("ffi: all checks passed\n");
+  }
+  return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,83 @@
+//! Headless tests for the JavaScript bindings. Run under node with
+//!
+//! ```text
+//! cargo test --target wasm32-unknown-unknown --features wasm --test wasm
+//! ```
+//!
+//! which needs `wasm-bindgen-test-runner` from `wasm-bindgen-cli` on the `PATH`, and
+//! `CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner`.
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use cffdrs::wasm::{
+    calculate_fbp, calculate_hourly, distance_at_time, length_to_breadth, rate_of_spread_at_theta,
+};
+use js_sys::{Reflect, JSON};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn object(json: &str) -> JsValue {
+    JSON::parse(json).unwrap()
+}
+
+fn field(value: &JsValue, name: &str) -> f64 {
+    Reflect::get(value, &JsValue::from_str(name))
+        .unwrap()
+        .as_f64()
+        .unwrap_or_else(|| panic!("{name} is not a number"))
+}
+
+fn weather() -> JsValue {
+    object(
+        r#"{
+            "time": "2023-07-08T21:00:00Z",
+            "lat": 50.69,
+            "long": -120.34,
+            "temp": 28,
+            "rh": 12,
+            "ws": 16,
+            "wd": 95,
+            "precip": 0
+        }"#,
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_hourly() {
+    let fwi = calculate_hourly(weather(), object(r#"{"ffmc": 88, "dmc": 40, "dc": 300}"#)).unwrap();
+    assert!(field(&fwi, "ffmc") > 88.);
+    assert!(field(&fwi, "fwi") > 0.);
+
+    let without_previous = calculate_hourly(weather(), JsValue::UNDEFINED).unwrap();
+    assert!(field(&without_previous, "isi") > 0.);
+
+    assert!(calculate_hourly(object(r#"{"time": "yesterday"}"#), JsValue::NULL).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_fbp() {
+    let fwi = calculate_hourly(weather(), object(r#"{"ffmc": 88, "dmc": 40, "dc": 300}"#)).unwrap();
+
+    let flat = calculate_fbp("C-2", fwi.clone(), weather(), JsValue::UNDEFINED).unwrap();
+    let steep = calculate_fbp("c2", fwi.clone(), weather(), object(r#"{"slope": 40}"#)).unwrap();
+    assert!(field(&flat, "ros") > 0.);
+    assert!(field(&steep, "wsv") > field(&flat, "wsv"));
+
+    assert!(calculate_fbp("C-8", fwi, weather(), JsValue::UNDEFINED).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_ellipse() {
+    let ros = 34.02;
+    let fros = 393.66;
+    let bros = 590.49;
+    assert_eq!(
+        rate_of_spread_at_theta(ros, fros, bros, 90_f64.to_radians()),
+        178.72808229830116
+    );
+
+    assert!(length_to_breadth("C-2", 20.).unwrap() > 1.);
+    assert!(length_to_breadth("X", 20.).is_err());
+
+    let distance = distance_at_time("C-2", 10., 60., 0.5).unwrap();
+    assert!(distance > 0. && distance < 600.);
+}